@@ -0,0 +1,192 @@
+use std::error::Error;
+use std::io::{self, Write};
+use std::iter;
+use std::str::FromStr;
+
+use config::Config;
+use glib::ExitCode;
+use gtk::glib;
+use gtk::prelude::ListModelExtManual;
+use log::error;
+
+use crate::app::APP_NAME;
+use crate::daemon;
+use crate::data::output::Output;
+use crate::data::outputs::Outputs;
+use crate::profile::Profiles;
+use crate::query::Query;
+use crate::x11::randr::Randr;
+use crate::xrandr::Args;
+
+pub const USAGE: &str = "\
+Usage: rrandr [OPTION]
+
+Without options the graphical interface is shown.
+
+Options:
+  --profile NAME  Apply the profile NAME
+  --daemon        Restore the last layout of the connected monitors on change
+  --query [--json]
+                  Print the current screen configuration
+  --brightness OUTPUT [+|-]PERCENT[%]
+                  Set or change the backlight brightness of OUTPUT
+  --edid OUTPUT   Write the raw EDID of the monitor connected to OUTPUT
+  --output NAME [--auto] [--off] [--mode WxH] [--rate RATE] [--pos XxY]
+                [--rotate normal|left|right|inverted] [--reflect normal|x|y|xy]
+                [--scale S|SXxSY] [--scale-from WxH] [--transform A,B,...,I]
+                [--filter nearest|bilinear] [--gamma R:G:B] [--brightness B]
+                [--panning WxH[+X+Y[/TRACK[/BORDER]]]] [--primary]
+                [--left-of|--right-of|--above|--below|--same-as OUTPUT]
+                [--output NAME ...] [--noprimary]
+                  Change outputs like xrandr does
+  -h, --help      Show this help";
+
+pub enum Command {
+    Gui,
+    Help,
+    Profile(String),
+    Daemon,
+    Query { json: bool },
+    Brightness { output: String, change: Brightness },
+    Edid(String),
+    Set(Args),
+}
+
+pub enum Brightness {
+    Set(f64),
+    Change(f64),
+}
+
+impl FromStr for Brightness {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid brightness: {s}");
+        let value = s.strip_suffix('%').unwrap_or(s);
+        let percent = value.parse::<f64>().map_err(|_| invalid())?;
+        if !percent.is_finite() {
+            return Err(invalid());
+        }
+        Ok(if value.starts_with(['+', '-']) { Self::Change(percent) } else { Self::Set(percent) })
+    }
+}
+
+pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let Some(arg) = args.next() else {
+        return Ok(Command::Gui);
+    };
+    let cmd = match arg.as_str() {
+        "-h" | "--help" => Command::Help,
+        "--profile" => Command::Profile(args.next().ok_or("Missing profile name")?),
+        "--daemon" => Command::Daemon,
+        "--brightness" => Command::Brightness {
+            output: args.next().ok_or("Missing output name")?,
+            change: args.next().ok_or("Missing brightness")?.parse()?,
+        },
+        "--edid" => Command::Edid(args.next().ok_or("Missing output name")?),
+        "--output" | "--noprimary" | "--dpi" => {
+            return Args::parse(iter::once(arg).chain(args)).map(Command::Set);
+        }
+        "--query" => match args.next().as_deref() {
+            None => return Ok(Command::Query { json: false }),
+            Some("--json") => Command::Query { json: true },
+            Some(arg) => return Err(format!("Unexpected argument: {arg}")),
+        },
+        _ => return Err(format!("Unknown option: {arg}")),
+    };
+    if let Some(arg) = args.next() {
+        return Err(format!("Unexpected argument: {arg}"));
+    }
+    Ok(cmd)
+}
+
+pub fn run(cmd: Command) -> ExitCode {
+    let result = match cmd {
+        Command::Gui => unreachable!("GUI is run by the application"),
+        Command::Help => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        Command::Profile(name) => {
+            let profiles = Profiles::named();
+            match (profiles.get(&name), profiles.error()) {
+                (Some(profile), _) => apply(|outputs| profile.apply_to(outputs)),
+                (None, Some(e)) => Err(e.into()),
+                (None, None) => Err(format!("No profile named: {name}").into()),
+            }
+        }
+        Command::Daemon => daemon::run(),
+        Command::Query { json } => query(json),
+        Command::Brightness { output, change } => brightness(&output, &change),
+        Command::Edid(output) => edid(&output),
+        Command::Set(args) => apply(|outputs| args.apply_to(outputs)),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            error!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn apply(
+    change: impl FnOnce(&Outputs) -> Result<(), Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+    let randr = Randr::new()?;
+    let outputs = randr.output_model();
+    change(&outputs)?;
+    let snapshot = randr.snapshot();
+    match randr.apply(&outputs) {
+        Ok(()) => {
+            let config = Config::new(APP_NAME, None);
+            if config.xft.update {
+                randr.update_xft(&outputs, config.xft.dpi());
+            }
+            Ok(())
+        }
+        Err(e) => {
+            randr.revert(snapshot);
+            Err(format!("{e}. Changes have been reverted.").into())
+        }
+    }
+}
+
+fn query(json: bool) -> Result<(), Box<dyn Error>> {
+    let query = Query::new(&Randr::new()?);
+    if json {
+        println!("{}", query.to_json()?);
+    } else {
+        print!("{query}");
+    }
+    Ok(())
+}
+
+fn brightness(name: &str, change: &Brightness) -> Result<(), Box<dyn Error>> {
+    let randr = Randr::new()?;
+    let output = randr
+        .output_model()
+        .iter::<Output>()
+        .map(Result::unwrap)
+        .find(|o| o.name() == name)
+        .ok_or(format!("Output {name} not found or not connected"))?;
+    let backlight =
+        randr.backlight(output.id()).ok_or(format!("Output {name} has no backlight"))?;
+    let percent = match *change {
+        Brightness::Set(percent) => percent,
+        Brightness::Change(percent) => backlight.percent() + percent,
+    };
+    randr.set_backlight(output.id(), &backlight, percent)
+}
+
+fn edid(name: &str) -> Result<(), Box<dyn Error>> {
+    let randr = Randr::new()?;
+    let output = randr
+        .output_model()
+        .iter::<Output>()
+        .map(Result::unwrap)
+        .find(|o| o.name() == name)
+        .ok_or(format!("Output {name} not found or not connected"))?;
+    let data = randr.edid_data(output.id()).ok_or(format!("Output {name} has no EDID"))?;
+    Ok(io::stdout().lock().write_all(&data)?)
+}
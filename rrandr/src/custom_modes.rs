@@ -0,0 +1,100 @@
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use glib::user_config_dir;
+use gtk::glib;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::app::APP_NAME;
+
+const CUSTOM_MODES_FILE: &str = "custom_modes.toml";
+
+/// Names of the modes created by the user, so they can be told apart from
+/// the modes of the driver and deleted again in later runs.
+#[derive(Default, Deserialize, Serialize)]
+pub struct CustomModes {
+    #[serde(default)]
+    names: BTreeSet<String>,
+    /// Why the file couldn't be loaded, which keeps it from being overwritten.
+    #[serde(skip)]
+    error: Option<String>,
+}
+
+impl CustomModes {
+    pub fn load() -> Self {
+        let mut modes = Self::default();
+        let path = Self::path();
+        if !path.exists() {
+            info!("No custom modes found in {CUSTOM_MODES_FILE}");
+            return modes;
+        }
+        match fs::read_to_string(&path) {
+            Ok(m) => match Self::parse(&m) {
+                Ok(m) => modes.names = m.names,
+                Err(e) => modes.error = Some(format!("Failed to parse {}\n{e}", path.display())),
+            },
+            Err(e) => modes.error = Some(format!("Failed to read {}: {e}", path.display())),
+        }
+        if let Some(e) = &modes.error {
+            warn!("{e}");
+        }
+        modes
+    }
+
+    fn parse(modes: &str) -> Result<Self, toml::de::Error> { toml::from_str(modes) }
+
+    /// Saves the mode names unless loading them failed, in which case the
+    /// file is kept until it is fixed or removed.
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        if let Some(e) = &self.error {
+            return Err(
+                format!("{e}\nFix or remove the file to remember custom modes again.").into()
+            );
+        }
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Whether the mode named `name` was created by the user.
+    pub fn contains(&self, name: &str) -> bool { self.names.contains(name) }
+
+    pub fn insert(&mut self, name: &str) { self.names.insert(name.to_owned()); }
+
+    pub fn remove(&mut self, name: &str) { self.names.remove(name); }
+
+    fn path() -> PathBuf { user_config_dir().join(APP_NAME).join(CUSTOM_MODES_FILE) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_by_created_names() {
+        let mut modes = CustomModes::parse("names = [\"1920x1080_60.00\"]").unwrap();
+        assert!(modes.contains("1920x1080_60.00"));
+        // Named like the output of cvt, but not created by the user
+        assert!(!modes.contains("2560x1440_60.00"));
+        // Created without an underscore
+        modes.insert("ultrawide");
+        assert!(modes.contains("ultrawide"));
+        modes.remove("1920x1080_60.00");
+        assert!(!modes.contains("1920x1080_60.00"));
+    }
+
+    #[test]
+    fn remember_created_names() {
+        let mut modes = CustomModes::default();
+        modes.insert("1280x720_59.94");
+        let modes = CustomModes::parse(&toml::to_string(&modes).unwrap()).unwrap();
+        assert!(modes.contains("1280x720_59.94"));
+        assert!(CustomModes::parse("").unwrap().names.is_empty());
+    }
+}
@@ -0,0 +1,242 @@
+use std::cell::RefCell;
+use std::error::Error;
+use std::time::Duration;
+
+use config::data::enums::Fallback;
+use config::Config;
+use glib::{spawn_future_local, timeout_future, MainLoop};
+use gtk::glib;
+use gtk::prelude::ListModelExtManual;
+use log::{debug, error, info, warn};
+use x11rb::protocol::randr::Output as OutputId;
+use x11rb::protocol::Event;
+
+use crate::app::APP_NAME;
+use crate::data::output::Output;
+use crate::data::outputs::Outputs;
+use crate::hook;
+use crate::profile::{self, Profile, Profiles};
+use crate::x11::randr::{self, Randr};
+
+struct Daemon {
+    config: Config,
+    randr: RefCell<Randr>,
+    fingerprint: RefCell<String>,
+}
+
+/// Watches for changes of the connected monitors and restores the layout last
+/// seen for them.
+pub fn run() -> Result<(), Box<dyn Error>> {
+    let (sender, receiver) = async_channel::unbounded();
+    randr::run_event_loop(sender, false)?;
+
+    let config = Config::new(APP_NAME, None);
+    let debounce = Duration::from_millis(config.daemon.debounce.into());
+    let randr = Randr::new()?;
+    let outputs = randr.output_model();
+    let daemon = Daemon {
+        config,
+        randr: RefCell::new(randr),
+        fingerprint: RefCell::new(profile::fingerprint(&outputs)),
+    };
+    info!("Connected monitors: {}", daemon.fingerprint.borrow());
+    daemon.restore(&outputs, false);
+
+    let main_loop = MainLoop::new(None, false);
+    spawn_future_local({
+        let main_loop = main_loop.clone();
+        async move {
+            while let Ok(event) = receiver.recv().await {
+                daemon.handle_event(&event);
+                // A single change causes a burst of events, wait for it to settle
+                loop {
+                    timeout_future(debounce).await;
+                    if receiver.is_empty() {
+                        break;
+                    }
+                    while let Ok(event) = receiver.try_recv() {
+                        daemon.handle_event(&event);
+                    }
+                }
+                daemon.on_screen_changed();
+            }
+            main_loop.quit();
+        }
+    });
+    main_loop.run();
+    Err("Lost connection to X Server".into())
+}
+
+impl Daemon {
+    /// Updates the configuration from `event`, querying it anew if the update
+    /// fails.
+    fn handle_event(&self, event: &Event) {
+        let Err(e) = self.randr.borrow().handle_event(event) else {
+            return;
+        };
+        warn!("{e}, reconnecting");
+        match Randr::new() {
+            Ok(randr) => {
+                self.randr.replace(randr);
+            }
+            Err(e) => error!("Failed to reconnect: {e}"),
+        }
+    }
+
+    fn on_screen_changed(&self) {
+        let outputs = self.randr.borrow().output_model();
+        let fingerprint = profile::fingerprint(&outputs);
+        if fingerprint == *self.fingerprint.borrow() {
+            remember(&fingerprint, &outputs);
+        } else {
+            info!("Connected monitors changed: {fingerprint}");
+            self.fingerprint.replace(fingerprint);
+            self.restore(&outputs, true);
+        }
+    }
+
+    fn restore(&self, outputs: &Outputs, fallback: bool) {
+        let fingerprint = self.fingerprint.borrow();
+        let policy = self.config.daemon.fallback;
+        let layouts = Profiles::remembered();
+        if let Some(e) = layouts.error() {
+            // Don't rearrange known monitors because their layout can't be read
+            error!("Not restoring layouts. {e}");
+            return;
+        }
+        if let Some(layout) = layouts.get(&fingerprint) {
+            info!("Restoring layout for {fingerprint}");
+            if let Err(e) = layout.apply_to(outputs) {
+                error!("{e}");
+                return;
+            }
+        } else if fallback && policy != Fallback::None {
+            info!("No layout for {fingerprint}, using fallback {policy}");
+            let randr = self.randr.borrow();
+            arrange(outputs, policy, |outputs| randr.blocked_outputs(outputs));
+        } else {
+            return;
+        }
+
+        let randr = self.randr.borrow();
+        let snapshot = randr.snapshot();
+        match randr.apply(outputs) {
+            Ok(()) => {
+                if self.config.xft.update {
+                    randr.update_xft(outputs, self.config.xft.dpi());
+                }
+                if !self.config.apply_hook.is_empty() {
+                    if let Err(e) = hook::spawn(&self.config.apply_hook) {
+                        warn!("{e}");
+                    }
+                }
+            }
+            Err(e) => {
+                randr.revert(snapshot);
+                error!("Failed to apply layout. {e}. Changes have been reverted.");
+            }
+        }
+    }
+}
+
+/// Remembers the current layout as the one to restore for the connected
+/// monitors.
+fn remember(fingerprint: &str, outputs: &Outputs) {
+    let profile = Profile::from_outputs(outputs);
+    let mut layouts = Profiles::remembered();
+    if let Some(e) = layouts.error() {
+        error!("Not remembering layout. {e}");
+        return;
+    }
+    if layouts.get(fingerprint) != Some(&profile) {
+        debug!("Remembering layout for {fingerprint}");
+        layouts.insert(fingerprint, profile);
+        if let Err(e) = layouts.save() {
+            error!("Failed to remember layout: {e}");
+        }
+    }
+}
+
+/// Enables the outputs that `blocked` doesn't leave without a CRTC, primary
+/// output first, and places them next to each other, starting from the primary
+/// output in the direction given by `policy`.
+fn arrange(outputs: &Outputs, policy: Fallback, blocked: impl Fn(&Outputs) -> Vec<OutputId>) {
+    if policy == Fallback::None {
+        return;
+    }
+    let mut sorted = outputs.iter::<Output>().map(Result::unwrap).collect::<Vec<_>>();
+    if !sorted.iter().any(Output::primary) {
+        if let Some(output) = sorted.iter().find(|o| o.enabled()).or(sorted.first()) {
+            output.set_primary(true);
+        }
+    }
+    sorted.sort_by_key(|o| (!o.primary(), o.name()));
+    for output in &sorted {
+        if !output.enabled() && !blocked(outputs).contains(&output.id()) {
+            output.enable();
+        }
+    }
+    sorted.retain(Output::enabled);
+    if matches!(policy, Fallback::ExtendLeft | Fallback::ExtendAbove) {
+        sorted.reverse();
+    }
+    let mut pos: i16 = 0;
+    for output in &sorted {
+        if matches!(policy, Fallback::ExtendRight | Fallback::ExtendLeft) {
+            output.set_x(pos);
+            output.set_y(0);
+            pos = pos.saturating_add_unsigned(output.width());
+        } else {
+            output.set_x(0);
+            output.set_y(pos);
+            pos = pos.saturating_add_unsigned(output.height());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::output::tests::output;
+    use crate::data::outputs::tests::outputs;
+
+    /// Blocks all disabled outputs once `crtcs` outputs are enabled.
+    fn blocked(crtcs: usize) -> impl Fn(&Outputs) -> Vec<OutputId> {
+        move |outputs| {
+            let outputs = outputs.iter::<Output>().map(Result::unwrap).collect::<Vec<_>>();
+            if outputs.iter().filter(|o| o.enabled()).count() < crtcs {
+                return Vec::new();
+            }
+            outputs.iter().filter(|o| !o.enabled()).map(Output::id).collect()
+        }
+    }
+
+    #[test]
+    fn arrange_more_outputs_than_crtcs() {
+        let outputs = outputs(&[
+            output(1, "DP-1", "DP-1", 1920),
+            output(2, "DP-2", "DP-2", 1920),
+            output(3, "eDP-1", "eDP-1", 1920),
+        ]);
+        outputs.index(2).set_primary(true);
+        arrange(&outputs, Fallback::ExtendRight, blocked(2));
+
+        let enabled = outputs
+            .iter::<Output>()
+            .map(Result::unwrap)
+            .filter(Output::enabled)
+            .map(|o| (o.name(), o.x()))
+            .collect::<Vec<_>>();
+        assert_eq!(enabled, [("DP-1".to_owned(), 1920), ("eDP-1".to_owned(), 0)]);
+        assert!(outputs.index(2).primary());
+    }
+
+    #[test]
+    fn arrange_picks_primary() {
+        let outputs = outputs(&[output(1, "DP-1", "DP-1", 1280), output(2, "DP-2", "DP-2", 1280)]);
+        arrange(&outputs, Fallback::ExtendLeft, blocked(2));
+
+        assert!(outputs.index(0).primary());
+        assert_eq!([outputs.index(0).x(), outputs.index(1).x()], [1280, 0]);
+    }
+}
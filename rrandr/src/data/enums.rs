@@ -1,4 +1,5 @@
 use std::f64::consts::PI;
+use std::str::FromStr;
 
 use glib::Enum;
 use gtk::glib;
@@ -27,6 +28,20 @@ impl Rotation {
     pub fn xrandr(&self) -> String { format!("{self:?}").to_lowercase() }
 }
 
+impl FromStr for Rotation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(Self::Normal),
+            "left" => Ok(Self::Left),
+            "right" => Ok(Self::Right),
+            "inverted" => Ok(Self::Inverted),
+            _ => Err(format!("Not a rotation: {s}")),
+        }
+    }
+}
+
 impl From<Rotation> for u32 {
     fn from(value: Rotation) -> Self { value as u32 }
 }
@@ -90,6 +105,20 @@ impl Reflection {
     }
 }
 
+impl FromStr for Reflection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(Self::Normal),
+            "x" => Ok(Self::Horizontal),
+            "y" => Ok(Self::Vertical),
+            "xy" => Ok(Self::Both),
+            _ => Err(format!("Not a reflection: {s}")),
+        }
+    }
+}
+
 impl From<Reflection> for u32 {
     fn from(value: Reflection) -> Self { value as u32 }
 }
@@ -130,3 +159,25 @@ impl From<Reflection> for RRotation {
         }
     }
 }
+
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Enum)]
+#[enum_type(name = "MirrorFit")]
+pub enum MirrorFit {
+    #[default]
+    Letterbox,
+    Stretch,
+}
+
+impl From<MirrorFit> for u32 {
+    fn from(value: MirrorFit) -> Self { value as u32 }
+}
+
+impl From<u32> for MirrorFit {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => Self::Letterbox,
+            1 => Self::Stretch,
+            x => panic!("Not a mirror fit value: {x}"),
+        }
+    }
+}
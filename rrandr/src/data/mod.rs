@@ -3,4 +3,6 @@ pub mod mode;
 pub mod modes;
 pub mod output;
 pub mod outputs;
+pub mod panning;
+pub mod transform;
 pub mod values;
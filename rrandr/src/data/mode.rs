@@ -4,12 +4,12 @@ use gettextrs::gettext;
 use glib::subclass::types::ObjectSubclassIsExt;
 use glib::{wrapper, Object};
 use gtk::glib;
-use x11rb::protocol::randr::{Mode as ModeId, ModeFlag, ModeInfo};
+use x11rb::protocol::randr::{ModeFlag, ModeInfo};
 
 use super::values::U16;
 
 mod imp {
-    use std::cell::Cell;
+    use std::cell::{Cell, RefCell};
 
     use glib::subclass::object::ObjectImpl;
     use glib::subclass::types::ObjectSubclass;
@@ -26,6 +26,11 @@ mod imp {
     pub struct Mode {
         #[property(get, set, construct_only)]
         id: Cell<ModeId>,
+        #[property(get, set)]
+        name: RefCell<String>,
+        /// Whether the mode was created by the user, no matter how it's named.
+        #[property(get, set)]
+        custom: Cell<bool>,
         #[property(set, construct_only)]
         pub(super) width: Cell<U16>,
         #[property(set, construct_only)]
@@ -34,6 +39,22 @@ mod imp {
         refresh: Cell<f64>,
         #[property(set, construct_only)]
         pub(super) flags: Cell<u32>,
+        #[property(get, set, construct_only)]
+        dot_clock: Cell<u32>,
+        #[property(get, set, construct_only)]
+        hsync_start: Cell<u32>,
+        #[property(get, set, construct_only)]
+        hsync_end: Cell<u32>,
+        #[property(get, set, construct_only)]
+        htotal: Cell<u32>,
+        #[property(get, set, construct_only)]
+        hskew: Cell<u32>,
+        #[property(get, set, construct_only)]
+        vsync_start: Cell<u32>,
+        #[property(get, set, construct_only)]
+        vsync_end: Cell<u32>,
+        #[property(get, set, construct_only)]
+        vtotal: Cell<u32>,
     }
 
     #[object_subclass]
@@ -52,16 +73,6 @@ wrapper! {
 }
 
 impl Mode {
-    pub fn new(id: ModeId, width: u16, height: u16, refresh: f64, flags: ModeFlag) -> Mode {
-        Object::builder()
-            .property("id", id)
-            .property("width", U16::from(width))
-            .property("height", U16::from(height))
-            .property("refresh", refresh)
-            .property("flags", u32::from(flags))
-            .build()
-    }
-
     pub fn width(&self) -> u16 { self.imp().width.get().get() }
 
     pub fn height(&self) -> u16 { self.imp().height.get().get() }
@@ -73,20 +84,67 @@ impl Mode {
         format!("{}\u{202F}x\u{202F}{:<fw$}", self.width(), self.height())
     }
 
+    /// Horizontal frequency in Hz.
+    pub fn hfreq(&self) -> f64 {
+        if self.htotal() > 0 {
+            f64::from(self.dot_clock()) / f64::from(self.htotal())
+        } else {
+            0.0
+        }
+    }
+
+    /// Bits per second needed to transfer the pixels of this mode at the given
+    /// color depth.
+    pub fn bandwidth(&self, bits_per_pixel: u32) -> f64 {
+        f64::from(self.dot_clock()) * f64::from(bits_per_pixel)
+    }
+
     pub fn as_refresh_rate_str(&self) -> String {
         format!("{:.2}\u{202F}{}", self.refresh(), gettext("Hz"))
     }
+
+    /// Mode flags as named by xrandr.
+    pub fn flag_names(&self) -> Vec<&'static str> {
+        [
+            (ModeFlag::HSYNC_POSITIVE, "+HSync"),
+            (ModeFlag::HSYNC_NEGATIVE, "-HSync"),
+            (ModeFlag::VSYNC_POSITIVE, "+VSync"),
+            (ModeFlag::VSYNC_NEGATIVE, "-VSync"),
+            (ModeFlag::INTERLACE, "Interlace"),
+            (ModeFlag::DOUBLE_SCAN, "DoubleScan"),
+            (ModeFlag::CSYNC, "CSync"),
+            (ModeFlag::CSYNC_POSITIVE, "+CSync"),
+            (ModeFlag::CSYNC_NEGATIVE, "-CSync"),
+            (ModeFlag::HSKEW_PRESENT, "HSkew"),
+            (ModeFlag::BCAST, "BCast"),
+            (ModeFlag::PIXEL_MULTIPLEX, "PixMux"),
+            (ModeFlag::DOUBLE_CLOCK, "DoubleClock"),
+            (ModeFlag::HALVE_CLOCK, "ClockDivideBy2"),
+        ]
+        .into_iter()
+        .filter(|(flag, _)| self.flags().contains(*flag))
+        .map(|(_, name)| name)
+        .collect()
+    }
 }
 
 impl From<ModeInfo> for Mode {
     fn from(mode_info: ModeInfo) -> Self {
-        Self::new(
-            mode_info.id,
-            mode_info.width,
-            mode_info.height,
-            get_refresh_rate(&mode_info),
-            mode_info.mode_flags,
-        )
+        Object::builder()
+            .property("id", mode_info.id)
+            .property("width", U16::from(mode_info.width))
+            .property("height", U16::from(mode_info.height))
+            .property("refresh", get_refresh_rate(&mode_info))
+            .property("flags", u32::from(mode_info.mode_flags))
+            .property("dot-clock", mode_info.dot_clock)
+            .property("hsync-start", u32::from(mode_info.hsync_start))
+            .property("hsync-end", u32::from(mode_info.hsync_end))
+            .property("htotal", u32::from(mode_info.htotal))
+            .property("hskew", u32::from(mode_info.hskew))
+            .property("vsync-start", u32::from(mode_info.vsync_start))
+            .property("vsync-end", u32::from(mode_info.vsync_end))
+            .property("vtotal", u32::from(mode_info.vtotal))
+            .build()
     }
 }
 
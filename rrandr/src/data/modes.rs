@@ -64,6 +64,16 @@ impl Modes {
         self.items_changed(index, 0, 1);
     }
 
+    pub fn remove(&self, mode: ModeId) -> Option<Mode> {
+        let (index, removed) = {
+            let mut modes = self.imp().0.borrow_mut();
+            let index = modes.iter().position(|m| m.id() == mode)?;
+            (index, modes.remove(index))
+        };
+        self.items_changed(index.try_into().expect("ListModel should have less items"), 1, 0);
+        Some(removed)
+    }
+
     pub fn find_by_id(&self, mode: ModeId) -> Option<Mode> {
         self.imp().0.borrow().iter().find(|&m| m.id() == mode).cloned()
     }
@@ -77,6 +87,20 @@ impl Modes {
             .map(|i| i.try_into().expect("smaller position"))
     }
 
+    /// Finds the mode with the given resolution and the refresh rate closest to
+    /// `refresh` or the first such mode if no refresh rate is given.
+    pub fn find_by_res(&self, width: u16, height: u16, refresh: Option<f64>) -> Option<Mode> {
+        let modes = self.imp().0.borrow();
+        let mut modes = modes.iter().filter(|m| m.width() == width && m.height() == height);
+        match refresh {
+            Some(r) => {
+                modes.min_by(|a, b| (a.refresh() - r).abs().total_cmp(&(b.refresh() - r).abs()))
+            }
+            None => modes.next(),
+        }
+        .cloned()
+    }
+
     pub fn contains_res(&self, width: u16, height: u16) -> bool {
         self.position_by_res(width, height).is_some()
     }
@@ -4,6 +4,8 @@ use gtk::subclass::prelude::ObjectSubclassIsExt;
 use x11rb::protocol::randr::{Output as OutputId, Rotation as RRotation};
 
 use super::enums::{Reflection, Rotation};
+use super::panning::Panning;
+use super::transform::Transform;
 use super::values::U16;
 use crate::data::mode::Mode;
 use crate::data::modes::Modes;
@@ -25,9 +27,11 @@ mod imp {
     use gtk::subclass::prelude::{DerivedObjectProperties, ObjectSubclassExt};
     use x11rb::protocol::randr::Output as OutputId;
 
-    use crate::data::enums::{Reflection, Rotation};
+    use crate::data::enums::{MirrorFit, Reflection, Rotation};
     use crate::data::mode::Mode;
     use crate::data::modes::Modes;
+    use crate::data::panning::Panning;
+    use crate::data::transform::Transform;
     use crate::data::values::{I16, U16};
 
     #[derive(Properties, Default)]
@@ -39,6 +43,8 @@ mod imp {
         name: RefCell<String>,
         #[property(get, set, nullable)]
         product_name: RefCell<Option<String>>,
+        #[property(get, set, construct_only)]
+        monitor_id: RefCell<String>,
         #[property(get, set)]
         enabled: Cell<bool>,
         #[property(get, set)]
@@ -59,10 +65,33 @@ mod imp {
         pub(super) scale_x: Cell<f64>,
         #[property(get, set, construct_only, default = 1.)]
         pub(super) scale_y: Cell<f64>,
+        /// Full transform replacing the scale, e.g. for keystone correction.
+        #[property(get, set = Self::set_transform, nullable)]
+        transform: RefCell<Option<Transform>>,
+        /// Filter used to sample the screen when the output is transformed.
+        #[property(get, set)]
+        filter: RefCell<String>,
         #[property(set, construct_only)]
         pub(super) width: Cell<U16>,
         #[property(set, construct_only)]
         pub(super) height: Cell<U16>,
+        #[property(get, set)]
+        mirror: Cell<OutputId>,
+        #[property(get, set, builder(MirrorFit::default()))]
+        mirror_fit: Cell<MirrorFit>,
+        #[property(get, set, construct, default = 1.)]
+        gamma_red: Cell<f64>,
+        #[property(get, set, construct, default = 1.)]
+        gamma_green: Cell<f64>,
+        #[property(get, set, construct, default = 1.)]
+        gamma_blue: Cell<f64>,
+        #[property(get, set, construct, default = 1.)]
+        brightness: Cell<f64>,
+        #[property(get, set, nullable)]
+        panning: RefCell<Option<Panning>>,
+        /// Path of the assigned ICC profile.
+        #[property(get, set, nullable)]
+        icc_profile: RefCell<Option<String>>,
         #[property(get, set, construct_only)]
         mm_width: Cell<u32>,
         #[property(get, set, construct_only)]
@@ -106,6 +135,13 @@ mod imp {
             }
         }
 
+        fn set_transform(&self, transform: Option<Transform>) {
+            if transform != *self.transform.borrow() {
+                self.transform.replace(transform);
+                self.update_dim();
+            }
+        }
+
         pub(super) fn update_dim(&self) {
             let obj = self.obj();
 
@@ -127,12 +163,16 @@ mod imp {
                 Rotation::Left | Rotation::Right => [mode.height(), mode.width()],
             }
             .map(f64::from);
-            let width = (w * self.scale_x.get()).round() as u16;
+            let [w, h] = match *self.transform.borrow() {
+                Some(transform) => transform.bounds(w, h),
+                None => [w * self.scale_x.get(), h * self.scale_y.get()],
+            };
+            let width = w.round() as u16;
             if width != obj.width() {
                 self.width.set(width.into());
                 obj.notify_width();
             }
-            let height = (h * self.scale_y.get()).round() as u16;
+            let height = h.round() as u16;
             if height != obj.height() {
                 self.height.set(height.into());
                 obj.notify_height();
@@ -151,6 +191,7 @@ impl Output {
         id: OutputId,
         name: String,
         product_name: Option<String>,
+        monitor_id: String,
         enabled: bool,
         primary: bool,
         pos: [i16; 2],
@@ -166,6 +207,7 @@ impl Output {
             .property("id", id)
             .property("name", name)
             .property("product-name", product_name)
+            .property("monitor-id", monitor_id)
             .property("enabled", enabled)
             .property("primary", primary)
             .property("pos-x", I16::from(pos[0]))
@@ -205,6 +247,9 @@ impl Output {
     }
 
     pub fn disable(&self) {
+        if self.mirror() > 0 {
+            self.unmirror();
+        }
         self.set_enabled(false);
         self.set_primary(false);
         self.set_x(0);
@@ -212,6 +257,8 @@ impl Output {
         self.set_mode(None::<Mode>);
         self.set_rotation(Rotation::Normal);
         self.set_reflection(Reflection::Normal);
+        self.set_panning(None::<Panning>);
+        self.set_transform(None::<Transform>);
     }
 
     pub fn ppi(&self) -> [f64; 2] {
@@ -226,22 +273,116 @@ impl Output {
         PPI_DEFAULT
     }
 
+    /// Size of the current mode after rotation, before any scaling.
+    pub fn rotated_mode_size(&self) -> [u16; 2] {
+        self.mode().map_or([0, 0], |mode| match self.rotation() {
+            Rotation::Normal | Rotation::Inverted => [mode.width(), mode.height()],
+            Rotation::Left | Rotation::Right => [mode.height(), mode.width()],
+        })
+    }
+
     pub fn rect(&self) -> Rect { Rect::new(self.x(), self.y(), self.width(), self.height()) }
 
+    /// Area of the screen the output pans over or its own area if it doesn't
+    /// pan.
+    pub fn panning_rect(&self) -> Rect {
+        match self.panning() {
+            Some(p) => p.area(self.x(), self.y(), self.width(), self.height()),
+            None => self.rect(),
+        }
+    }
+
+    /// Whether both outputs are enabled and show the same area of the screen.
+    pub fn is_clone_of(&self, other: &Output) -> bool {
+        self != other && self.enabled() && other.enabled() && self.rect() == other.rect()
+    }
+
     pub fn randr_rotation(&self) -> RRotation {
         RRotation::from(self.rotation()) | RRotation::from(self.reflection())
     }
 
-    pub fn set_scale(&self, scale: f64) {
+    /// Gamma of the red, green and blue channel.
+    pub fn gamma(&self) -> [f64; 3] { [self.gamma_red(), self.gamma_green(), self.gamma_blue()] }
+
+    pub fn set_gamma(&self, [red, green, blue]: [f64; 3]) {
+        self.set_gamma_red(red);
+        self.set_gamma_green(green);
+        self.set_gamma_blue(blue);
+    }
+
+    pub fn set_scale(&self, scale: f64) { self.set_scale_xy(scale, scale); }
+
+    /// Shows the area of `source` on this output, scaled to fit.
+    pub fn mirror_of(&self, source: &Output) {
+        self.set_transform(None::<Transform>);
+        self.set_mirror(source.id());
+        self.update_mirror(source);
+    }
+
+    /// Moves and scales this output to cover the area of `source` again.
+    pub fn update_mirror(&self, source: &Output) {
+        let Some(mode) = self.mode() else {
+            return;
+        };
+        if self.x() != source.x() {
+            self.set_x(source.x());
+        }
+        if self.y() != source.y() {
+            self.set_y(source.y());
+        }
+        let [w, h] = match self.rotation() {
+            Rotation::Normal | Rotation::Inverted => [mode.width(), mode.height()],
+            Rotation::Left | Rotation::Right => [mode.height(), mode.width()],
+        }
+        .map(f64::from);
+        self.set_scale_xy(f64::from(source.width()) / w, f64::from(source.height()) / h);
+    }
+
+    pub fn unmirror(&self) {
+        self.set_mirror(0);
+        self.set_scale(1.);
+    }
+
+    pub fn set_scale_xy(&self, scale_x: f64, scale_y: f64) {
         let imp = self.imp();
-        if !nearly_eq(scale, imp.scale_x.get()) {
-            imp.scale_x.set(scale);
+        if !nearly_eq(scale_x, imp.scale_x.get()) {
+            imp.scale_x.set(scale_x);
             self.notify_scale_x();
         }
-        if !nearly_eq(scale, imp.scale_y.get()) {
-            imp.scale_y.set(scale);
+        if !nearly_eq(scale_y, imp.scale_y.get()) {
+            imp.scale_y.set(scale_y);
             self.notify_scale_y();
         }
         imp.update_dim();
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use x11rb::protocol::randr::ModeInfo;
+
+    use super::*;
+
+    /// Disabled output `name` connected to `monitor`, with a single mode of
+    /// `width`x1080.
+    pub fn output(id: OutputId, name: &str, monitor: &str, width: u16) -> Output {
+        let modes = Modes::new();
+        modes.append(&Mode::from(ModeInfo { id, width, height: 1080, ..ModeInfo::default() }));
+        Output::new(
+            id,
+            name.to_owned(),
+            None,
+            monitor.to_owned(),
+            false,
+            false,
+            [0, 0],
+            None,
+            modes,
+            Rotation::Normal,
+            Reflection::Normal,
+            [1., 1.],
+            [0, 0],
+            [0, 0],
+        )
+    }
+}
@@ -93,8 +93,37 @@ impl Outputs {
     }
 
     pub fn to_vec(&self) -> Vec<Output> { self.imp().0.borrow().to_vec() }
+
+    /// Keeps mirrors on top of their source output. Mirrors whose source is no
+    /// longer enabled or is a mirror itself stop mirroring.
+    pub fn update_mirrors(&self) {
+        for output in self.to_vec() {
+            if output.mirror() == 0 {
+                continue;
+            }
+            match self.find_by_id(output.mirror()) {
+                Some(source) if output.enabled() && source.enabled() && source.mirror() == 0 => {
+                    output.update_mirror(&source);
+                }
+                _ => output.unmirror(),
+            }
+        }
+    }
 }
 
 impl Default for Outputs {
     fn default() -> Self { Object::new() }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub fn outputs(outputs: &[Output]) -> Outputs {
+        let model = Outputs::new();
+        for output in outputs {
+            model.append(output);
+        }
+        model
+    }
+}
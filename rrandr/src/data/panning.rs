@@ -0,0 +1,35 @@
+use glib::Boxed;
+use gtk::glib;
+
+use crate::math::Rect;
+
+/// Panning of an output over an area of the screen larger than the output.
+/// The output follows the pointer within the panning area, which starts at
+/// the position of the output.
+#[derive(Boxed, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[boxed_type(name = "Panning", nullable)]
+pub struct Panning {
+    pub width: u16,
+    pub height: u16,
+    /// Area the pointer needs to be in for the output to follow it, relative
+    /// to the panning area. An empty tracking area covers the whole screen.
+    pub track_x: i16,
+    pub track_y: i16,
+    pub track_width: u16,
+    pub track_height: u16,
+    /// Distance from the left, top, right and bottom edge of the output at
+    /// which panning starts.
+    pub border: [i16; 4],
+}
+
+impl Panning {
+    pub fn new(width: u16, height: u16) -> Self { Self { width, height, ..Self::default() } }
+
+    pub fn has_tracking_area(&self) -> bool { self.track_width > 0 && self.track_height > 0 }
+
+    /// Panning area of an output at `x`, `y` of `width` x `height`. The
+    /// panning area is never smaller than the output.
+    pub fn area(&self, x: i16, y: i16, width: u16, height: u16) -> Rect {
+        Rect::new(x, y, self.width.max(width), self.height.max(height))
+    }
+}
@@ -0,0 +1,171 @@
+use glib::Boxed;
+use gtk::glib;
+
+use crate::utils::nearly_eq_abs;
+
+/// Filter used when no other filter was chosen.
+pub const FILTER_DEFAULT: &str = "bilinear";
+/// Filters every CRTC supports.
+pub const FILTERS: [&str; 2] = ["nearest", "bilinear"];
+
+/// Precision of the fixed point values the server stores a transform in.
+const TOLERANCE: f64 = 1. / 65536.;
+
+/// Projective transformation of an output, mapping a point of the rotated
+/// and reflected mode to a point of the screen.
+#[derive(Boxed, Clone, Copy, Debug, PartialEq)]
+#[boxed_type(name = "Transform", nullable)]
+pub struct Transform(pub [[f64; 3]; 3]);
+
+impl Default for Transform {
+    fn default() -> Self { Self::scale(1., 1.) }
+}
+
+impl Transform {
+    pub fn scale(x: f64, y: f64) -> Self { Self([[x, 0., 0.], [0., y, 0.], [0., 0., 1.]]) }
+
+    /// Whether this transform only scales and translates.
+    pub fn is_scale(&self) -> bool {
+        let [[_, m12, _], [m21, _, _], [m31, m32, m33]] = self.0;
+        [m12, m21, m31, m32].iter().all(|&v| nearly_eq_abs(v, 0., TOLERANCE))
+            && nearly_eq_abs(m33, 1., TOLERANCE)
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.0
+            .iter()
+            .flatten()
+            .zip(Self::default().0.iter().flatten())
+            .all(|(&a, &b)| nearly_eq_abs(a, b, TOLERANCE))
+    }
+
+    /// Maps `point` or returns `None` if it ends up at infinity.
+    pub fn map(&self, [x, y]: [f64; 2]) -> Option<[f64; 2]> {
+        let [r1, r2, r3] = self.0.map(|[a, b, c]| a * x + b * y + c);
+        (r3.abs() > f64::EPSILON).then(|| [r1 / r3, r2 / r3])
+    }
+
+    #[allow(clippy::many_single_char_names)]
+    pub fn inverse(&self) -> Option<Self> {
+        let [[a, b, c], [d, e, f], [g, h, i]] = self.0;
+        let det = a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g);
+        if det.abs() < f64::EPSILON {
+            return None;
+        }
+        Some(Self(
+            [
+                [e * i - f * h, c * h - b * i, b * f - c * e],
+                [f * g - d * i, a * i - c * g, c * d - a * f],
+                [d * h - e * g, b * g - a * h, a * e - b * d],
+            ]
+            .map(|row| row.map(|v| v / det)),
+        ))
+    }
+
+    /// Transform mapping the four points `from` to the four points `to`, or
+    /// `None` if three of them are on a line.
+    pub fn from_corners(from: [[f64; 2]; 4], to: [[f64; 2]; 4]) -> Option<Self> {
+        let mut rows = [[0.; 9]; 8];
+        for (i, ([x, y], [u, v])) in from.into_iter().zip(to).enumerate() {
+            rows[2 * i] = [x, y, 1., 0., 0., 0., -u * x, -u * y, u];
+            rows[2 * i + 1] = [0., 0., 0., x, y, 1., -v * x, -v * y, v];
+        }
+        let m = solve(rows)?;
+        let transform = Self([[m[0], m[1], m[2]], [m[3], m[4], m[5]], [m[6], m[7], 1.]]);
+        // Collinear targets give a transform flattening the screen
+        transform.inverse().map(|_| transform)
+    }
+
+    /// Size of the bounding box of a `width` x `height` rectangle after
+    /// transformation.
+    pub fn bounds(&self, width: f64, height: f64) -> [f64; 2] {
+        let corners = [[0., 0.], [width, 0.], [width, height], [0., height]]
+            .map(|p| self.map(p).unwrap_or(p));
+        let extent = |axis: usize| {
+            let (min, max) = corners
+                .iter()
+                .fold((f64::MAX, f64::MIN), |(min, max), p| (min.min(p[axis]), max.max(p[axis])));
+            max - min
+        };
+        [extent(0), extent(1)]
+    }
+}
+
+/// Solves the linear system of the augmented matrix `rows` by Gaussian
+/// elimination.
+fn solve(mut rows: [[f64; 9]; 8]) -> Option<[f64; 8]> {
+    for col in 0..8 {
+        let pivot = (col..8).max_by(|&a, &b| rows[a][col].abs().total_cmp(&rows[b][col].abs()))?;
+        if rows[pivot][col].abs() < f64::EPSILON {
+            return None;
+        }
+        rows.swap(col, pivot);
+        for row in 0..8 {
+            if row != col {
+                let factor = rows[row][col] / rows[col][col];
+                let pivot_row = rows[col];
+                for (v, p) in rows[row].iter_mut().zip(pivot_row) {
+                    *v -= factor * p;
+                }
+            }
+        }
+    }
+    Some(std::array::from_fn(|i| rows[i][8] / rows[i][i]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECT: [[f64; 2]; 4] = [[0., 0.], [1920., 0.], [1920., 1080.], [0., 1080.]];
+    /// Keystone correction leaning to the top right.
+    const QUAD: [[f64; 2]; 4] = [[100., 50.], [2020., 0.], [1920., 1080.], [0., 1000.]];
+
+    fn assert_nearly_eq(a: [f64; 2], b: [f64; 2]) {
+        assert!(a.iter().zip(b).all(|(&a, b)| nearly_eq_abs(a, b, 1e-6)), "{a:?} != {b:?}");
+    }
+
+    fn multiply(a: &Transform, b: &Transform) -> Transform {
+        Transform(std::array::from_fn(|i| {
+            std::array::from_fn(|j| (0..3).map(|k| a.0[i][k] * b.0[k][j]).sum())
+        }))
+    }
+
+    #[test]
+    fn map_corners() {
+        let transform = Transform::from_corners(RECT, QUAD).unwrap();
+        for (from, to) in RECT.into_iter().zip(QUAD) {
+            assert_nearly_eq(transform.map(from).unwrap(), to);
+        }
+        assert!(Transform::from_corners(RECT, RECT).unwrap().is_identity());
+    }
+
+    #[test]
+    fn invert() {
+        let transform = Transform::from_corners(RECT, QUAD).unwrap();
+        let inverse = transform.inverse().unwrap();
+        assert!(multiply(&inverse, &transform).is_identity());
+        assert!(multiply(&transform, &inverse).is_identity());
+        for (from, to) in RECT.into_iter().zip(QUAD) {
+            assert_nearly_eq(inverse.map(to).unwrap(), from);
+        }
+        assert_eq!(Transform::scale(2., 0.5).inverse(), Some(Transform::scale(0.5, 2.)));
+    }
+
+    #[test]
+    fn reject_singular() {
+        assert_eq!(Transform::scale(1., 0.).inverse(), None);
+        assert_eq!(Transform([[1., 2., 0.], [2., 4., 0.], [0., 0., 1.]]).inverse(), None);
+        // Three corners on a line
+        let line = [[0., 0.], [960., 0.], [1920., 0.], [0., 1080.]];
+        assert_eq!(Transform::from_corners(RECT, line), None);
+        assert_eq!(Transform::from_corners(line, RECT), None);
+    }
+
+    #[test]
+    fn keystone_bounds() {
+        let transform = Transform::from_corners(RECT, QUAD).unwrap();
+        assert_nearly_eq(transform.bounds(1920., 1080.), [2020., 1080.]);
+        assert_eq!(Transform::scale(1.5, 2.).bounds(1920., 1080.), [2880., 2160.]);
+    }
+}
@@ -72,6 +72,13 @@ impl DrawContext {
         self.cairo.stroke().unwrap();
     }
 
+    /// Tints an output that prevents the configuration from being applied.
+    pub fn draw_invalid_output(&self, rect: &Rectangle) {
+        self.cairo.rectangle(rect.x(), rect.y(), rect.width(), rect.height());
+        self.cairo.set_source_color(&self.config.display_invalid_color().to_rgba(0.4));
+        self.cairo.fill().unwrap();
+    }
+
     pub fn draw_output_label(
         &self,
         rect: &Rectangle,
@@ -117,6 +124,51 @@ impl DrawContext {
         self.cairo.restore().unwrap();
     }
 
+    /// Draws the outline of the area an output pans over.
+    pub fn draw_panning(&self, rect: &Rectangle) {
+        let line_width =
+            self.config.display.output_line_width.clamp(0., rect.width().min(rect.height()) / 2.);
+        self.cairo.rectangle(
+            rect.x() + line_width / 2.,
+            rect.y() + line_width / 2.,
+            rect.width() - line_width,
+            rect.height() - line_width,
+        );
+        self.cairo.set_source_color(&self.config.display_border_color().into());
+        self.set_stroke_style(BorderStyle::Dotted, line_width);
+        self.cairo.stroke().unwrap();
+    }
+
+    /// Draws the outline of a logical monitor with its name in the top left
+    /// corner.
+    pub fn draw_monitor(&self, rect: &Rectangle, name: &str) {
+        self.cairo.save().unwrap();
+        let line_width = 2_f64.min(rect.width().min(rect.height()) / 2.);
+        self.cairo.rectangle(
+            rect.x() + line_width / 2.,
+            rect.y() + line_width / 2.,
+            rect.width() - line_width,
+            rect.height() - line_width,
+        );
+        self.cairo.set_source_color(&self.config.display_text_color().into());
+        self.set_stroke_style(BorderStyle::Dashed, line_width);
+        self.cairo.stroke().unwrap();
+
+        let layout = create_layout(&self.cairo);
+        layout.set_text(name);
+        let mut desc = FontDescription::new();
+        desc.set_family(&self.config.display.font.family);
+        desc.set_size(i32::from(self.config.display.font.size) * PANGO_SCALE * 3 / 4);
+        layout.set_font_description(Some(&desc));
+        let (w, h) = layout.pixel_size();
+        let pad = f64::from(PADDING) / 2.;
+        if f64::from(w) <= rect.width() - pad * 2. && f64::from(h) <= rect.height() - pad * 2. {
+            self.cairo.move_to(rect.x() + pad, rect.y() + pad);
+            show_layout(&self.cairo, &layout);
+        }
+        self.cairo.restore().unwrap();
+    }
+
     pub fn draw_popup(
         &self,
         rect: &Rect,
@@ -0,0 +1,545 @@
+use super::{parse_detailed_timing, DetailedTiming, DESCRIPTOR_LEN};
+
+/// Tag of a CTA-861 extension block.
+pub const TAG: u8 = 0x02;
+
+const TAG_AUDIO: u8 = 1;
+const TAG_VIDEO: u8 = 2;
+const TAG_VENDOR: u8 = 3;
+const TAG_EXTENDED: u8 = 7;
+
+const EXT_TAG_COLORIMETRY: u8 = 5;
+const EXT_TAG_HDR_STATIC_METADATA: u8 = 6;
+const EXT_TAG_YCBCR420_VIDEO: u8 = 14;
+
+const OUI_HDMI: u32 = 0x00_0C03;
+const OUI_HDMI_FORUM: u32 = 0xC4_5DD8;
+
+const AUDIO_FORMATS: [&str; 15] = [
+    "LPCM", "AC-3", "MPEG-1", "MP3", "MPEG-2", "AAC LC", "DTS", "ATRAC", "DSD", "E-AC-3", "DTS-HD",
+    "MAT", "DST", "WMA Pro", "Extended",
+];
+/// Sample rates in Hz from the least significant bit.
+const SAMPLE_RATES: [u32; 7] = [32000, 44100, 48000, 88200, 96000, 176_400, 192_000];
+const COLORIMETRIES: [&str; 9] = [
+    "xvYCC601",
+    "xvYCC709",
+    "sYCC601",
+    "opYCC601",
+    "opRGB",
+    "BT2020cYCC",
+    "BT2020YCC",
+    "BT2020RGB",
+    "DCI-P3",
+];
+const EOTFS: [&str; 4] = ["SDR", "HDR", "PQ", "HLG"];
+
+/// Video identification code, resolution, nominal refresh rate in Hz,
+/// whether it is interlaced and picture aspect ratio of the video formats of
+/// CTA-861-G.
+const VIDEO_FORMATS: [(u8, u16, u16, u16, bool, &str); 154] = [
+    (1, 640, 480, 60, false, "4:3"),
+    (2, 720, 480, 60, false, "4:3"),
+    (3, 720, 480, 60, false, "16:9"),
+    (4, 1280, 720, 60, false, "16:9"),
+    (5, 1920, 1080, 60, true, "16:9"),
+    (6, 1440, 480, 60, true, "4:3"),
+    (7, 1440, 480, 60, true, "16:9"),
+    (8, 1440, 240, 60, false, "4:3"),
+    (9, 1440, 240, 60, false, "16:9"),
+    (10, 2880, 480, 60, true, "4:3"),
+    (11, 2880, 480, 60, true, "16:9"),
+    (12, 2880, 240, 60, false, "4:3"),
+    (13, 2880, 240, 60, false, "16:9"),
+    (14, 1440, 480, 60, false, "4:3"),
+    (15, 1440, 480, 60, false, "16:9"),
+    (16, 1920, 1080, 60, false, "16:9"),
+    (17, 720, 576, 50, false, "4:3"),
+    (18, 720, 576, 50, false, "16:9"),
+    (19, 1280, 720, 50, false, "16:9"),
+    (20, 1920, 1080, 50, true, "16:9"),
+    (21, 1440, 576, 50, true, "4:3"),
+    (22, 1440, 576, 50, true, "16:9"),
+    (23, 1440, 288, 50, false, "4:3"),
+    (24, 1440, 288, 50, false, "16:9"),
+    (25, 2880, 576, 50, true, "4:3"),
+    (26, 2880, 576, 50, true, "16:9"),
+    (27, 2880, 288, 50, false, "4:3"),
+    (28, 2880, 288, 50, false, "16:9"),
+    (29, 1440, 576, 50, false, "4:3"),
+    (30, 1440, 576, 50, false, "16:9"),
+    (31, 1920, 1080, 50, false, "16:9"),
+    (32, 1920, 1080, 24, false, "16:9"),
+    (33, 1920, 1080, 25, false, "16:9"),
+    (34, 1920, 1080, 30, false, "16:9"),
+    (35, 2880, 480, 60, false, "4:3"),
+    (36, 2880, 480, 60, false, "16:9"),
+    (37, 2880, 576, 50, false, "4:3"),
+    (38, 2880, 576, 50, false, "16:9"),
+    (39, 1920, 1080, 50, true, "16:9"),
+    (40, 1920, 1080, 100, true, "16:9"),
+    (41, 1280, 720, 100, false, "16:9"),
+    (42, 720, 576, 100, false, "4:3"),
+    (43, 720, 576, 100, false, "16:9"),
+    (44, 1440, 576, 100, true, "4:3"),
+    (45, 1440, 576, 100, true, "16:9"),
+    (46, 1920, 1080, 120, true, "16:9"),
+    (47, 1280, 720, 120, false, "16:9"),
+    (48, 720, 480, 120, false, "4:3"),
+    (49, 720, 480, 120, false, "16:9"),
+    (50, 1440, 480, 120, true, "4:3"),
+    (51, 1440, 480, 120, true, "16:9"),
+    (52, 720, 576, 200, false, "4:3"),
+    (53, 720, 576, 200, false, "16:9"),
+    (54, 1440, 576, 200, true, "4:3"),
+    (55, 1440, 576, 200, true, "16:9"),
+    (56, 720, 480, 240, false, "4:3"),
+    (57, 720, 480, 240, false, "16:9"),
+    (58, 1440, 480, 240, true, "4:3"),
+    (59, 1440, 480, 240, true, "16:9"),
+    (60, 1280, 720, 24, false, "16:9"),
+    (61, 1280, 720, 25, false, "16:9"),
+    (62, 1280, 720, 30, false, "16:9"),
+    (63, 1920, 1080, 120, false, "16:9"),
+    (64, 1920, 1080, 100, false, "16:9"),
+    (65, 1280, 720, 24, false, "64:27"),
+    (66, 1280, 720, 25, false, "64:27"),
+    (67, 1280, 720, 30, false, "64:27"),
+    (68, 1280, 720, 50, false, "64:27"),
+    (69, 1280, 720, 60, false, "64:27"),
+    (70, 1280, 720, 100, false, "64:27"),
+    (71, 1280, 720, 120, false, "64:27"),
+    (72, 1920, 1080, 24, false, "64:27"),
+    (73, 1920, 1080, 25, false, "64:27"),
+    (74, 1920, 1080, 30, false, "64:27"),
+    (75, 1920, 1080, 50, false, "64:27"),
+    (76, 1920, 1080, 60, false, "64:27"),
+    (77, 1920, 1080, 100, false, "64:27"),
+    (78, 1920, 1080, 120, false, "64:27"),
+    (79, 1680, 720, 24, false, "64:27"),
+    (80, 1680, 720, 25, false, "64:27"),
+    (81, 1680, 720, 30, false, "64:27"),
+    (82, 1680, 720, 50, false, "64:27"),
+    (83, 1680, 720, 60, false, "64:27"),
+    (84, 1680, 720, 100, false, "64:27"),
+    (85, 1680, 720, 120, false, "64:27"),
+    (86, 2560, 1080, 24, false, "64:27"),
+    (87, 2560, 1080, 25, false, "64:27"),
+    (88, 2560, 1080, 30, false, "64:27"),
+    (89, 2560, 1080, 50, false, "64:27"),
+    (90, 2560, 1080, 60, false, "64:27"),
+    (91, 2560, 1080, 100, false, "64:27"),
+    (92, 2560, 1080, 120, false, "64:27"),
+    (93, 3840, 2160, 24, false, "16:9"),
+    (94, 3840, 2160, 25, false, "16:9"),
+    (95, 3840, 2160, 30, false, "16:9"),
+    (96, 3840, 2160, 50, false, "16:9"),
+    (97, 3840, 2160, 60, false, "16:9"),
+    (98, 4096, 2160, 24, false, "256:135"),
+    (99, 4096, 2160, 25, false, "256:135"),
+    (100, 4096, 2160, 30, false, "256:135"),
+    (101, 4096, 2160, 50, false, "256:135"),
+    (102, 4096, 2160, 60, false, "256:135"),
+    (103, 3840, 2160, 24, false, "64:27"),
+    (104, 3840, 2160, 25, false, "64:27"),
+    (105, 3840, 2160, 30, false, "64:27"),
+    (106, 3840, 2160, 50, false, "64:27"),
+    (107, 3840, 2160, 60, false, "64:27"),
+    (108, 1280, 720, 48, false, "16:9"),
+    (109, 1280, 720, 48, false, "64:27"),
+    (110, 1680, 720, 48, false, "64:27"),
+    (111, 1920, 1080, 48, false, "16:9"),
+    (112, 1920, 1080, 48, false, "64:27"),
+    (113, 2560, 1080, 48, false, "64:27"),
+    (114, 3840, 2160, 48, false, "16:9"),
+    (115, 4096, 2160, 48, false, "256:135"),
+    (116, 3840, 2160, 48, false, "64:27"),
+    (117, 3840, 2160, 100, false, "16:9"),
+    (118, 3840, 2160, 120, false, "16:9"),
+    (119, 3840, 2160, 100, false, "64:27"),
+    (120, 3840, 2160, 120, false, "64:27"),
+    (121, 5120, 2160, 24, false, "64:27"),
+    (122, 5120, 2160, 25, false, "64:27"),
+    (123, 5120, 2160, 30, false, "64:27"),
+    (124, 5120, 2160, 48, false, "64:27"),
+    (125, 5120, 2160, 50, false, "64:27"),
+    (126, 5120, 2160, 60, false, "64:27"),
+    (127, 5120, 2160, 100, false, "64:27"),
+    (193, 5120, 2160, 120, false, "64:27"),
+    (194, 7680, 4320, 24, false, "16:9"),
+    (195, 7680, 4320, 25, false, "16:9"),
+    (196, 7680, 4320, 30, false, "16:9"),
+    (197, 7680, 4320, 48, false, "16:9"),
+    (198, 7680, 4320, 50, false, "16:9"),
+    (199, 7680, 4320, 60, false, "16:9"),
+    (200, 7680, 4320, 100, false, "16:9"),
+    (201, 7680, 4320, 120, false, "16:9"),
+    (202, 7680, 4320, 24, false, "64:27"),
+    (203, 7680, 4320, 25, false, "64:27"),
+    (204, 7680, 4320, 30, false, "64:27"),
+    (205, 7680, 4320, 48, false, "64:27"),
+    (206, 7680, 4320, 50, false, "64:27"),
+    (207, 7680, 4320, 60, false, "64:27"),
+    (208, 7680, 4320, 100, false, "64:27"),
+    (209, 7680, 4320, 120, false, "64:27"),
+    (210, 10240, 4320, 24, false, "64:27"),
+    (211, 10240, 4320, 25, false, "64:27"),
+    (212, 10240, 4320, 30, false, "64:27"),
+    (213, 10240, 4320, 48, false, "64:27"),
+    (214, 10240, 4320, 50, false, "64:27"),
+    (215, 10240, 4320, 60, false, "64:27"),
+    (216, 10240, 4320, 100, false, "64:27"),
+    (217, 10240, 4320, 120, false, "64:27"),
+    (218, 4096, 2160, 100, false, "256:135"),
+    (219, 4096, 2160, 120, false, "256:135"),
+];
+
+/// CTA-861 extension block, as used by TVs and most HDMI monitors.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cta {
+    pub revision: u8,
+    pub underscan: bool,
+    pub basic_audio: bool,
+    /// Whether YCbCr 4:4:4 and YCbCr 4:2:2 are supported.
+    pub ycbcr: [bool; 2],
+    pub video: Vec<ShortVideo>,
+    /// Video identification codes only supported with YCbCr 4:2:0.
+    pub ycbcr420_only: Vec<u8>,
+    pub audio: Vec<ShortAudio>,
+    pub hdmi: Option<Hdmi>,
+    pub hdmi_forum: Option<HdmiForum>,
+    pub hdr: Option<HdrStaticMetadata>,
+    pub colorimetry: Vec<&'static str>,
+    pub detailed_timings: Vec<DetailedTiming>,
+}
+
+/// Short video descriptor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShortVideo {
+    /// Video identification code.
+    pub vic: u8,
+    pub native: bool,
+}
+
+/// Video format of a video identification code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VideoFormat {
+    pub width: u16,
+    pub height: u16,
+    /// Nominal refresh rate in Hz, formats at 24, 30, 60, 120 and 240 Hz
+    /// also come in a variant divided by 1.001.
+    pub refresh: u16,
+    pub interlaced: bool,
+    pub aspect_ratio: &'static str,
+}
+
+/// Short audio descriptor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShortAudio {
+    pub format: &'static str,
+    pub channels: u8,
+    /// Sample rates in Hz.
+    pub sample_rates: Vec<u32>,
+    /// Sample sizes in bits of LPCM.
+    pub sample_sizes: Vec<u8>,
+    /// Maximum bit rate in kbit/s of compressed formats.
+    pub max_bit_rate: Option<u16>,
+}
+
+/// HDMI Licensing vendor specific data block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hdmi {
+    /// Address of the sink in the CEC topology.
+    pub physical_address: [u8; 4],
+    /// Supported bits per pixel above 24.
+    pub deep_color: Vec<u8>,
+    /// Maximum TMDS clock in MHz.
+    pub max_tmds_clock: Option<u16>,
+}
+
+/// HDMI Forum vendor specific data block of HDMI 2.x sinks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HdmiForum {
+    pub version: u8,
+    /// Maximum TMDS character rate in MHz.
+    pub max_tmds_rate: Option<u16>,
+    /// Whether the sink has a status and control data channel.
+    pub scdc: bool,
+}
+
+/// HDR static metadata data block with luminances in cd/m².
+#[derive(Clone, Debug, PartialEq)]
+pub struct HdrStaticMetadata {
+    /// Supported electro-optical transfer functions.
+    pub eotfs: Vec<&'static str>,
+    pub max_luminance: Option<f64>,
+    pub max_frame_average: Option<f64>,
+    pub min_luminance: Option<f64>,
+}
+
+impl ShortVideo {
+    pub fn format(self) -> Option<VideoFormat> {
+        VIDEO_FORMATS.iter().find(|f| f.0 == self.vic).map(
+            |&(_, width, height, refresh, interlaced, aspect_ratio)| VideoFormat {
+                width,
+                height,
+                refresh,
+                interlaced,
+                aspect_ratio,
+            },
+        )
+    }
+}
+
+impl Cta {
+    pub fn parse(block: &[u8]) -> Self {
+        let revision = block[1];
+        // An offset of 0 means there are neither data blocks nor detailed timings
+        let dtd_offset = usize::from(block[2]).min(block.len() - 1);
+        let flags = block[3];
+        let mut cta = Self {
+            revision,
+            underscan: flags & 0x80 != 0,
+            basic_audio: flags & 0x40 != 0,
+            ycbcr: [flags & 0x20 != 0, flags & 0x10 != 0],
+            video: Vec::new(),
+            ycbcr420_only: Vec::new(),
+            audio: Vec::new(),
+            hdmi: None,
+            hdmi_forum: None,
+            hdr: None,
+            colorimetry: Vec::new(),
+            detailed_timings: Vec::new(),
+        };
+        let mut i = 4;
+        while i < dtd_offset {
+            let tag = block[i] >> 5;
+            let end = (i + 1 + usize::from(block[i] & 0x1F)).min(dtd_offset);
+            cta.parse_data_block(tag, &block[(i + 1)..end]);
+            i = end;
+        }
+        if dtd_offset < 4 {
+            return cta;
+        }
+        // The last byte is the checksum
+        cta.detailed_timings = block[dtd_offset..(block.len() - 1)]
+            .chunks_exact(DESCRIPTOR_LEN)
+            .take_while(|desc| desc[0..2] != [0, 0])
+            .map(parse_detailed_timing)
+            .collect();
+        cta
+    }
+
+    fn parse_data_block(&mut self, tag: u8, payload: &[u8]) {
+        match tag {
+            TAG_AUDIO => self.audio.extend(payload.chunks_exact(3).map(parse_short_audio)),
+            TAG_VIDEO => self.video.extend(payload.iter().map(|&b| parse_short_video(b))),
+            TAG_VENDOR if payload.len() >= 3 => {
+                let oui = u32::from_le_bytes([payload[0], payload[1], payload[2], 0]);
+                match oui {
+                    OUI_HDMI => self.hdmi = Some(parse_hdmi(payload)),
+                    OUI_HDMI_FORUM => self.hdmi_forum = Some(parse_hdmi_forum(payload)),
+                    _ => (),
+                }
+            }
+            TAG_EXTENDED if !payload.is_empty() => {
+                let payload_ext = &payload[1..];
+                match payload[0] {
+                    EXT_TAG_COLORIMETRY => {
+                        let bits = u16::from_le_bytes([
+                            payload_ext.first().copied().unwrap_or_default(),
+                            payload_ext.get(1).copied().unwrap_or_default() >> 7,
+                        ]);
+                        self.colorimetry = (0..)
+                            .zip(COLORIMETRIES)
+                            .filter(|(i, _)| bits & (1 << i) != 0)
+                            .map(|(_, c)| c)
+                            .collect();
+                    }
+                    EXT_TAG_HDR_STATIC_METADATA => self.hdr = Some(parse_hdr(payload_ext)),
+                    EXT_TAG_YCBCR420_VIDEO => {
+                        self.ycbcr420_only
+                            .extend(payload_ext.iter().map(|&b| parse_short_video(b).vic));
+                    }
+                    _ => (),
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Codes 129 to 192 are the native codes 1 to 64 with the most significant
+/// bit set.
+fn parse_short_video(byte: u8) -> ShortVideo {
+    match byte {
+        129..=192 => ShortVideo { vic: byte & 0x7F, native: true },
+        vic => ShortVideo { vic, native: false },
+    }
+}
+
+fn parse_short_audio(sad: &[u8]) -> ShortAudio {
+    let code = usize::from((sad[0] >> 3) & 0x0F);
+    let lpcm = code == 1;
+    ShortAudio {
+        format: code.checked_sub(1).and_then(|i| AUDIO_FORMATS.get(i)).copied().unwrap_or("?"),
+        channels: (sad[0] & 0x07) + 1,
+        sample_rates: (0..)
+            .zip(SAMPLE_RATES)
+            .filter(|(i, _)| sad[1] & (1 << i) != 0)
+            .map(|(_, rate)| rate)
+            .collect(),
+        sample_sizes: if lpcm {
+            (0..)
+                .zip([16, 20, 24])
+                .filter(|(i, _)| sad[2] & (1 << i) != 0)
+                .map(|(_, s)| s)
+                .collect()
+        } else {
+            Vec::new()
+        },
+        max_bit_rate: (!lpcm && (2..=8).contains(&code)).then(|| u16::from(sad[2]) * 8),
+    }
+}
+
+fn parse_hdmi(payload: &[u8]) -> Hdmi {
+    let byte = |i: usize| payload.get(i).copied().unwrap_or_default();
+    let address = [byte(3), byte(4)];
+    Hdmi {
+        physical_address: [address[0] >> 4, address[0] & 0x0F, address[1] >> 4, address[1] & 0x0F],
+        deep_color: [(0x40, 48), (0x20, 36), (0x10, 30)]
+            .into_iter()
+            .filter(|(bit, _)| byte(5) & bit != 0)
+            .map(|(_, bits)| bits)
+            .rev()
+            .collect(),
+        max_tmds_clock: (byte(6) > 0).then(|| u16::from(byte(6)) * 5),
+    }
+}
+
+fn parse_hdmi_forum(payload: &[u8]) -> HdmiForum {
+    let byte = |i: usize| payload.get(i).copied().unwrap_or_default();
+    HdmiForum {
+        version: byte(3),
+        max_tmds_rate: (byte(4) > 0).then(|| u16::from(byte(4)) * 5),
+        scdc: byte(5) & 0x80 != 0,
+    }
+}
+
+fn parse_hdr(payload: &[u8]) -> HdrStaticMetadata {
+    let eotfs = payload.first().copied().unwrap_or_default();
+    // Coded values as defined by CTA-861.3
+    let max = |cv: u8| 50. * 2_f64.powf(f64::from(cv) / 32.);
+    let max_luminance = payload.get(2).filter(|&&cv| cv > 0).map(|&cv| max(cv));
+    HdrStaticMetadata {
+        eotfs: (0..)
+            .zip(EOTFS)
+            .filter(|(i, _)| eotfs & (1 << i) != 0)
+            .map(|(_, eotf)| eotf)
+            .collect(),
+        max_luminance,
+        max_frame_average: payload.get(3).filter(|&&cv| cv > 0).map(|&cv| max(cv)),
+        min_luminance: payload.get(4).zip(max_luminance).map(|(&cv, max)| {
+            let cv = f64::from(cv) / 255.;
+            max * cv * cv / 100.
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edid::tests::from_hex;
+    use crate::edid::BLOCK_LEN;
+
+    /// CTA-861 extension block of an HDMI 1.4 monitor with HDR10 support.
+    const CTA_HDMI: &str = "
+        02 03 1f f1 44 90 04 03 5f 23 09 07 07 67 03 0c
+        00 10 00 30 3c e3 05 c0 00 e5 06 05 01 60 50 02
+        3a 80 18 71 38 2d 40 58 2c 45 00 20 2f 21 00 00
+        1e 01 1d 00 72 51 d0 1e 20 6e 28 55 00 c4 8e 21
+        00 00 1e 00 00 00 00 00 00 00 00 00 00 00 00 00
+        00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+        00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+        00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 90
+    ";
+
+    #[test]
+    fn parse_data_blocks() {
+        let cta = Cta::parse(&from_hex(CTA_HDMI));
+        assert_eq!(cta.revision, 3);
+        assert!(cta.underscan && cta.basic_audio);
+        assert_eq!(cta.ycbcr, [true, true]);
+        assert_eq!(
+            cta.video,
+            [
+                ShortVideo { vic: 16, native: true },
+                ShortVideo { vic: 4, native: false },
+                ShortVideo { vic: 3, native: false },
+                ShortVideo { vic: 95, native: false },
+            ]
+        );
+        assert_eq!(
+            cta.video[3].format(),
+            Some(VideoFormat {
+                width: 3840,
+                height: 2160,
+                refresh: 30,
+                interlaced: false,
+                aspect_ratio: "16:9"
+            })
+        );
+        assert_eq!(
+            cta.audio,
+            [ShortAudio {
+                format: "LPCM",
+                channels: 2,
+                sample_rates: vec![32000, 44100, 48000],
+                sample_sizes: vec![16, 20, 24],
+                max_bit_rate: None,
+            }]
+        );
+        assert_eq!(
+            cta.hdmi,
+            Some(Hdmi {
+                physical_address: [1, 0, 0, 0],
+                deep_color: vec![30, 36],
+                max_tmds_clock: Some(300)
+            })
+        );
+        assert_eq!(cta.colorimetry, ["BT2020YCC", "BT2020RGB"]);
+        let hdr = cta.hdr.unwrap();
+        assert_eq!(hdr.eotfs, ["SDR", "PQ"]);
+        assert_eq!(hdr.max_luminance, Some(400.));
+        assert!(hdr.max_frame_average.is_some_and(|l| (l - 282.84).abs() < 0.01));
+    }
+
+    #[test]
+    fn parse_detailed_timings() {
+        let cta = Cta::parse(&from_hex(CTA_HDMI));
+        let modelines = cta.detailed_timings.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            modelines,
+            [
+                "148.50 1920 2008 2052 2200 1080 1084 1089 1125",
+                "74.25 1280 1390 1430 1650 720 725 730 750"
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_without_detailed_timings() {
+        let mut block = from_hex(CTA_HDMI);
+        block[2] = 0;
+        let cta = Cta::parse(&block);
+        assert!(cta.video.is_empty() && cta.detailed_timings.is_empty());
+
+        // Detailed timings right after the header
+        let block = from_hex(CTA_HDMI);
+        let mut block = [&[0x02, 0x03, 0x04, 0xF1], &block[31..67]].concat();
+        block.resize(BLOCK_LEN, 0);
+        let cta = Cta::parse(&block);
+        assert!(cta.video.is_empty());
+        assert_eq!(cta.detailed_timings.len(), 2);
+    }
+}
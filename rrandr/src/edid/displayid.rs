@@ -0,0 +1,155 @@
+use super::DetailedTiming;
+
+/// Tag of a `DisplayID` extension block.
+pub const TAG: u8 = 0x70;
+
+const TAG_TYPE_I_TIMING: u8 = 0x03;
+const TAG_TILED_DISPLAY: u8 = 0x12;
+const TAG_TYPE_VII_TIMING: u8 = 0x22;
+const TAG_TILED_DISPLAY_2: u8 = 0x28;
+
+const TIMING_LEN: usize = 20;
+const TILE_LEN: usize = 22;
+
+/// `DisplayID` section of an extension block, as used by tiled and high
+/// resolution monitors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DisplayId {
+    /// Version and revision.
+    pub version: [u8; 2],
+    pub detailed_timings: Vec<DetailedTiming>,
+    pub tile: Option<Tile>,
+}
+
+/// Position of a monitor in a display made of several tiles, each driven by
+/// its own output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tile {
+    /// Number of horizontal and vertical tiles.
+    pub tiles: [u8; 2],
+    /// Horizontal and vertical index of this tile.
+    pub location: [u8; 2],
+    /// Size of this tile in pixels.
+    pub size: [u16; 2],
+    /// PNP ID of the manufacturer of the tiled display.
+    pub manufacturer: String,
+    pub product_code: u16,
+    pub serial_number: u32,
+}
+
+impl DisplayId {
+    pub fn parse(block: &[u8]) -> Self {
+        let section = &block[1..];
+        let mut displayid = Self {
+            version: [section[0] >> 4, section[0] & 0x0F],
+            detailed_timings: Vec::new(),
+            tile: None,
+        };
+        // Header of 4 bytes and a checksum
+        let end = (4 + usize::from(section[1])).min(section.len() - 1);
+        let mut i = 4;
+        while i + 3 <= end {
+            let tag = section[i];
+            let payload_end = (i + 3 + usize::from(section[i + 2])).min(end);
+            let payload = &section[(i + 3)..payload_end];
+            match tag {
+                TAG_TYPE_I_TIMING => displayid
+                    .detailed_timings
+                    .extend(payload.chunks_exact(TIMING_LEN).map(|t| parse_timing(t, 10))),
+                TAG_TYPE_VII_TIMING => displayid
+                    .detailed_timings
+                    .extend(payload.chunks_exact(TIMING_LEN).map(|t| parse_timing(t, 1))),
+                TAG_TILED_DISPLAY | TAG_TILED_DISPLAY_2 if payload.len() >= TILE_LEN => {
+                    displayid.tile = Some(parse_tile(payload));
+                }
+                // Padding
+                0 => break,
+                _ => (),
+            }
+            i = payload_end;
+        }
+        displayid
+    }
+}
+
+/// Decodes a type I or type VII timing with its pixel clock given in units of
+/// `clock_unit` kHz.
+fn parse_timing(desc: &[u8], clock_unit: u32) -> DetailedTiming {
+    let value = |i: usize| (u16::from_le_bytes([desc[i], desc[i + 1]]) & 0x7FFF) + 1;
+    let [h_active, h_blank, h_front, h_sync] = [4, 6, 8, 10].map(value);
+    let [v_active, v_blank, v_front, v_sync] = [12, 14, 16, 18].map(value);
+    DetailedTiming {
+        pixel_clock: (u32::from_le_bytes([desc[0], desc[1], desc[2], 0]) + 1) * clock_unit,
+        horizontal: [h_active, h_front, h_sync, h_blank],
+        vertical: [v_active, v_front, v_sync, v_blank],
+        size: [0, 0],
+        interlaced: desc[3] & 0x10 != 0,
+    }
+}
+
+fn parse_tile(payload: &[u8]) -> Tile {
+    let high = payload[3];
+    let tiles =
+        [((payload[1] >> 4) | ((high >> 2) & 0x30)) + 1, ((payload[1] & 0x0F) | (high & 0x30)) + 1];
+    let location =
+        [(payload[2] >> 4) | ((high << 2) & 0x30), (payload[2] & 0x0F) | ((high << 4) & 0x30)];
+    Tile {
+        tiles,
+        location,
+        size: [4, 6].map(|i| u16::from_le_bytes([payload[i], payload[i + 1]]) + 1),
+        manufacturer: String::from_utf8_lossy(&payload[13..16]).into_owned(),
+        product_code: u16::from_le_bytes([payload[16], payload[17]]),
+        serial_number: u32::from_le_bytes([payload[18], payload[19], payload[20], payload[21]]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edid::tests::from_hex;
+
+    /// `DisplayID` 1.2 extension block of the left tile of a 5K monitor made
+    /// of two 2560x2880 tiles.
+    const TILED_5K_LEFT: &str = "
+        70 12 30 00 00 03 00 14 d3 bc 00 00 ff 09 9f 00
+        2f 80 1f 00 3f 0b 51 00 02 00 04 00 12 00 16 82
+        10 00 00 ff 09 3f 0b 00 00 00 00 00 47 53 4d 71
+        5b a3 f0 01 00 af 00 00 00 00 00 00 00 00 00 00
+        00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+        00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+        00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00
+        00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 90
+    ";
+
+    #[test]
+    fn parse_tiled_display() {
+        let displayid = DisplayId::parse(&from_hex(TILED_5K_LEFT));
+        assert_eq!(displayid.version, [1, 2]);
+        assert_eq!(
+            displayid.tile,
+            Some(Tile {
+                tiles: [2, 1],
+                location: [0, 0],
+                size: [2560, 2880],
+                manufacturer: "GSM".to_owned(),
+                product_code: 0x5B71,
+                serial_number: 0x0001_F0A3,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_type_i_timing() {
+        let displayid = DisplayId::parse(&from_hex(TILED_5K_LEFT));
+        assert_eq!(
+            displayid.detailed_timings,
+            [DetailedTiming {
+                pixel_clock: 483_400,
+                horizontal: [2560, 48, 32, 160],
+                vertical: [2880, 3, 5, 82],
+                size: [0, 0],
+                interlaced: false,
+            }]
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use std::fmt::{self, Display, Formatter, Write};
+
+use super::cta::Cta;
+use super::displayid::DisplayId;
+use super::{DetailedTiming, Edid, Extension, Manufactured, ScreenSize, StandardTiming, BLOCK_LEN};
+
+const BYTES_PER_LINE: usize = 16;
+
+/// Formats `data` as lines of 16 hexadecimal bytes with an empty line after
+/// each block, the format `edid-decode` reads and writes.
+pub fn hex_dump(data: &[u8]) -> String {
+    let mut dump = String::new();
+    for (i, line) in data.chunks(BYTES_PER_LINE).enumerate() {
+        if i > 0 && i % (BLOCK_LEN / BYTES_PER_LINE) == 0 {
+            dump.push('\n');
+        }
+        let line = line.iter().map(|b| format!("{b:02x}")).collect::<Vec<_>>().join(" ");
+        dump.push_str(&line);
+        dump.push('\n');
+    }
+    dump
+}
+
+impl Display for Edid {
+    /// Describes the EDID in the style of `edid-decode`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Block 0, Base EDID:")?;
+        writeln!(
+            f,
+            "  EDID Structure Version & Revision: {}.{}",
+            self.version[0], self.version[1]
+        )?;
+        writeln!(f, "  Vendor & Product Identification:")?;
+        match self.manufacturer_name() {
+            Some(name) => writeln!(f, "    Manufacturer: {} ({name})", self.manufacturer)?,
+            None => writeln!(f, "    Manufacturer: {}", self.manufacturer)?,
+        }
+        writeln!(f, "    Model: {}", self.product_code)?;
+        if self.serial_number > 0 {
+            writeln!(f, "    Serial Number: {}", self.serial_number)?;
+        }
+        match self.manufactured {
+            Manufactured::Week(Some(week), year) => {
+                writeln!(f, "    Made in: week {week} of {year}")?;
+            }
+            Manufactured::Week(None, year) => writeln!(f, "    Made in: {year}")?,
+            Manufactured::ModelYear(year) => writeln!(f, "    Model year: {year}")?,
+        }
+        writeln!(f, "  Basic Display Parameters & Features:")?;
+        if self.input.digital {
+            writeln!(f, "    Digital display")?;
+            if let Some(depth) = self.input.bit_depth {
+                writeln!(f, "    Bits per primary color channel: {depth}")?;
+            }
+            if let Some(interface) = self.input.interface {
+                writeln!(f, "    {interface} interface")?;
+            }
+        } else {
+            writeln!(f, "    Analog display")?;
+        }
+        match self.size {
+            ScreenSize::Size(width, height) => {
+                writeln!(f, "    Maximum image size: {width} cm x {height} cm")?;
+            }
+            ScreenSize::AspectRatio(ratio) => writeln!(f, "    Aspect ratio: {ratio:.2}")?,
+            ScreenSize::Undefined => writeln!(f, "    Image size is variable")?,
+        }
+        match self.gamma {
+            Some(gamma) => writeln!(f, "    Gamma: {gamma:.2}")?,
+            None => writeln!(f, "    Gamma is defined in an extension block")?,
+        }
+        writeln!(f, "  Color Characteristics:")?;
+        let chroma = &self.chromaticity;
+        for (name, [x, y]) in [
+            ("Red  ", chroma.red),
+            ("Green", chroma.green),
+            ("Blue ", chroma.blue),
+            ("White", chroma.white),
+        ] {
+            writeln!(f, "    {name}: {x:.4}, {y:.4}")?;
+        }
+        writeln!(f, "  Established Timings I & II:")?;
+        write_timings(f, &self.established_timings)?;
+        writeln!(f, "  Standard Timings:")?;
+        write_timings(f, &self.standard_timings)?;
+        writeln!(f, "  Detailed Timing Descriptors:")?;
+        write_detailed_timings(f, &self.detailed_timings)?;
+        if let Some(name) = &self.name {
+            writeln!(f, "    Display Product Name: '{name}'")?;
+        }
+        if let Some(serial) = &self.serial {
+            writeln!(f, "    Display Product Serial Number: '{serial}'")?;
+        }
+        for text in &self.texts {
+            writeln!(f, "    Alphanumeric Data String: '{text}'")?;
+        }
+        if let Some(limits) = &self.range_limits {
+            let [v_min, v_max] = limits.vertical;
+            let [h_min, h_max] = limits.horizontal;
+            write!(f, "    Display Range Limits: {v_min}-{v_max} Hz V, {h_min}-{h_max} kHz H")?;
+            match limits.max_pixel_clock {
+                Some(clock) => writeln!(f, ", max dotclock {clock} MHz")?,
+                None => writeln!(f)?,
+            }
+        }
+        writeln!(f, "  Extension blocks: {}", self.extensions.len())?;
+        for (i, extension) in (1..).zip(&self.extensions) {
+            match extension {
+                Extension::Cta(cta) => write_cta(f, i, cta)?,
+                Extension::DisplayId(displayid) => write_displayid(f, i, displayid)?,
+                Extension::Other(tag) => writeln!(f, "Block {i}, Unknown Extension ({tag:#04x})")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_timings(f: &mut Formatter<'_>, timings: &[StandardTiming]) -> fmt::Result {
+    for t in timings {
+        writeln!(f, "    {:>11} {:>4} Hz", format!("{}x{}", t.width, t.height), t.refresh)?;
+    }
+    Ok(())
+}
+
+fn write_detailed_timings(f: &mut Formatter<'_>, timings: &[DetailedTiming]) -> fmt::Result {
+    for (i, t) in (1..).zip(timings) {
+        let [h_active, h_front, h_sync, h_blank] = t.horizontal;
+        let [v_active, v_front, v_sync, v_blank] = t.vertical;
+        let [width, height] = t.size;
+        let mut line = format!(
+            "    DTD {i}: {:>11} {:>10.6} Hz {:>8.3} MHz",
+            format!("{h_active}x{v_active}{}", if t.interlaced { "i" } else { "" }),
+            t.refresh(),
+            f64::from(t.pixel_clock) / 1000.
+        );
+        if width > 0 && height > 0 {
+            write!(line, " ({width} mm x {height} mm)")?;
+        }
+        writeln!(f, "{line}")?;
+        writeln!(
+            f,
+            "               Hfront {h_front:>4} Hsync {h_sync:>3} Hback {:>4}",
+            h_blank.saturating_sub(h_front + h_sync)
+        )?;
+        writeln!(
+            f,
+            "               Vfront {v_front:>4} Vsync {v_sync:>3} Vback {:>4}",
+            v_blank.saturating_sub(v_front + v_sync)
+        )?;
+    }
+    Ok(())
+}
+
+fn write_cta(f: &mut Formatter<'_>, block: usize, cta: &Cta) -> fmt::Result {
+    writeln!(f, "Block {block}, CTA-861 Extension Block:")?;
+    writeln!(f, "  Revision: {}", cta.revision)?;
+    if cta.underscan {
+        writeln!(f, "  Underscans IT Video Formats by default")?;
+    }
+    if cta.basic_audio {
+        writeln!(f, "  Basic audio support")?;
+    }
+    for (supported, name) in cta.ycbcr.into_iter().zip(["YCbCr 4:4:4", "YCbCr 4:2:2"]) {
+        if supported {
+            writeln!(f, "  Supports {name}")?;
+        }
+    }
+    if !cta.video.is_empty() {
+        writeln!(f, "  Video Data Block:")?;
+        for svd in &cta.video {
+            write!(f, "    VIC {:>3}:", svd.vic)?;
+            if let Some(format) = svd.format() {
+                let res = format!(
+                    "{}x{}{}",
+                    format.width,
+                    format.height,
+                    if format.interlaced { "i" } else { "" }
+                );
+                write!(f, " {res:>11} {:>4} Hz {:>7}", format.refresh, format.aspect_ratio)?;
+            }
+            writeln!(f, "{}", if svd.native { " (native)" } else { "" })?;
+        }
+    }
+    if !cta.ycbcr420_only.is_empty() {
+        writeln!(f, "  YCbCr 4:2:0 Video Data Block:")?;
+        for vic in &cta.ycbcr420_only {
+            writeln!(f, "    VIC {vic:>3}")?;
+        }
+    }
+    if !cta.audio.is_empty() {
+        writeln!(f, "  Audio Data Block:")?;
+        for sad in &cta.audio {
+            writeln!(f, "    {}:", sad.format)?;
+            writeln!(f, "      Max channels: {}", sad.channels)?;
+            let rates = sad.sample_rates.iter().map(|&r| format!("{}", f64::from(r) / 1000.));
+            writeln!(
+                f,
+                "      Supported sample rates (kHz): {}",
+                rates.collect::<Vec<_>>().join(" ")
+            )?;
+            if !sad.sample_sizes.is_empty() {
+                let sizes = sad.sample_sizes.iter().map(u8::to_string).collect::<Vec<_>>();
+                writeln!(f, "      Supported sample sizes (bits): {}", sizes.join(" "))?;
+            }
+            if let Some(rate) = sad.max_bit_rate {
+                writeln!(f, "      Maximum bit rate: {rate} kb/s")?;
+            }
+        }
+    }
+    write_hdmi(f, cta)?;
+    if !cta.colorimetry.is_empty() {
+        writeln!(f, "  Colorimetry Data Block:")?;
+        for colorimetry in &cta.colorimetry {
+            writeln!(f, "    {colorimetry}")?;
+        }
+    }
+    if let Some(hdr) = &cta.hdr {
+        writeln!(f, "  HDR Static Metadata Data Block:")?;
+        writeln!(f, "    Electro optical transfer functions: {}", hdr.eotfs.join(", "))?;
+        let luminance = [
+            ("Desired content max luminance", hdr.max_luminance),
+            ("Desired content max frame-average luminance", hdr.max_frame_average),
+            ("Desired content min luminance", hdr.min_luminance),
+        ];
+        for (name, value) in luminance {
+            if let Some(value) = value {
+                writeln!(f, "    {name}: {value:.3} cd/m^2")?;
+            }
+        }
+    }
+    if !cta.detailed_timings.is_empty() {
+        writeln!(f, "  Detailed Timing Descriptors:")?;
+        write_detailed_timings(f, &cta.detailed_timings)?;
+    }
+    Ok(())
+}
+
+fn write_hdmi(f: &mut Formatter<'_>, cta: &Cta) -> fmt::Result {
+    if let Some(hdmi) = &cta.hdmi {
+        let address = hdmi.physical_address.map(|n| n.to_string()).join(".");
+        writeln!(f, "  Vendor-Specific Data Block (HDMI), OUI 00-0C-03:")?;
+        writeln!(f, "    Source physical address: {address}")?;
+        for bits in &hdmi.deep_color {
+            writeln!(f, "    DC_{bits}bit")?;
+        }
+        if let Some(clock) = hdmi.max_tmds_clock {
+            writeln!(f, "    Maximum TMDS clock: {clock} MHz")?;
+        }
+    }
+    if let Some(hdmi) = &cta.hdmi_forum {
+        writeln!(f, "  Vendor-Specific Data Block (HDMI Forum), OUI C4-5D-D8:")?;
+        writeln!(f, "    Version: {}", hdmi.version)?;
+        if let Some(rate) = hdmi.max_tmds_rate {
+            writeln!(f, "    Maximum TMDS Character Rate: {rate} MHz")?;
+        }
+        if hdmi.scdc {
+            writeln!(f, "    SCDC Present")?;
+        }
+    }
+    Ok(())
+}
+
+fn write_displayid(f: &mut Formatter<'_>, block: usize, displayid: &DisplayId) -> fmt::Result {
+    writeln!(
+        f,
+        "Block {block}, DisplayID Extension Block:\n  Version: {}.{}",
+        displayid.version[0], displayid.version[1]
+    )?;
+    if let Some(tile) = &displayid.tile {
+        let [h_tiles, v_tiles] = tile.tiles;
+        let [x, y] = tile.location;
+        let [width, height] = tile.size;
+        writeln!(f, "  Tiled Display Topology Data Block:")?;
+        writeln!(f, "    Number of tiles: {h_tiles} (horizontal) x {v_tiles} (vertical)")?;
+        writeln!(f, "    Tile location: {x}, {y}")?;
+        writeln!(f, "    Tile resolution: {width}x{height}")?;
+        writeln!(
+            f,
+            "    Tiled Display Manufacturer/Vendor ID: {}\n    Tiled Display Product ID Code: \
+             {}\n    Tiled Display Serial Number: {}",
+            tile.manufacturer, tile.product_code, tile.serial_number
+        )?;
+    }
+    if !displayid.detailed_timings.is_empty() {
+        writeln!(f, "  Detailed Timings:")?;
+        write_detailed_timings(f, &displayid.detailed_timings)?;
+    }
+    Ok(())
+}
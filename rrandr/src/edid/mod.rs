@@ -0,0 +1,509 @@
+pub mod cta;
+pub mod displayid;
+pub mod dump;
+mod pnp;
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use log::warn;
+
+use self::cta::Cta;
+use self::displayid::DisplayId;
+
+/// Length of the base block and of each extension block.
+pub const BLOCK_LEN: usize = 128;
+
+const HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+const DESCRIPTORS_OFFSET: usize = 0x36;
+const DESCRIPTOR_LEN: usize = 18;
+
+const TAG_SERIAL: u8 = 0xFF;
+const TAG_TEXT: u8 = 0xFE;
+const TAG_RANGE_LIMITS: u8 = 0xFD;
+const TAG_NAME: u8 = 0xFC;
+
+/// Established timings I and II from the most significant bit of byte 0x23
+/// to the least significant bit of byte 0x24, followed by the manufacturer's
+/// timing in the most significant bit of byte 0x25.
+const ESTABLISHED_TIMINGS: [(u16, u16, u8); 17] = [
+    (720, 400, 70),
+    (720, 400, 88),
+    (640, 480, 60),
+    (640, 480, 67),
+    (640, 480, 72),
+    (640, 480, 75),
+    (800, 600, 56),
+    (800, 600, 60),
+    (800, 600, 72),
+    (800, 600, 75),
+    (832, 624, 75),
+    (1024, 768, 87),
+    (1024, 768, 60),
+    (1024, 768, 70),
+    (1024, 768, 75),
+    (1280, 1024, 75),
+    (1152, 870, 75),
+];
+
+/// Base block of the Extended Display Identification Data of a monitor
+/// (EDID 1.x as defined by VESA E-EDID Release A, Revision 2).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edid {
+    /// Version and revision.
+    pub version: [u8; 2],
+    /// Three letter PNP ID of the manufacturer.
+    pub manufacturer: String,
+    pub product_code: u16,
+    pub serial_number: u32,
+    pub manufactured: Manufactured,
+    pub input: Input,
+    pub size: ScreenSize,
+    pub gamma: Option<f64>,
+    pub chromaticity: Chromaticity,
+    pub established_timings: Vec<StandardTiming>,
+    pub standard_timings: Vec<StandardTiming>,
+    pub detailed_timings: Vec<DetailedTiming>,
+    /// Product name from the display product name descriptor.
+    pub name: Option<String>,
+    /// Serial number from the display product serial number descriptor.
+    pub serial: Option<String>,
+    /// Alphanumeric data string descriptors.
+    pub texts: Vec<String>,
+    pub range_limits: Option<RangeLimits>,
+    pub extensions: Vec<Extension>,
+}
+
+/// Extension block following the base block.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Extension {
+    Cta(Cta),
+    DisplayId(DisplayId),
+    /// Block with a tag that isn't decoded.
+    Other(u8),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Manufactured {
+    /// Week (if specified) and year of manufacture.
+    Week(Option<u8>, u16),
+    /// Year the model was first released.
+    ModelYear(u16),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Input {
+    pub digital: bool,
+    /// Bits per color of a digital input.
+    pub bit_depth: Option<u8>,
+    /// Interface of a digital input.
+    pub interface: Option<&'static str>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ScreenSize {
+    /// Width and height in cm.
+    Size(u8, u8),
+    /// Aspect ratio of width to height of a projector.
+    AspectRatio(f64),
+    Undefined,
+}
+
+/// CIE 1931 xy coordinates of the primaries and the white point.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Chromaticity {
+    pub red: [f64; 2],
+    pub green: [f64; 2],
+    pub blue: [f64; 2],
+    pub white: [f64; 2],
+}
+
+/// Resolution and refresh rate in Hz of an established or standard timing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StandardTiming {
+    pub width: u16,
+    pub height: u16,
+    pub refresh: u8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DetailedTiming {
+    /// Pixel clock in kHz.
+    pub pixel_clock: u32,
+    /// Active pixels, front porch, sync width and blanking of the lines.
+    pub horizontal: [u16; 4],
+    /// Active lines, front porch, sync width and blanking of a frame.
+    pub vertical: [u16; 4],
+    /// Width and height of the image in mm.
+    pub size: [u16; 2],
+    pub interlaced: bool,
+}
+
+/// Display range limits, each as minimum and maximum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RangeLimits {
+    /// Vertical rate in Hz.
+    pub vertical: [u16; 2],
+    /// Horizontal rate in kHz.
+    pub horizontal: [u16; 2],
+    /// Maximum pixel clock in MHz.
+    pub max_pixel_clock: Option<u16>,
+}
+
+impl Edid {
+    pub fn parse(data: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let Some(block) = data.get(..BLOCK_LEN) else {
+            return Err(format!("EDID too short: {} bytes", data.len()).into());
+        };
+        if block[..HEADER.len()] != HEADER {
+            return Err("Invalid EDID header".into());
+        }
+        check_sum(block);
+        let version = [block[0x12], block[0x13]];
+        let mut edid = Self {
+            version,
+            manufacturer: parse_manufacturer([block[0x08], block[0x09]]),
+            product_code: u16::from_le_bytes([block[0x0A], block[0x0B]]),
+            serial_number: u32::from_le_bytes([block[0x0C], block[0x0D], block[0x0E], block[0x0F]]),
+            manufactured: parse_manufactured(block[0x10], block[0x11]),
+            input: parse_input(block[0x14], version),
+            size: parse_screen_size(block[0x15], block[0x16]),
+            gamma: (block[0x17] != 0xFF).then(|| (f64::from(block[0x17]) + 100.) / 100.),
+            chromaticity: parse_chromaticity(&block[0x19..0x23]),
+            established_timings: parse_established_timings([block[0x23], block[0x24], block[0x25]]),
+            standard_timings: block[0x26..0x36]
+                .chunks_exact(2)
+                .filter_map(|b| parse_standard_timing([b[0], b[1]], version))
+                .collect(),
+            detailed_timings: Vec::new(),
+            name: None,
+            serial: None,
+            texts: Vec::new(),
+            range_limits: None,
+            extensions: Vec::new(),
+        };
+        for desc in block[DESCRIPTORS_OFFSET..0x7E].chunks_exact(DESCRIPTOR_LEN) {
+            if desc[0..2] != [0, 0] {
+                edid.detailed_timings.push(parse_detailed_timing(desc));
+                continue;
+            }
+            match desc[3] {
+                TAG_SERIAL => edid.serial = Some(parse_string(desc)),
+                TAG_TEXT => edid.texts.push(parse_string(desc)),
+                TAG_RANGE_LIMITS => edid.range_limits = Some(parse_range_limits(desc)),
+                TAG_NAME => edid.name = Some(parse_string(desc)),
+                _ => (),
+            }
+        }
+        let count = usize::from(block[0x7E]);
+        let blocks = data[BLOCK_LEN..].chunks_exact(BLOCK_LEN);
+        if blocks.len() < count {
+            warn!("EDID has {} of {count} extension blocks", blocks.len());
+        }
+        edid.extensions = blocks
+            .take(count)
+            .map(|block| {
+                check_sum(block);
+                match block[0] {
+                    cta::TAG => Extension::Cta(Cta::parse(block)),
+                    displayid::TAG => Extension::DisplayId(DisplayId::parse(block)),
+                    tag => Extension::Other(tag),
+                }
+            })
+            .collect();
+        Ok(edid)
+    }
+
+    pub fn cta(&self) -> impl Iterator<Item = &Cta> {
+        self.extensions.iter().filter_map(|ext| match ext {
+            Extension::Cta(cta) => Some(cta),
+            _ => None,
+        })
+    }
+
+    pub fn displayid(&self) -> impl Iterator<Item = &DisplayId> {
+        self.extensions.iter().filter_map(|ext| match ext {
+            Extension::DisplayId(displayid) => Some(displayid),
+            _ => None,
+        })
+    }
+
+    /// Detailed timings of the base block followed by those of the extension
+    /// blocks.
+    pub fn all_detailed_timings(&self) -> impl Iterator<Item = &DetailedTiming> {
+        self.detailed_timings.iter().chain(self.extensions.iter().flat_map(|ext| match ext {
+            Extension::Cta(cta) => cta.detailed_timings.as_slice(),
+            Extension::DisplayId(displayid) => displayid.detailed_timings.as_slice(),
+            Extension::Other(_) => &[],
+        }))
+    }
+
+    /// Identifies the monitor by its manufacturer ID, product code and serial
+    /// number.
+    pub fn monitor_id(&self) -> String {
+        format!("{}-{:04X}-{:08X}", self.manufacturer, self.product_code, self.serial_number)
+    }
+
+    /// Name of the manufacturer if its PNP ID is known.
+    pub fn manufacturer_name(&self) -> Option<&'static str> { pnp::vendor(&self.manufacturer) }
+}
+
+impl DetailedTiming {
+    /// Refresh rate in Hz.
+    pub fn refresh(&self) -> f64 {
+        let total = f64::from(self.horizontal[0] + self.horizontal[3])
+            * f64::from(self.vertical[0] + self.vertical[3]);
+        if total > 0. {
+            f64::from(self.pixel_clock) * 1000. / total
+        } else {
+            0.
+        }
+    }
+}
+
+impl Display for DetailedTiming {
+    /// Formats the timings like a modeline of xrandr.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let [h_active, h_front, h_sync, h_blank] = self.horizontal;
+        let [v_active, v_front, v_sync, v_blank] = self.vertical;
+        write!(
+            f,
+            "{:.2} {h_active} {} {} {} {v_active} {} {} {}{}",
+            f64::from(self.pixel_clock) / 1000.,
+            h_active + h_front,
+            h_active + h_front + h_sync,
+            h_active + h_blank,
+            v_active + v_front,
+            v_active + v_front + v_sync,
+            v_active + v_blank,
+            if self.interlaced { " Interlace" } else { "" }
+        )
+    }
+}
+
+fn check_sum(block: &[u8]) {
+    if block.iter().fold(0_u8, |sum, &b| sum.wrapping_add(b)) != 0 {
+        warn!("EDID block checksum mismatch");
+    }
+}
+
+/// Decodes three 5 bit letters where 1 is 'A'.
+fn parse_manufacturer(bytes: [u8; 2]) -> String {
+    let id = u16::from_be_bytes(bytes);
+    [10, 5, 0]
+        .map(|shift| char::from(b'@' + u8::try_from((id >> shift) & 0x1F).unwrap_or_default()))
+        .iter()
+        .collect()
+}
+
+fn parse_manufactured(week: u8, year: u8) -> Manufactured {
+    let year = 1990 + u16::from(year);
+    match week {
+        0xFF => Manufactured::ModelYear(year),
+        0 => Manufactured::Week(None, year),
+        w => Manufactured::Week(Some(w), year),
+    }
+}
+
+fn parse_input(input: u8, version: [u8; 2]) -> Input {
+    let digital = input & 0x80 != 0;
+    if !digital || version < [1, 4] {
+        return Input { digital, bit_depth: None, interface: None };
+    }
+    Input {
+        digital,
+        bit_depth: match (input >> 4) & 0x07 {
+            depth @ 1..=6 => Some(4 + 2 * depth),
+            _ => None,
+        },
+        interface: match input & 0x0F {
+            1 => Some("DVI"),
+            2 => Some("HDMI-a"),
+            3 => Some("HDMI-b"),
+            4 => Some("MDDI"),
+            5 => Some("DisplayPort"),
+            _ => None,
+        },
+    }
+}
+
+fn parse_screen_size(width: u8, height: u8) -> ScreenSize {
+    match (width, height) {
+        (0, 0) => ScreenSize::Undefined,
+        (w, 0) => ScreenSize::AspectRatio((f64::from(w) + 99.) / 100.),
+        (0, h) => ScreenSize::AspectRatio(100. / (f64::from(h) + 99.)),
+        (w, h) => ScreenSize::Size(w, h),
+    }
+}
+
+/// Decodes the 10 bit coordinates from the bytes 0x19 to 0x22.
+fn parse_chromaticity(bytes: &[u8]) -> Chromaticity {
+    let low = u16::from_be_bytes([bytes[0], bytes[1]]);
+    let coord = |i: usize| {
+        let low = (low >> (14 - 2 * i)) & 0x03;
+        f64::from((u16::from(bytes[2 + i]) << 2) | low) / 1024.
+    };
+    Chromaticity {
+        red: [coord(0), coord(1)],
+        green: [coord(2), coord(3)],
+        blue: [coord(4), coord(5)],
+        white: [coord(6), coord(7)],
+    }
+}
+
+fn parse_established_timings(bytes: [u8; 3]) -> Vec<StandardTiming> {
+    let bits = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+    (0..)
+        .zip(ESTABLISHED_TIMINGS)
+        .filter(|(i, _)| bits & (1 << (23 - i)) != 0)
+        .map(|(_, (width, height, refresh))| StandardTiming { width, height, refresh })
+        .collect()
+}
+
+fn parse_standard_timing(bytes: [u8; 2], version: [u8; 2]) -> Option<StandardTiming> {
+    if bytes == [0x01, 0x01] || bytes[0] == 0 {
+        return None;
+    }
+    let width = (u16::from(bytes[0]) + 31) * 8;
+    let height = match bytes[1] >> 6 {
+        0 if version < [1, 3] => width,
+        0 => width * 10 / 16,
+        1 => width * 3 / 4,
+        2 => width * 4 / 5,
+        _ => width * 9 / 16,
+    };
+    Some(StandardTiming { width, height, refresh: (bytes[1] & 0x3F) + 60 })
+}
+
+fn parse_detailed_timing(desc: &[u8]) -> DetailedTiming {
+    let value = |low: u8, high: u8| u16::from(low) | (u16::from(high) << 8);
+    DetailedTiming {
+        pixel_clock: u32::from(u16::from_le_bytes([desc[0], desc[1]])) * 10,
+        horizontal: [
+            value(desc[2], desc[4] >> 4),
+            value(desc[8], desc[11] >> 6),
+            value(desc[9], (desc[11] >> 4) & 0x03),
+            value(desc[3], desc[4] & 0x0F),
+        ],
+        vertical: [
+            value(desc[5], desc[7] >> 4),
+            (u16::from(desc[10] >> 4)) | (u16::from((desc[11] >> 2) & 0x03) << 4),
+            (u16::from(desc[10] & 0x0F)) | (u16::from(desc[11] & 0x03) << 4),
+            value(desc[6], desc[7] & 0x0F),
+        ],
+        size: [value(desc[12], desc[14] >> 4), value(desc[13], desc[14] & 0x0F)],
+        interlaced: desc[17] & 0x80 != 0,
+    }
+}
+
+/// Decodes the text of a display descriptor, which ends with a line feed
+/// and is padded with spaces.
+fn parse_string(desc: &[u8]) -> String {
+    let text = &desc[5..];
+    let end = text.iter().position(|&b| b == b'\n').unwrap_or(text.len());
+    String::from_utf8_lossy(&text[..end]).trim_end().to_owned()
+}
+
+fn parse_range_limits(desc: &[u8]) -> RangeLimits {
+    // EDID 1.4 adds 255 to rates whose offset flag is set
+    let flags = desc[4];
+    let rate = |value: u8, flag: u8| u16::from(value) + if flags & flag != 0 { 255 } else { 0 };
+    RangeLimits {
+        vertical: [rate(desc[5], 0x01), rate(desc[6], 0x02)],
+        horizontal: [rate(desc[7], 0x04), rate(desc[8], 0x08)],
+        max_pixel_clock: (desc[9] != 0 && desc[9] != 0xFF).then(|| u16::from(desc[9]) * 10),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Base block of a 1920x1080 monitor with a 360 Hz maximum refresh rate,
+    /// which needs the rate offsets of EDID 1.4 in its range limits.
+    const EDID_360HZ: &str = "
+        00 ff ff ff ff ff ff 00 06 b3 19 28 45 23 01 00
+        0c 1f 01 04 a5 36 1e 78 3a ee 91 a3 54 4c 99 26
+        0f 50 54 21 08 00 d1 c0 81 80 01 01 01 01 01 01
+        01 01 01 01 01 01 02 3a 80 18 71 38 2d 40 58 2c
+        45 00 20 2f 21 00 00 1e 00 00 00 fd 0a 30 69 1e
+        9b 58 01 0a 20 20 20 20 20 20 00 00 00 fc 00 56
+        47 32 35 39 51 4d 0a 20 20 20 20 20 00 00 00 ff
+        00 4d 39 4c 4d 51 53 31 32 33 34 35 36 0a 00 8e
+    ";
+
+    /// Reads bytes from a hex dump as written by `edid-decode`.
+    pub fn from_hex(hex: &str) -> Vec<u8> {
+        hex.split_whitespace().map(|b| u8::from_str_radix(b, 16).unwrap()).collect()
+    }
+
+    #[test]
+    fn parse_base_block() {
+        let edid = Edid::parse(&from_hex(EDID_360HZ)).unwrap();
+        assert_eq!(edid.version, [1, 4]);
+        assert_eq!(edid.manufacturer, "AUS");
+        assert_eq!(edid.monitor_id(), "AUS-2819-00012345");
+        assert_eq!(edid.manufactured, Manufactured::Week(Some(12), 2021));
+        assert_eq!(
+            edid.input,
+            Input { digital: true, bit_depth: Some(8), interface: Some("DisplayPort") }
+        );
+        assert_eq!(edid.size, ScreenSize::Size(54, 30));
+        assert_eq!(edid.gamma, Some(2.2));
+        let [x, y] = edid.chromaticity.red;
+        assert!((x - 0.6396).abs() < 1e-4 && (y - 0.3301).abs() < 1e-4);
+        assert_eq!(
+            edid.established_timings,
+            [
+                StandardTiming { width: 640, height: 480, refresh: 60 },
+                StandardTiming { width: 800, height: 600, refresh: 60 },
+                StandardTiming { width: 1024, height: 768, refresh: 60 },
+            ]
+        );
+        assert_eq!(
+            edid.standard_timings,
+            [
+                StandardTiming { width: 1920, height: 1080, refresh: 60 },
+                StandardTiming { width: 1280, height: 1024, refresh: 60 },
+            ]
+        );
+        assert_eq!(
+            edid.detailed_timings,
+            [DetailedTiming {
+                pixel_clock: 148_500,
+                horizontal: [1920, 88, 44, 280],
+                vertical: [1080, 4, 5, 45],
+                size: [544, 303],
+                interlaced: false,
+            }]
+        );
+        assert_eq!(
+            edid.detailed_timings[0].to_string(),
+            "148.50 1920 2008 2052 2200 1080 1084 1089 1125"
+        );
+        assert_eq!(edid.name.as_deref(), Some("VG259QM"));
+        assert_eq!(edid.serial.as_deref(), Some("M9LMQS123456"));
+        assert!(edid.extensions.is_empty());
+    }
+
+    #[test]
+    fn parse_range_limit_offsets() {
+        let edid = Edid::parse(&from_hex(EDID_360HZ)).unwrap();
+        assert_eq!(
+            edid.range_limits,
+            Some(RangeLimits {
+                vertical: [48, 360],
+                horizontal: [30, 410],
+                max_pixel_clock: Some(880)
+            })
+        );
+    }
+
+    #[test]
+    fn reject_invalid_header() {
+        let mut data = from_hex(EDID_360HZ);
+        data[0] = 0xFF;
+        assert!(Edid::parse(&data).is_err());
+        assert!(Edid::parse(&data[..64]).is_err());
+    }
+}
@@ -0,0 +1,77 @@
+/// Names of common display manufacturers by PNP ID as registered with UEFI,
+/// sorted by ID.
+const VENDORS: [(&str, &str); 68] = [
+    ("AAC", "AcerView"),
+    ("ACI", "Ancor Communications Inc"),
+    ("ACR", "Acer Technologies"),
+    ("AOC", "AOC"),
+    ("APP", "Apple Computer Inc"),
+    ("AUO", "AU Optronics"),
+    ("AUS", "ASUSTek COMPUTER INC"),
+    ("BNQ", "BenQ Corporation"),
+    ("BOE", "BOE"),
+    ("CMN", "Chimei Innolux Corporation"),
+    ("CMO", "Chi Mei Optoelectronics corp."),
+    ("CPQ", "Compaq Computer Company"),
+    ("DEL", "Dell Inc."),
+    ("DON", "DENON, Ltd."),
+    ("DWE", "Daewoo Electronics Company Ltd"),
+    ("ELO", "Elo TouchSystems Inc"),
+    ("ENC", "Eizo Nanao Corporation"),
+    ("EPI", "Envision Peripherals, Inc"),
+    ("FNI", "Funai Electric Co., Ltd."),
+    ("FUS", "Fujitsu Siemens Computers GmbH"),
+    ("GBT", "GIGA-BYTE TECHNOLOGY CO., LTD."),
+    ("GGL", "Google Inc."),
+    ("GSM", "Goldstar Company Ltd"),
+    ("GWY", "Gateway 2000"),
+    ("HEC", "Hisense Electric Co., Ltd."),
+    ("HIT", "Hitachi America Ltd"),
+    ("HPN", "HP Inc."),
+    ("HRE", "Qingdao Haier Electronics Co., Ltd."),
+    ("HSD", "HannStar Display Corp"),
+    ("HTC", "Hitachi Ltd"),
+    ("HWP", "Hewlett Packard"),
+    ("IBM", "IBM Brasil"),
+    ("IFS", "InFocus Corporation"),
+    ("INL", "InnoLux Display Corporation"),
+    ("IQT", "IMAGEQUEST Co., Ltd"),
+    ("IVM", "Iiyama North America"),
+    ("KDS", "KDS USA"),
+    ("LEN", "Lenovo Group Limited"),
+    ("LGD", "LG Display"),
+    ("LPL", "LG Philips"),
+    ("MAG", "MAG InnoVision"),
+    ("MEI", "Panasonic Industry Company"),
+    ("MTX", "Matrox"),
+    ("NEC", "NEC Corporation"),
+    ("NOK", "Nokia Display Products"),
+    ("NVD", "Nvidia"),
+    ("ONK", "ONKYO Corporation"),
+    ("OQI", "Oksori Company Ltd"),
+    ("OTM", "Optoma Corporation"),
+    ("PGS", "Princeton Graphic Systems"),
+    ("PHL", "Philips Consumer Electronics Company"),
+    ("PIO", "Pioneer Electronic Corporation"),
+    ("PNR", "Planar Systems, Inc."),
+    ("QDS", "Quanta Display Inc."),
+    ("RHT", "Red Hat, Inc."),
+    ("SAM", "Samsung Electric Company"),
+    ("SDC", "Samsung Display Corp"),
+    ("SEC", "Seiko Epson Corporation"),
+    ("SHP", "Sharp Corporation"),
+    ("SNY", "Sony"),
+    ("SPT", "Sceptre Tech Inc"),
+    ("TMX", "Tianma Microelectronics Ltd."),
+    ("TOS", "Toshiba Corporation"),
+    ("TSB", "Toshiba America Info Systems Inc"),
+    ("VIZ", "VIZIO, Inc"),
+    ("VSC", "ViewSonic Corporation"),
+    ("WAC", "Wacom Tech"),
+    ("YMH", "Yamaha Corporation"),
+];
+
+/// Name of the manufacturer with the PNP ID `id`.
+pub fn vendor(id: &str) -> Option<&'static str> {
+    VENDORS.binary_search_by(|(v, _)| v.cmp(&id)).ok().map(|i| VENDORS[i].1)
+}
@@ -0,0 +1,307 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use glib::user_config_dir;
+use gtk::glib;
+use log::{info, warn};
+
+use crate::app::APP_NAME;
+use crate::x11::gamma::Ramps;
+
+const ICC_PROFILES_FILE: &str = "icc_profiles.toml";
+
+const HEADER_LEN: usize = 128;
+const TAG_LEN: usize = 12;
+
+/// ICC profiles assigned to monitors by monitor ID.
+#[derive(Default)]
+pub struct IccProfiles {
+    profiles: BTreeMap<String, PathBuf>,
+    /// Why the file couldn't be loaded, which keeps it from being overwritten.
+    error: Option<String>,
+}
+
+impl IccProfiles {
+    pub fn load() -> Self {
+        let mut profiles = Self::default();
+        let path = Self::path();
+        if !path.exists() {
+            info!("No ICC profiles assigned");
+            return profiles;
+        }
+        match fs::read_to_string(&path) {
+            Ok(p) => match toml::from_str(&p) {
+                Ok(p) => profiles.profiles = p,
+                Err(e) => profiles.error = Some(format!("Failed to parse {}\n{e}", path.display())),
+            },
+            Err(e) => profiles.error = Some(format!("Failed to read {}: {e}", path.display())),
+        }
+        if let Some(e) = &profiles.error {
+            warn!("{e}");
+        }
+        profiles
+    }
+
+    /// Saves the assignments unless loading them failed, in which case the
+    /// file is kept until it is fixed or removed.
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        if let Some(e) = &self.error {
+            return Err(format!("{e}\nFix or remove the file to assign ICC profiles again.").into());
+        }
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string(&self.profiles)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, monitor: &str) -> Option<&PathBuf> { self.profiles.get(monitor) }
+
+    /// Assigns `path` to `monitor` or removes its assignment.
+    pub fn set(&mut self, monitor: &str, path: Option<PathBuf>) {
+        match path {
+            Some(path) => self.profiles.insert(monitor.to_owned(), path),
+            None => self.profiles.remove(monitor),
+        };
+    }
+
+    /// Paths of all assigned profiles.
+    pub fn paths(&self) -> impl Iterator<Item = &PathBuf> { self.profiles.values() }
+
+    fn path() -> PathBuf { user_config_dir().join(APP_NAME).join(ICC_PROFILES_FILE) }
+}
+
+/// Video card gamma table (`vcgt`) of an ICC profile with values from 0 to 1.
+enum Vcgt {
+    Table([Vec<f64>; 3]),
+    /// Gamma, minimum and maximum per channel.
+    Formula([[f64; 3]; 3]),
+}
+
+impl Vcgt {
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_precision_loss)]
+    #[allow(clippy::cast_sign_loss)]
+    fn ramps(&self, size: u16) -> Ramps {
+        let last = f64::from(size.saturating_sub(1).max(1));
+        let ramp = |value: &dyn Fn(f64) -> f64| -> Vec<u16> {
+            (0..size)
+                .map(|i| {
+                    let v = value(f64::from(i) / last);
+                    (v.clamp(0., 1.) * f64::from(u16::MAX)).round() as u16
+                })
+                .collect()
+        };
+        match self {
+            Vcgt::Table(table) => table.each_ref().map(|t| {
+                ramp(&|x| {
+                    // Linear interpolation between the two nearest entries
+                    let pos = x * (t.len() - 1) as f64;
+                    let i = (pos.floor() as usize).min(t.len() - 1);
+                    let next = t[(i + 1).min(t.len() - 1)];
+                    t[i] + (next - t[i]) * (pos - i as f64)
+                })
+            }),
+            Vcgt::Formula(formula) => {
+                formula.map(|[gamma, min, max]| ramp(&|x| min + (max - min) * x.powf(gamma)))
+            }
+        }
+    }
+}
+
+/// Reads the ICC profile at `path` and returns its calibration curves
+/// resampled to `size` entries per channel. Profiles without a `vcgt` tag
+/// don't change calibration and give `None`.
+pub fn read_calibration(path: &Path, size: u16) -> Result<Option<Ramps>, Box<dyn Error>> {
+    let data = fs::read(path)?;
+    Ok(parse_vcgt(&data)?.map(|vcgt| vcgt.ramps(size)))
+}
+
+/// Whether the ICC profile `current` announced to color managed applications
+/// may be removed when the primary output has none. Profiles are only removed
+/// if some output has one `assigned` or if it is one of the `known` profiles,
+/// so that profiles announced by other tools are kept.
+pub fn is_removable(current: Option<&[u8]>, assigned: bool, known: &[Vec<u8>]) -> bool {
+    current.is_some_and(|current| assigned || known.iter().any(|k| k == current))
+}
+
+/// Reads the ICC profile at `path` checking it is one.
+pub fn read_profile(path: &Path) -> Result<Vec<u8>, Box<dyn Error>> {
+    let data = fs::read(path)?;
+    check_header(&data)?;
+    Ok(data)
+}
+
+fn check_header(data: &[u8]) -> Result<(), Box<dyn Error>> {
+    if data.len() < HEADER_LEN + 4 || data[36..40] != *b"acsp" {
+        return Err("Not an ICC profile".into());
+    }
+    Ok(())
+}
+
+fn parse_vcgt(data: &[u8]) -> Result<Option<Vcgt>, Box<dyn Error>> {
+    check_header(data)?;
+    let invalid = || "Invalid vcgt tag";
+    let count = usize::try_from(read_u32(data, HEADER_LEN).ok_or(invalid())?)?;
+    // Only as many entries as fit into the data are read from the tag table
+    let Some((offset, len)) = data[HEADER_LEN + 4..]
+        .chunks_exact(TAG_LEN)
+        .take(count)
+        .find(|entry| entry[..4] == *b"vcgt")
+        .and_then(|entry| Some((read_u32(entry, 4)?, read_u32(entry, 8)?)))
+    else {
+        return Ok(None);
+    };
+    let start = usize::try_from(offset)?;
+    let end = start.checked_add(usize::try_from(len)?).ok_or(invalid())?;
+    let tag = data.get(start..end).ok_or(invalid())?;
+    if !tag.starts_with(b"vcgt") {
+        return Err(invalid().into());
+    }
+    match read_u32(tag, 8).ok_or(invalid())? {
+        0 => {
+            let channels = usize::from(read_u16(tag, 12).ok_or(invalid())?);
+            let entries = usize::from(read_u16(tag, 14).ok_or(invalid())?);
+            let entry_size = usize::from(read_u16(tag, 16).ok_or(invalid())?);
+            if !matches!(channels, 1 | 3) || entries < 2 || !matches!(entry_size, 1 | 2) {
+                return Err(invalid().into());
+            }
+            let values = tag.get(18..18 + channels * entries * entry_size).ok_or(invalid())?;
+            let values = if entry_size == 1 {
+                values.iter().map(|&v| f64::from(v) / f64::from(u8::MAX)).collect::<Vec<_>>()
+            } else {
+                values
+                    .chunks_exact(2)
+                    .map(|c| f64::from(u16::from_be_bytes([c[0], c[1]])) / f64::from(u16::MAX))
+                    .collect()
+            };
+            let channel = |c: usize| values[c * entries..(c + 1) * entries].to_vec();
+            Ok(Some(Vcgt::Table(if channels == 1 {
+                [channel(0), channel(0), channel(0)]
+            } else {
+                [channel(0), channel(1), channel(2)]
+            })))
+        }
+        1 => {
+            // Signed 15.16 fixed point numbers
+            let fixed = |i: usize| {
+                tag.get(12 + 4 * i..16 + 4 * i)
+                    .map(|b| f64::from(i32::from_be_bytes([b[0], b[1], b[2], b[3]])) / 65536.)
+            };
+            let mut formula = [[0.; 3]; 3];
+            for (c, channel) in formula.iter_mut().enumerate() {
+                for (j, value) in channel.iter_mut().enumerate() {
+                    *value = fixed(3 * c + j).ok_or(invalid())?;
+                }
+            }
+            Ok(Some(Vcgt::Formula(formula)))
+        }
+        _ => Err(invalid().into()),
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds an ICC profile whose tag table lists `tags` followed by their
+    /// data, claiming `count` tags.
+    fn profile(tags: &[(&[u8; 4], Vec<u8>)], count: u32) -> Vec<u8> {
+        let mut data = vec![0; HEADER_LEN];
+        data[36..40].copy_from_slice(b"acsp");
+        data.extend(count.to_be_bytes());
+        let mut offset = HEADER_LEN + 4 + tags.len() * TAG_LEN;
+        for (signature, tag) in tags {
+            data.extend(*signature);
+            data.extend(u32::try_from(offset).unwrap().to_be_bytes());
+            data.extend(u32::try_from(tag.len()).unwrap().to_be_bytes());
+            offset += tag.len();
+        }
+        for (_, tag) in tags {
+            data.extend(tag);
+        }
+        data
+    }
+
+    fn vcgt(kind: u32, values: &[u8]) -> Vec<u8> {
+        [b"vcgt".as_slice(), &[0; 4], &kind.to_be_bytes(), values].concat()
+    }
+
+    fn table_vcgt() -> Vec<u8> {
+        // 3 channels of 3 entries of 2 bytes
+        let header = [0, 3, 0, 3, 0, 2];
+        let values: [u16; 9] = [0, 0x8000, 0xFFFF, 0, 0x4000, 0x8000, 0xFFFF, 0xFFFF, 0xFFFF];
+        vcgt(0, &[header.as_slice(), &values.map(u16::to_be_bytes).concat()].concat())
+    }
+
+    #[test]
+    fn read_table_vcgt() {
+        let data = profile(&[(b"desc", vec![0; 12]), (b"vcgt", table_vcgt())], 2);
+        let ramps = parse_vcgt(&data).unwrap().unwrap().ramps(5);
+        assert_eq!(
+            ramps,
+            [
+                vec![0, 16384, 32768, 49152, 65535],
+                vec![0, 8192, 16384, 24576, 32768],
+                vec![65535; 5],
+            ]
+        );
+    }
+
+    #[test]
+    fn read_formula_vcgt() {
+        // Gamma, minimum and maximum per channel as 15.16 fixed point numbers
+        let formula: [u32; 9] =
+            [0x1_0000, 0, 0x1_0000, 0x2_0000, 0, 0x1_0000, 0x1_0000, 0x8000, 0x1_0000];
+        let data = profile(&[(b"vcgt", vcgt(1, &formula.map(u32::to_be_bytes).concat()))], 1);
+        let ramps = parse_vcgt(&data).unwrap().unwrap().ramps(3);
+        assert_eq!(
+            ramps,
+            [vec![0, 32768, 65535], vec![0, 16384, 65535], vec![32768, 49151, 65535]]
+        );
+    }
+
+    #[test]
+    fn read_profile_without_vcgt() {
+        let data = profile(&[(b"desc", vec![0; 12])], 1);
+        assert!(parse_vcgt(&data).unwrap().is_none());
+        // A corrupt tag count is limited to the tags in the data
+        let data = profile(&[(b"desc", vec![0; 12])], u32::MAX);
+        assert!(parse_vcgt(&data).unwrap().is_none());
+    }
+
+    #[test]
+    fn reject_invalid_data() {
+        assert!(parse_vcgt(&[0; HEADER_LEN]).is_err());
+        let mut data = profile(&[(b"vcgt", table_vcgt())], 1);
+        data.truncate(data.len() - 1);
+        assert!(parse_vcgt(&data).is_err());
+        let mut data = profile(&[(b"vcgt", table_vcgt())], 1);
+        data[HEADER_LEN + 8..HEADER_LEN + 12].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(parse_vcgt(&data).is_err());
+    }
+
+    #[test]
+    fn keep_foreign_profile() {
+        let ours = profile(&[(b"vcgt", table_vcgt())], 1);
+        let foreign = profile(&[(b"desc", vec![0; 12])], 1);
+        let known = [ours.clone()];
+        // Announced by another tool while rrandr assigns no profiles
+        assert!(!is_removable(Some(foreign.as_slice()), false, &known));
+        assert!(!is_removable(None, true, &known));
+        // Announced by rrandr earlier or profiles are assigned
+        assert!(is_removable(Some(ours.as_slice()), false, &known));
+        assert!(is_removable(Some(foreign.as_slice()), true, &known));
+    }
+}
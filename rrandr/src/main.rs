@@ -2,16 +2,28 @@
 // #![warn(clippy::restriction)]
 
 mod app;
+mod cli;
+mod custom_modes;
+mod daemon;
 mod data;
 mod draw;
+mod edid;
 mod hook;
+mod icc;
 mod math;
+mod profile;
+mod query;
+mod timing;
 mod utils;
 mod widget;
 mod window;
 mod x11;
+mod xrandr;
+
+use std::env;
 
 use app::Application;
+use cli::Command;
 use gettextrs::{bind_textdomain_codeset, bindtextdomain, setlocale, textdomain, LocaleCategory};
 use gio::resources_register_include;
 use glib::ExitCode;
@@ -26,11 +38,26 @@ fn main() -> ExitCode {
     bind_textdomain_codeset(domainname, "UTF-8").expect("bind text domain encoding");
     textdomain(domainname).expect("text domain");
 
+    let cmd = match cli::parse(env::args().skip(1)) {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            eprintln!("{err}\n\n{}", cli::USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Command::Help = cmd {
+        return cli::run(cmd);
+    }
+
     if let Err(err) = x11::randr::check() {
         log::error!("{err}");
         return ExitCode::FAILURE;
     }
 
-    resources_register_include!("rrandr.gresource").expect("resources registered");
-    Application::run()
+    if let Command::Gui = cmd {
+        resources_register_include!("rrandr.gresource").expect("resources registered");
+        Application::run()
+    } else {
+        cli::run(cmd)
+    }
 }
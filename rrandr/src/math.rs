@@ -10,7 +10,7 @@ impl Point {
     pub fn max() -> Self { Point { x: i32::MAX, y: i32::MAX } }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Rect {
     x: i16,
     y: i16,
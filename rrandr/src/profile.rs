@@ -0,0 +1,323 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use glib::user_config_dir;
+use gtk::glib;
+use gtk::prelude::ListModelExtManual;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::app::APP_NAME;
+use crate::data::enums::{Reflection, Rotation};
+use crate::data::output::Output;
+use crate::data::outputs::Outputs;
+use crate::data::transform::Transform;
+
+const PROFILES_FILE: &str = "profiles.toml";
+const LAYOUTS_FILE: &str = "layouts.toml";
+
+/// A screen layout stored by monitor identity rather than by connector name.
+#[derive(Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct Profile {
+    pub outputs: Vec<OutputProfile>,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Serialize)]
+pub struct OutputProfile {
+    pub monitor: String,
+    /// Connector the monitor was connected to, which tells identical monitors
+    /// apart.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    pub enabled: bool,
+    #[serde(default)]
+    pub primary: bool,
+    #[serde(default)]
+    pub pos: [i16; 2],
+    pub mode: Option<ModeProfile>,
+    #[serde(default = "default_transform")]
+    pub rotation: String,
+    #[serde(default = "default_transform")]
+    pub reflection: String,
+    #[serde(default = "default_scale")]
+    pub scale: [f64; 2],
+    /// Row-major matrix replacing the scale, e.g. for keystone correction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transform: Option<[[f64; 3]; 3]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Deserialize, Serialize)]
+pub struct ModeProfile {
+    pub width: u16,
+    pub height: u16,
+    pub refresh: f64,
+}
+
+fn default_transform() -> String { "normal".to_owned() }
+
+fn default_scale() -> [f64; 2] { [1., 1.] }
+
+impl Profile {
+    pub fn from_outputs(outputs: &Outputs) -> Self {
+        let outputs = outputs
+            .iter::<Output>()
+            .map(Result::unwrap)
+            .map(|o| OutputProfile {
+                monitor: o.monitor_id(),
+                output: Some(o.name()),
+                enabled: o.enabled(),
+                primary: o.primary(),
+                pos: [o.x(), o.y()],
+                mode: o.mode().map(|m| ModeProfile {
+                    width: m.width(),
+                    height: m.height(),
+                    refresh: m.refresh(),
+                }),
+                rotation: o.rotation().xrandr(),
+                reflection: o.reflection().xrandr(),
+                scale: [o.scale_x(), o.scale_y()],
+                transform: o.transform().map(|t| t.0),
+                filter: o.transform().map(|_| o.filter()),
+            })
+            .collect();
+        Self { outputs }
+    }
+
+    /// Changes `outputs` to match this profile. Outputs whose monitor isn't
+    /// part of the profile get disabled.
+    pub fn apply_to(&self, outputs: &Outputs) -> Result<(), Box<dyn Error>> {
+        let outputs = outputs.iter::<Output>().map(Result::unwrap).collect::<Vec<_>>();
+        let matched = self.match_outputs(&outputs);
+        // Disabled monitors don't need to be connected
+        if let Some(missing) = (0..self.outputs.len())
+            .filter(|i| !matched.contains(&Some(*i)))
+            .map(|i| &self.outputs[i])
+            .find(|p| p.enabled)
+        {
+            return Err(format!("Monitor {} is not connected", missing.monitor).into());
+        }
+        for (output, i) in outputs.iter().zip(matched) {
+            match i.map(|i| &self.outputs[i]) {
+                Some(p @ OutputProfile { enabled: true, mode: Some(mode), .. }) => {
+                    let Some(m) =
+                        output.modes().find_by_res(mode.width, mode.height, Some(mode.refresh))
+                    else {
+                        return Err(format!(
+                            "No mode {}x{} for output {}",
+                            mode.width,
+                            mode.height,
+                            output.name()
+                        )
+                        .into());
+                    };
+                    output.set_enabled(true);
+                    output.set_mode(Some(m));
+                    output.set_x(p.pos[0]);
+                    output.set_y(p.pos[1]);
+                    output.set_rotation(p.rotation.parse::<Rotation>()?);
+                    output.set_reflection(p.reflection.parse::<Reflection>()?);
+                    output.set_scale_xy(p.scale[0], p.scale[1]);
+                    output.set_transform(p.transform.map(Transform));
+                    if let Some(filter) = &p.filter {
+                        output.set_filter(filter.as_str());
+                    }
+                    output.set_primary(p.primary);
+                }
+                _ => output.disable(),
+            }
+        }
+        Ok(())
+    }
+
+    /// Finds the index of the entry of each output. Each entry is used once,
+    /// preferring the one saved for the same connector, so identical monitors
+    /// keep their places.
+    fn match_outputs(&self, outputs: &[Output]) -> Vec<Option<usize>> {
+        let mut used = vec![false; self.outputs.len()];
+        let mut matched = vec![None; outputs.len()];
+        for same_connector in [true, false] {
+            for (output, m) in outputs.iter().zip(&mut matched) {
+                if m.is_some() {
+                    continue;
+                }
+                *m = (0..self.outputs.len()).find(|&i| {
+                    let p = &self.outputs[i];
+                    !used[i]
+                        && p.monitor == output.monitor_id()
+                        && (!same_connector || p.output.as_ref() == Some(&output.name()))
+                });
+                if let Some(i) = *m {
+                    used[i] = true;
+                }
+            }
+        }
+        matched
+    }
+}
+
+/// Identifies the set of connected monitors independent of connector names and
+/// order. Identical monitors are told apart by their connectors.
+pub fn fingerprint(outputs: &Outputs) -> String {
+    let outputs = outputs.iter::<Output>().map(Result::unwrap).collect::<Vec<_>>();
+    let mut ids = outputs
+        .iter()
+        .map(|o| {
+            let id = o.monitor_id();
+            if outputs.iter().filter(|other| other.monitor_id() == id).count() > 1 {
+                format!("{id}@{}", o.name())
+            } else {
+                id
+            }
+        })
+        .collect::<Vec<_>>();
+    ids.sort();
+    ids.join("+")
+}
+
+/// Profiles stored in the user's configuration directory.
+pub struct Profiles {
+    file: &'static str,
+    entries: BTreeMap<String, Profile>,
+    /// Why the file couldn't be loaded, which keeps it from being overwritten.
+    error: Option<String>,
+}
+
+impl Profiles {
+    /// Profiles saved by the user by name.
+    pub fn named() -> Self { Self::load(PROFILES_FILE) }
+
+    /// Layouts remembered by the daemon by monitor set fingerprint.
+    pub fn remembered() -> Self { Self::load(LAYOUTS_FILE) }
+
+    fn load(file: &'static str) -> Self {
+        let mut profiles = Self { file, entries: BTreeMap::new(), error: None };
+        let path = profiles.path();
+        if !path.exists() {
+            info!("No profiles found in {file}");
+            return profiles;
+        }
+        match fs::read_to_string(&path) {
+            Ok(p) => match toml::from_str(&p) {
+                Ok(p) => profiles.entries = p,
+                Err(e) => profiles.error = Some(format!("Failed to parse {}\n{e}", path.display())),
+            },
+            Err(e) => profiles.error = Some(format!("Failed to read {}: {e}", path.display())),
+        }
+        if let Some(e) = &profiles.error {
+            warn!("{e}");
+        }
+        profiles
+    }
+
+    /// Error that occurred while loading the profiles. Saving is refused until
+    /// the file is fixed or removed.
+    pub fn error(&self) -> Option<&str> { self.error.as_deref() }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        if let Some(e) = &self.error {
+            return Err(format!("{e}\nFix or remove the file to save profiles again.").into());
+        }
+        let path = self.path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string(&self.entries)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Profile> { self.entries.get(name) }
+
+    pub fn insert(&mut self, name: &str, profile: Profile) {
+        self.entries.insert(name.to_owned(), profile);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Profile> { self.entries.remove(name) }
+
+    pub fn names(&self) -> impl Iterator<Item = &String> { self.entries.keys() }
+
+    fn path(&self) -> PathBuf { user_config_dir().join(APP_NAME).join(self.file) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::output::tests::output;
+    use crate::data::outputs::tests::outputs;
+
+    fn entry(monitor: &str, output: &str, x: i16) -> OutputProfile {
+        OutputProfile {
+            monitor: monitor.to_owned(),
+            output: Some(output.to_owned()),
+            enabled: true,
+            primary: false,
+            pos: [x, 0],
+            mode: Some(ModeProfile { width: 1920, height: 1080, refresh: 0. }),
+            rotation: default_transform(),
+            reflection: default_transform(),
+            scale: default_scale(),
+            transform: None,
+            filter: None,
+        }
+    }
+
+    #[test]
+    fn match_monitor_on_other_connector() {
+        let profile = Profile { outputs: vec![entry("A", "DP-1", 1920)] };
+        let model = outputs(&[output(1, "HDMI-1", "B", 1920), output(2, "DP-2", "A", 1920)]);
+        profile.apply_to(&model).unwrap();
+        assert!(!model.index(0).enabled());
+        assert!(model.index(1).enabled());
+        assert_eq!(model.index(1).x(), 1920);
+    }
+
+    #[test]
+    fn match_identical_monitors_by_connector() {
+        let profile = Profile { outputs: vec![entry("A", "DP-1", 0), entry("A", "DP-2", 1920)] };
+        let [dp2, dp1] = [output(1, "DP-2", "A", 1920), output(2, "DP-1", "A", 1920)];
+        assert_eq!(profile.match_outputs(&[dp2.clone(), dp1.clone()]), [Some(1), Some(0)]);
+
+        // Each entry is used once even if the connectors changed
+        let [hdmi1, hdmi2] = [output(3, "HDMI-1", "A", 1920), output(4, "HDMI-2", "A", 1920)];
+        assert_eq!(profile.match_outputs(&[hdmi1, hdmi2]), [Some(0), Some(1)]);
+
+        profile.apply_to(&outputs(&[dp2.clone(), dp1.clone()])).unwrap();
+        assert_eq!([dp1.x(), dp2.x()], [0, 1920]);
+    }
+
+    #[test]
+    fn fail_on_missing_monitor() {
+        let profile = Profile { outputs: vec![entry("A", "DP-1", 0), entry("B", "DP-2", 1920)] };
+        let model = outputs(&[output(1, "DP-1", "A", 1920)]);
+        let e = profile.apply_to(&model).unwrap_err();
+        assert_eq!(e.to_string(), "Monitor B is not connected");
+    }
+
+    #[test]
+    fn skip_missing_disabled_monitor() {
+        let profile = Profile {
+            outputs: vec![
+                entry("A", "DP-1", 1920),
+                OutputProfile { enabled: false, ..entry("B", "DP-2", 0) },
+            ],
+        };
+        let model = outputs(&[output(1, "DP-1", "A", 1920)]);
+        profile.apply_to(&model).unwrap();
+        assert!(model.index(0).enabled());
+        assert_eq!(model.index(0).x(), 1920);
+    }
+
+    #[test]
+    fn fingerprint_ignores_order() {
+        let [a, b] = [output(1, "DP-1", "A", 1920), output(2, "DP-2", "B", 1920)];
+        assert_eq!(fingerprint(&outputs(&[a.clone(), b.clone()])), "A+B");
+        assert_eq!(fingerprint(&outputs(&[b, a])), "A+B");
+
+        let [a1, a2] = [output(1, "DP-1", "A", 1920), output(2, "DP-2", "A", 1920)];
+        assert_eq!(fingerprint(&outputs(&[a2.clone(), a1.clone()])), "A@DP-1+A@DP-2");
+        assert_eq!(fingerprint(&outputs(&[a1, a2])), "A@DP-1+A@DP-2");
+    }
+}
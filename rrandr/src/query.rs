@@ -0,0 +1,168 @@
+use std::error::Error;
+use std::fmt::{self, Display, Write};
+
+use gtk::prelude::ListModelExtManual;
+use serde::Serialize;
+use x11rb::protocol::randr::ScreenSize;
+
+use crate::data::mode::Mode;
+use crate::data::output::Output;
+use crate::x11::randr::Randr;
+
+/// Current screen configuration as printed by `rrandr --query`.
+#[derive(Serialize)]
+pub struct Query {
+    screen: Screen,
+    outputs: Vec<OutputQuery>,
+}
+
+#[derive(Serialize)]
+struct Screen {
+    width: u16,
+    height: u16,
+    mm_width: u16,
+    mm_height: u16,
+    min_width: u16,
+    min_height: u16,
+    max_width: u16,
+    max_height: u16,
+}
+
+#[derive(Serialize)]
+struct OutputQuery {
+    name: String,
+    product_name: Option<String>,
+    enabled: bool,
+    primary: bool,
+    pos: [i16; 2],
+    mode: Option<ModeQuery>,
+    modes: Vec<ModeQuery>,
+    rotation: String,
+    reflection: String,
+    scale: [f64; 2],
+    mm_size: [u32; 2],
+}
+
+#[derive(Serialize)]
+struct ModeQuery {
+    id: u32,
+    width: u16,
+    height: u16,
+    refresh: f64,
+    flags: Vec<&'static str>,
+}
+
+impl From<&Mode> for ModeQuery {
+    fn from(mode: &Mode) -> Self {
+        Self {
+            id: mode.id(),
+            width: mode.width(),
+            height: mode.height(),
+            refresh: mode.refresh(),
+            flags: mode.flag_names(),
+        }
+    }
+}
+
+impl From<&Output> for OutputQuery {
+    fn from(output: &Output) -> Self {
+        Self {
+            name: output.name(),
+            product_name: output.product_name(),
+            enabled: output.enabled(),
+            primary: output.primary(),
+            pos: [output.x(), output.y()],
+            mode: output.mode().as_ref().map(ModeQuery::from),
+            modes: output.modes().iter::<Mode>().map(Result::unwrap).map(|m| (&m).into()).collect(),
+            rotation: output.rotation().xrandr(),
+            reflection: output.reflection().xrandr(),
+            scale: [output.scale_x(), output.scale_y()],
+            mm_size: [output.mm_width(), output.mm_height()],
+        }
+    }
+}
+
+impl Query {
+    pub fn new(randr: &Randr) -> Self {
+        let ScreenSize { width, height, mwidth, mheight } = randr.screen_size();
+        let range = randr.screen_size_range();
+        let mut outputs = randr
+            .output_model()
+            .iter::<Output>()
+            .map(Result::unwrap)
+            .map(|o| OutputQuery::from(&o))
+            .collect::<Vec<_>>();
+        outputs.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            screen: Screen {
+                width,
+                height,
+                mm_width: mwidth,
+                mm_height: mheight,
+                min_width: range.min_width,
+                min_height: range.min_height,
+                max_width: range.max_width,
+                max_height: range.max_height,
+            },
+            outputs,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = &self.screen;
+        writeln!(
+            f,
+            "Screen: {}x{} ({}x{} mm), minimum {}x{}, maximum {}x{}",
+            s.width,
+            s.height,
+            s.mm_width,
+            s.mm_height,
+            s.min_width,
+            s.min_height,
+            s.max_width,
+            s.max_height
+        )?;
+        for o in &self.outputs {
+            let mut line = o.name.clone();
+            if let Some(product_name) = &o.product_name {
+                write!(line, " ({product_name})")?;
+            }
+            line += if o.enabled { " enabled" } else { " disabled" };
+            if o.primary {
+                line += " primary";
+            }
+            if let Some(m) = &o.mode {
+                write!(
+                    line,
+                    " {}x{}+{}+{} rotate {} reflect {} scale {:.2}x{:.2}",
+                    m.width,
+                    m.height,
+                    o.pos[0],
+                    o.pos[1],
+                    o.rotation,
+                    o.reflection,
+                    o.scale[0],
+                    o.scale[1]
+                )?;
+            }
+            writeln!(f, "{line} {}x{} mm", o.mm_size[0], o.mm_size[1])?;
+            for m in &o.modes {
+                let current = if o.mode.as_ref().is_some_and(|c| c.id == m.id) { "*" } else { " " };
+                writeln!(
+                    f,
+                    "   {:>11} {:>7.2}{current} {}",
+                    format!("{}x{}", m.width, m.height),
+                    m.refresh,
+                    m.flags.join(" ")
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
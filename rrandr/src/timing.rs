@@ -0,0 +1,269 @@
+use x11rb::protocol::randr::{ModeFlag, ModeInfo};
+
+const CELL_GRAN: f64 = 8.;
+const MIN_V_PORCH: f64 = 3.;
+const MIN_VSYNC_BP: f64 = 550.;
+const HSYNC_PERCENT: f64 = 8.;
+const C_PRIME: f64 = 30.;
+const M_PRIME: f64 = 300.;
+
+const CVT_MIN_V_BPORCH: f64 = 6.;
+const CVT_CLOCK_STEP: f64 = 250.;
+const CVT_RB_MIN_VBLANK: f64 = 460.;
+const CVT_RB_H_SYNC: f64 = 32.;
+const CVT_RB_H_BLANK: f64 = 160.;
+
+const GTF_MIN_PORCH: f64 = 1.;
+const GTF_V_SYNC: f64 = 3.;
+
+/// Standard formulas to generate mode timings from resolution and refresh
+/// rate, as implemented by the `cvt` and `gtf` tools.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Timing {
+    Cvt,
+    CvtReducedBlanking,
+    Gtf,
+}
+
+impl TryFrom<u32> for Timing {
+    type Error = String;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Cvt),
+            1 => Ok(Self::CvtReducedBlanking),
+            2 => Ok(Self::Gtf),
+            x => Err(format!("Not a timing value: {x}")),
+        }
+    }
+}
+
+impl From<Timing> for u32 {
+    fn from(value: Timing) -> Self { value as u32 }
+}
+
+impl Timing {
+    /// Generates the timings of a mode and its name in the style of `cvt`,
+    /// e.g. `1920x1080_60.00`. The width must span at least one character
+    /// cell and a frame must last longer than the minimum vertical sync and
+    /// back porch.
+    pub fn gen_mode(
+        self,
+        width: u16,
+        height: u16,
+        refresh: f64,
+    ) -> Result<(ModeInfo, String), String> {
+        if f64::from(width) < CELL_GRAN || height == 0 {
+            return Err(format!("Invalid resolution: {width}x{height}"));
+        }
+        if !(refresh > 0. && 1_000_000. / refresh > MIN_VSYNC_BP) {
+            return Err(format!("Invalid refresh rate: {refresh}"));
+        }
+        let mut mode_info = match self {
+            Self::Cvt => cvt(width, height, refresh, false),
+            Self::CvtReducedBlanking => cvt(width, height, refresh, true),
+            Self::Gtf => gtf(width, height, refresh),
+        }
+        .map_err(|e| format!("Invalid mode {width}x{height} at {refresh} Hz: {e}"))?;
+        let reduced = if self == Self::CvtReducedBlanking { "R" } else { "" };
+        let name = format!("{}x{}{reduced}_{refresh:.2}", mode_info.width, mode_info.height);
+        mode_info.name_len = u16::try_from(name.len()).expect("short mode name");
+        Ok((mode_info, name))
+    }
+}
+
+/// VESA Coordinated Video Timings 1.1, progressive and without margins.
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+fn cvt(width: u16, height: u16, refresh: f64, reduced: bool) -> Result<ModeInfo, String> {
+    let hdisplay = f64::from(width - width % 8);
+    let vdisplay = f64::from(height);
+    let vsync = vsync_width(hdisplay, vdisplay);
+
+    let (hperiod, htotal, hsync_start, hsync_end, vtotal, vsync_start) = if reduced {
+        let hperiod = (1_000_000. / refresh - CVT_RB_MIN_VBLANK) / vdisplay;
+        let vbi_lines =
+            ((CVT_RB_MIN_VBLANK / hperiod).floor() + 1.).max(vsync + CVT_MIN_V_BPORCH + 3.);
+        let htotal = hdisplay + CVT_RB_H_BLANK;
+        let hsync_end = hdisplay + CVT_RB_H_BLANK / 2.;
+        (hperiod, htotal, hsync_end - CVT_RB_H_SYNC, hsync_end, vdisplay + vbi_lines, vdisplay + 3.)
+    } else {
+        let hperiod = (1_000_000. / refresh - MIN_VSYNC_BP) / (vdisplay + MIN_V_PORCH);
+        let vsync_bp = ((MIN_VSYNC_BP / hperiod).floor() + 1.).max(vsync + CVT_MIN_V_BPORCH);
+        let hblank_percent = (C_PRIME - M_PRIME * hperiod / 1000.).max(20.);
+        let mut hblank = (hdisplay * hblank_percent / (100. - hblank_percent)).floor();
+        hblank -= hblank % (2. * CELL_GRAN);
+        let htotal = hdisplay + hblank;
+        let hsync_end = hdisplay + hblank / 2.;
+        let mut hsync_start = hsync_end - (htotal * HSYNC_PERCENT / 100.).floor();
+        hsync_start += CELL_GRAN - hsync_start % CELL_GRAN;
+        let vtotal = vdisplay + vsync_bp + MIN_V_PORCH;
+        (hperiod, htotal, hsync_start, hsync_end, vtotal, vdisplay + MIN_V_PORCH)
+    };
+
+    let mut dot_clock = (htotal * 1000. / hperiod).floor();
+    dot_clock -= dot_clock % CVT_CLOCK_STEP;
+
+    Ok(ModeInfo {
+        id: 0,
+        width: to_u16(hdisplay)?,
+        height,
+        dot_clock: to_u32(dot_clock * 1000.)?,
+        hsync_start: to_u16(hsync_start)?,
+        hsync_end: to_u16(hsync_end)?,
+        htotal: to_u16(htotal)?,
+        hskew: 0,
+        vsync_start: to_u16(vsync_start)?,
+        vsync_end: to_u16(vsync_start + vsync)?,
+        vtotal: to_u16(vtotal)?,
+        name_len: 0,
+        mode_flags: if reduced {
+            ModeFlag::HSYNC_POSITIVE | ModeFlag::VSYNC_NEGATIVE
+        } else {
+            ModeFlag::HSYNC_NEGATIVE | ModeFlag::VSYNC_POSITIVE
+        },
+    })
+}
+
+/// Vertical sync width in lines encoding the aspect ratio.
+fn vsync_width(hdisplay: f64, vdisplay: f64) -> f64 {
+    let is = |w: f64, h: f64| (vdisplay * w / h - hdisplay).abs() < CELL_GRAN;
+    if is(4., 3.) {
+        4.
+    } else if is(16., 9.) {
+        5.
+    } else if is(16., 10.) {
+        6.
+    } else if is(5., 4.) || is(15., 9.) {
+        7.
+    } else {
+        10.
+    }
+}
+
+/// VESA Generalized Timing Formula, progressive and without margins.
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+fn gtf(width: u16, height: u16, refresh: f64) -> Result<ModeInfo, String> {
+    let hdisplay = (f64::from(width) / CELL_GRAN).round() * CELL_GRAN;
+    let vdisplay = f64::from(height);
+
+    let hperiod_est =
+        (1. / refresh - MIN_VSYNC_BP / 1_000_000.) / (vdisplay + GTF_MIN_PORCH) * 1_000_000.;
+    let vsync_bp = (MIN_VSYNC_BP / hperiod_est).round();
+    let vtotal = vdisplay + vsync_bp + GTF_MIN_PORCH;
+    let refresh_est = 1. / hperiod_est / vtotal * 1_000_000.;
+    let hperiod = hperiod_est / (refresh / refresh_est);
+
+    let duty_cycle = C_PRIME - M_PRIME * hperiod / 1000.;
+    let hblank =
+        (hdisplay * duty_cycle / (100. - duty_cycle) / (2. * CELL_GRAN)).round() * (2. * CELL_GRAN);
+    let htotal = hdisplay + hblank;
+    let hsync = (HSYNC_PERCENT / 100. * htotal / CELL_GRAN).round() * CELL_GRAN;
+    let hsync_start = hdisplay + hblank / 2. - hsync;
+    let vsync_start = vdisplay + GTF_MIN_PORCH;
+
+    Ok(ModeInfo {
+        id: 0,
+        width: to_u16(hdisplay)?,
+        height,
+        dot_clock: to_u32(htotal / hperiod * 1_000_000.)?,
+        hsync_start: to_u16(hsync_start)?,
+        hsync_end: to_u16(hsync_start + hsync)?,
+        htotal: to_u16(htotal)?,
+        hskew: 0,
+        vsync_start: to_u16(vsync_start)?,
+        vsync_end: to_u16(vsync_start + GTF_V_SYNC)?,
+        vtotal: to_u16(vtotal)?,
+        name_len: 0,
+        mode_flags: ModeFlag::HSYNC_NEGATIVE | ModeFlag::VSYNC_POSITIVE,
+    })
+}
+
+/// Converts a timing to the 16 bits the server stores it in, failing instead
+/// of saturating.
+#[allow(clippy::cast_possible_truncation)]
+fn to_u16(value: f64) -> Result<u16, String> {
+    u16::try_from(value as i64).map_err(|_| format!("timing {value} out of range"))
+}
+
+/// Converts a dot clock in Hz to the 32 bits the server stores it in.
+#[allow(clippy::cast_possible_truncation)]
+fn to_u32(value: f64) -> Result<u32, String> {
+    u32::try_from(value as i64).map_err(|_| format!("dot clock {value} Hz out of range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Formats the timings like the modelines printed by `cvt` and `gtf`.
+    fn modeline(timing: Timing, width: u16, height: u16, refresh: f64) -> String {
+        let (m, name) = timing.gen_mode(width, height, refresh).unwrap();
+        format!(
+            "\"{name}\" {:.2} {} {} {} {} {} {} {} {}",
+            f64::from(m.dot_clock) / 1_000_000.,
+            m.width,
+            m.hsync_start,
+            m.hsync_end,
+            m.htotal,
+            m.height,
+            m.vsync_start,
+            m.vsync_end,
+            m.vtotal
+        )
+    }
+
+    #[test]
+    fn gen_cvt_modes() {
+        assert_eq!(
+            modeline(Timing::Cvt, 1920, 1080, 60.),
+            "\"1920x1080_60.00\" 173.00 1920 2048 2248 2576 1080 1083 1088 1120"
+        );
+        assert_eq!(
+            modeline(Timing::Cvt, 1280, 720, 60.),
+            "\"1280x720_60.00\" 74.50 1280 1344 1472 1664 720 723 728 748"
+        );
+        assert_eq!(
+            modeline(Timing::Cvt, 800, 600, 60.),
+            "\"800x600_60.00\" 38.25 800 832 912 1024 600 603 607 624"
+        );
+    }
+
+    #[test]
+    fn gen_cvt_reduced_blanking_modes() {
+        assert_eq!(
+            modeline(Timing::CvtReducedBlanking, 1920, 1080, 60.),
+            "\"1920x1080R_60.00\" 138.50 1920 1968 2000 2080 1080 1083 1088 1111"
+        );
+        let (mode, _) = Timing::CvtReducedBlanking.gen_mode(1920, 1080, 60.).unwrap();
+        assert_eq!(mode.mode_flags, ModeFlag::HSYNC_POSITIVE | ModeFlag::VSYNC_NEGATIVE);
+    }
+
+    #[test]
+    fn gen_gtf_modes() {
+        assert_eq!(
+            modeline(Timing::Gtf, 1920, 1080, 60.),
+            "\"1920x1080_60.00\" 172.80 1920 2040 2248 2576 1080 1081 1084 1118"
+        );
+        assert_eq!(
+            modeline(Timing::Gtf, 1024, 768, 60.),
+            "\"1024x768_60.00\" 64.11 1024 1080 1184 1344 768 769 772 795"
+        );
+    }
+
+    #[test]
+    fn reject_invalid_modes() {
+        for timing in [Timing::Cvt, Timing::CvtReducedBlanking, Timing::Gtf] {
+            assert!(timing.gen_mode(1920, 0, 60.).is_err());
+            assert!(timing.gen_mode(4, 1080, 60.).is_err());
+            for refresh in [0., -60., f64::NAN, f64::INFINITY, 2000.] {
+                assert!(timing.gen_mode(1920, 1080, refresh).is_err());
+            }
+            // Blanking pushes the totals past 16 bits
+            assert!(timing.gen_mode(65_528, 1080, 60.).is_err());
+            assert!(timing.gen_mode(1920, 65_535, 1.).is_err());
+        }
+        assert!(Timing::try_from(3).is_err());
+    }
+}
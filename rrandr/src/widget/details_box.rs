@@ -2,11 +2,16 @@ use glib::subclass::types::ObjectSubclassIsExt;
 use glib::{closure_local, wrapper, Object, ValueDelegate};
 use gtk::prelude::ObjectExt;
 use gtk::{glib, Widget};
+use x11rb::protocol::xproto::Atom;
 
 use crate::data::output::Output;
+use crate::x11::error::ConfigError;
+use crate::x11::property::OutputProperty;
 
 mod imp {
+    use std::borrow::Cow;
     use std::cell::{Cell, RefCell};
+    use std::path::Path;
     use std::sync::OnceLock;
     use std::time::Duration;
 
@@ -19,18 +24,20 @@ mod imp {
         SignalHandlerId, SourceId,
     };
     use gtk::prelude::{
-        BoxExt, CheckButtonExt, ObjectExt, RangeExt, ScaleExt, StaticType, ToggleButtonExt,
-        WidgetExt,
+        BoxExt, ButtonExt, CastNone, CheckButtonExt, FileChooserExt, FileExt, GridExt,
+        NativeDialogExt, ObjectExt, RangeExt, ScaleExt, StaticType, ToggleButtonExt, WidgetExt,
     };
     use gtk::subclass::prelude::DerivedObjectProperties;
     use gtk::subclass::widget::{WidgetClassExt, WidgetImpl};
     use gtk::{
-        glib, Align, BinLayout, Box, DropDown, FlowBox, Orientation, PositionType, Scale,
-        SelectionMode, Separator, ToggleButton, Widget, INVALID_LIST_POSITION,
+        glib, pango, Align, BinLayout, Box, Button, DropDown, FileChooserAction, FileChooserNative,
+        FileFilter, FlowBox, Grid, Label, Orientation, PositionType, ResponseType, Scale,
+        SelectionMode, Separator, Stack, ToggleButton, Widget, INVALID_LIST_POSITION,
     };
+    use x11rb::protocol::randr::Output as OutputId;
 
     use super::Update;
-    use crate::data::enums::{Reflection, Rotation};
+    use crate::data::enums::{MirrorFit, Reflection, Rotation};
     use crate::data::mode::Mode;
     use crate::data::modes::Modes;
     use crate::data::output::Output;
@@ -40,8 +47,14 @@ mod imp {
     use crate::widget::details_child::DetailsChild;
     use crate::widget::icon_text::IconText;
     use crate::widget::mode_selector::ModeSelector;
+    use crate::widget::mode_timings::ModeTimings;
+    use crate::widget::monitor_info::MonitorInfo;
+    use crate::widget::panning_editor::PanningEditor;
     use crate::widget::position_entry::{Axis, PositionEntry};
+    use crate::widget::properties_box::PropertiesBox;
+    use crate::widget::scale_editor::ScaleEditor;
     use crate::widget::switch::Switch;
+    use crate::widget::transform_editor::TransformEditor;
     use crate::window::{PADDING, SPACING};
 
     const POS_UPDATE_DELAY: u64 = 500;
@@ -49,7 +62,19 @@ mod imp {
     const SW_ENABLED_NAME: &str = "sw_enabled";
     const DD_ROTATION_NAME: &str = "dd_rotation";
     const DD_REFLECTION_NAME: &str = "dd_reflection";
-    const SC_SCALE_NAME: &str = "sc_scale";
+    const SE_SCALE_NAME: &str = "se_scale";
+    const DD_MIRROR_NAME: &str = "dd_mirror";
+    const EX_TIMINGS_NAME: &str = "ex_timings";
+    const SC_BRIGHTNESS_NAME: &str = "sc_brightness";
+    const GR_GAMMA_NAME: &str = "gr_gamma";
+    const SC_GAMMA_BRIGHTNESS_NAME: &str = "sc_gamma_brightness";
+    const BX_ICC_PROFILE_NAME: &str = "bx_icc_profile";
+    const PE_PANNING_NAME: &str = "pe_panning";
+    const TE_TRANSFORM_NAME: &str = "te_transform";
+
+    const DETAILS_PAGE: &str = "details";
+    const PROPERTIES_PAGE: &str = "properties";
+    const INFO_PAGE: &str = "info";
 
     #[derive(Properties)]
     #[properties(wrapper_type = super::DetailsBox)]
@@ -64,12 +89,20 @@ mod imp {
         primary_changed_handler: RefCell<Option<SignalHandlerId>>,
         dd_rotation_selected_handler: RefCell<Option<SignalHandlerId>>,
         dd_reflection_selected_handler: RefCell<Option<SignalHandlerId>>,
-        scale_value_changed_handler: RefCell<Option<SignalHandlerId>>,
+        mirror_changed_handler: RefCell<Option<SignalHandlerId>>,
+        dd_mirror_selected_handler: RefCell<Option<SignalHandlerId>>,
+        brightness_value_changed_handler: RefCell<Option<SignalHandlerId>>,
+        gamma_value_changed_handlers: RefCell<[Option<SignalHandlerId>; 4]>,
+        has_backlight: Cell<bool>,
 
         pub(super) screen_max_width: Cell<u16>,
         pub(super) screen_max_height: Cell<u16>,
+        /// Reasons the configuration can't be applied with the affected
+        /// output.
+        pub(super) config_errors: RefCell<Vec<(OutputId, String)>>,
 
         root: Box,
+        errors: Label,
         fb_details: FlowBox,
         sw_enabled: Switch,
         mode_selector: ModeSelector,
@@ -77,26 +110,58 @@ mod imp {
         cb_primary: CheckButton,
         dd_rotation: DropDown,
         dd_reflection: DropDown,
-        scale: Scale,
+        scale: ScaleEditor,
+        dd_mirror: DropDown,
+        mode_timings: ModeTimings,
+        brightness: Scale,
+        gamma: [Scale; 3],
+        gamma_brightness: Scale,
+        gr_gamma: Grid,
+        icc_profile: Label,
+        icc_profile_clear: Button,
+        icc_profile_chooser: RefCell<Option<FileChooserNative>>,
+        panning: PanningEditor,
+        transform: TransformEditor,
         vsep: Separator,
         tb_advanced: ToggleButton,
+        tb_properties: ToggleButton,
+        tb_info: ToggleButton,
+        stack: Stack,
+        pub(super) properties: PropertiesBox,
+        pub(super) info: MonitorInfo,
     }
 
     impl Default for DetailsBox {
+        #[allow(clippy::too_many_lines)]
         fn default() -> Self {
-            let scale = Scale::with_range(Orientation::Horizontal, 1., 200., 1.);
-            scale.set_slider_size_fixed(true);
-            scale.set_draw_value(true);
-            scale.set_value_pos(PositionType::Right);
-            scale.set_value(100.);
-            scale.add_mark(100., PositionType::Bottom, None);
-            scale.set_flippable(true);
-            scale.set_format_value_func(|_, v| format!("{:.2}", v / 100.));
+            let brightness = Scale::with_range(Orientation::Horizontal, 1., 100., 1.);
+            brightness.set_draw_value(true);
+            brightness.set_value_pos(PositionType::Right);
+            brightness.set_format_value_func(|_, v| format!("{v:.0}\u{202F}%"));
+
+            let gamma_scale = |max| {
+                let scale = Scale::with_range(Orientation::Horizontal, 0.1, max, 0.01);
+                scale.set_digits(2);
+                scale.set_draw_value(true);
+                scale.set_value_pos(PositionType::Right);
+                scale.set_value(1.);
+                scale.add_mark(1., PositionType::Bottom, None);
+                scale.set_hexpand(true);
+                scale
+            };
 
             let tb_child = IconText::new(true);
             tb_child.set_icon_name("view-more-symbolic");
             tb_child.set_label(gettext("Advanced") + "\u{2026}");
 
+            let tb_properties_child = IconText::new(true);
+            tb_properties_child.set_icon_name("document-properties-symbolic");
+            tb_properties_child.set_label(gettext("Properties"));
+
+            let tb_info_child = IconText::new(true);
+            tb_info_child.set_icon_name("video-display-symbolic");
+            tb_info_child.set_label(gettext("Monitor"));
+
             Self {
                 output: RefCell::default(),
 
@@ -107,12 +172,23 @@ mod imp {
                 primary_changed_handler: RefCell::default(),
                 dd_rotation_selected_handler: RefCell::default(),
                 dd_reflection_selected_handler: RefCell::default(),
-                scale_value_changed_handler: RefCell::default(),
+                mirror_changed_handler: RefCell::default(),
+                dd_mirror_selected_handler: RefCell::default(),
+                brightness_value_changed_handler: RefCell::default(),
+                gamma_value_changed_handlers: RefCell::default(),
+                has_backlight: Cell::default(),
 
                 screen_max_width: Cell::default(),
                 screen_max_height: Cell::default(),
+                config_errors: RefCell::default(),
 
                 root: Box::new(Orientation::Horizontal, PADDING.into()),
+                errors: Label::builder()
+                    .visible(false)
+                    .xalign(0.)
+                    .wrap(true)
+                    .css_classes(["error"])
+                    .build(),
                 fb_details: FlowBox::builder()
                     .row_spacing(SPACING.into())
                     .column_spacing(SPACING.into())
@@ -142,7 +218,33 @@ mod imp {
                     // Translators: Horizontal and vertical reflection
                     &gettext("Both"),
                 ]),
-                scale,
+                scale: ScaleEditor::new(),
+                dd_mirror: DropDown::from_strings(&[
+                    // Translators: Stop mirroring another output
+                    &pgettext("Mirror", "Off"),
+                    // Translators: Keep the aspect ratio of the mirrored output
+                    &gettext("Letterbox"),
+                    // Translators: Fill the output ignoring the aspect ratio of the mirrored
+                    // output
+                    &gettext("Stretch"),
+                ]),
+                mode_timings: ModeTimings::new(),
+                brightness,
+                gamma: [(); 3].map(|()| gamma_scale(3.)),
+                gamma_brightness: gamma_scale(1.5),
+                gr_gamma: Grid::builder().column_spacing(SPACING.into()).width_request(250).build(),
+                icc_profile: Label::builder()
+                    .xalign(0.)
+                    .ellipsize(pango::EllipsizeMode::Middle)
+                    .max_width_chars(24)
+                    .build(),
+                icc_profile_clear: Button::builder()
+                    .icon_name("edit-clear-symbolic")
+                    .tooltip_text(gettext("Remove ICC profile"))
+                    .build(),
+                icc_profile_chooser: RefCell::default(),
+                panning: PanningEditor::new(),
+                transform: TransformEditor::new(),
                 vsep: Separator::new(Orientation::Vertical),
                 tb_advanced: ToggleButton::builder()
                     .visible(false)
@@ -150,6 +252,25 @@ mod imp {
                     .child(&tb_child)
                     .tooltip_text(gettext("Advanced") + "\u{2026}")
                     .build(),
+                tb_properties: ToggleButton::builder()
+                    .visible(false)
+                    .valign(Align::Center)
+                    .child(&tb_properties_child)
+                    .tooltip_text(gettext("Properties"))
+                    .build(),
+                tb_info: ToggleButton::builder()
+                    .visible(false)
+                    .valign(Align::Center)
+                    .child(&tb_info_child)
+                    .tooltip_text(gettext("Monitor information"))
+                    .build(),
+                stack: Stack::builder()
+                    .hhomogeneous(false)
+                    .vhomogeneous(false)
+                    .hexpand(true)
+                    .build(),
+                properties: PropertiesBox::new(),
+                info: MonitorInfo::new(),
             }
         }
     }
@@ -173,10 +294,14 @@ mod imp {
                     Signal::builder("output-changed")
                         .param_types([Output::static_type(), Update::static_type()])
                         .build(),
+                    Signal::builder("brightness-changed")
+                        .param_types([Output::static_type(), f64::static_type()])
+                        .build(),
                 ]
             })
         }
 
+        #[allow(clippy::too_many_lines)]
         fn constructed(&self) {
             self.parent_constructed();
 
@@ -220,9 +345,98 @@ mod imp {
             self.fb_details.append(&DetailsChild::new(
                 &gettext("Scale"),
                 &self.scale,
-                SC_SCALE_NAME,
+                SE_SCALE_NAME,
+            ));
+            self.fb_details.append(&DetailsChild::new(
+                // Show the area of another output scaled to fit
+                &gettext("Mirror"),
+                &self.dd_mirror,
+                DD_MIRROR_NAME,
+            ));
+            self.fb_details.append(&DetailsChild::new(
+                // Mode timings
+                &gettext("Timings"),
+                &self.mode_timings,
+                EX_TIMINGS_NAME,
+            ));
+            self.fb_details.append(&DetailsChild::new(
+                // Backlight brightness of a laptop panel
+                &gettext("Backlight"),
+                &self.brightness,
+                SC_BRIGHTNESS_NAME,
+            ));
+            self.brightness.set_width_request(200);
+            for (row, (name, scale)) in (0..)
+                .zip([gettext("Red"), gettext("Green"), gettext("Blue")].iter().zip(&self.gamma))
+            {
+                self.gr_gamma.attach(
+                    &Label::builder().label(name).xalign(0.).build(),
+                    0,
+                    row,
+                    1,
+                    1,
+                );
+                self.gr_gamma.attach(scale, 1, row, 1, 1);
+            }
+            self.fb_details.append(&DetailsChild::new(
+                // Gamma correction per color channel
+                &gettext("Gamma"),
+                &self.gr_gamma,
+                GR_GAMMA_NAME,
+            ));
+            self.fb_details.append(&DetailsChild::new(
+                // Software brightness applied to the gamma ramps
+                &gettext("Brightness"),
+                &self.gamma_brightness,
+                SC_GAMMA_BRIGHTNESS_NAME,
+            ));
+            self.gamma_brightness.set_width_request(200);
+            let bx_icc_profile = Box::new(Orientation::Horizontal, SPACING.into());
+            bx_icc_profile.append(&self.icc_profile);
+            let icc_profile_open = Button::builder()
+                .icon_name("document-open-symbolic")
+                .tooltip_text(gettext("Choose ICC profile") + "\u{2026}")
+                .build();
+            icc_profile_open.connect_clicked(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_| this.choose_icc_profile()
+            ));
+            bx_icc_profile.append(&icc_profile_open);
+            self.icc_profile_clear.connect_clicked(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_| this.update_icc_profile(None)
+            ));
+            bx_icc_profile.append(&self.icc_profile_clear);
+            self.fb_details.append(&DetailsChild::new(
+                // Color profile of the monitor with calibration curves
+                &gettext("ICC profile"),
+                &bx_icc_profile,
+                BX_ICC_PROFILE_NAME,
+            ));
+            self.fb_details.append(&DetailsChild::new(
+                // Virtual area larger than the output which it follows the pointer in
+                &gettext("Panning"),
+                &self.panning,
+                PE_PANNING_NAME,
+            ));
+            self.panning.connect_panning_changed(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |editor| this.on_panning_changed(editor)
+            ));
+            self.fb_details.append(&DetailsChild::new(
+                // Projective transformation of the output, e.g. to correct keystone distortion
+                &gettext("Transform"),
+                &self.transform,
+                TE_TRANSFORM_NAME,
+            ));
+            self.transform.connect_transform_changed(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |editor| this.on_transform_changed(editor)
             ));
-            self.scale.set_width_request(200);
 
             self.sw_enabled.connect_active_notify(clone!(
                 #[weak(rename_to = this)]
@@ -258,22 +472,70 @@ mod imp {
                     move |dd| this.on_reflection_selected(dd)
                 )),
             ));
-            self.scale_value_changed_handler.replace(Some(self.scale.connect_value_changed(
-                clone!(
+            self.scale.connect_scale_changed(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |editor| this.on_scale_changed(editor)
+            ));
+            self.brightness_value_changed_handler.replace(Some(
+                self.brightness.connect_value_changed(clone!(
+                    #[weak(rename_to = this)]
+                    self,
+                    move |s| this.on_brightness_changed(s)
+                )),
+            ));
+            let mut gamma_handlers = self.gamma_value_changed_handlers.borrow_mut();
+            for (handler, scale) in
+                gamma_handlers.iter_mut().zip(self.gamma.iter().chain([&self.gamma_brightness]))
+            {
+                handler.replace(scale.connect_value_changed(clone!(
+                    #[weak(rename_to = this)]
+                    self,
+                    move |_| this.on_gamma_changed()
+                )));
+            }
+            drop(gamma_handlers);
+            self.dd_mirror_selected_handler.replace(Some(
+                self.dd_mirror.connect_selected_item_notify(clone!(
                     #[weak(rename_to = this)]
                     self,
-                    move |s| this.on_scale_changed(s)
-                ),
-            )));
+                    move |dd| this.on_mirror_selected(dd)
+                )),
+            ));
             self.tb_advanced.connect_toggled(clone!(
                 #[weak(rename_to = this)]
                 self,
                 move |tb| this.on_advanced_toggle(tb)
             ));
+            self.tb_properties.connect_toggled(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |tb| this.on_properties_toggle(tb)
+            ));
+            self.tb_info.connect_toggled(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |tb| this.on_info_toggle(tb)
+            ));
 
-            self.root.append(&self.tb_advanced);
+            self.stack.add_named(&self.fb_details, Some(DETAILS_PAGE));
+            self.stack.add_named(&self.properties, Some(PROPERTIES_PAGE));
+            self.stack.add_named(&self.info, Some(INFO_PAGE));
+
+            let buttons = Box::builder()
+                .orientation(Orientation::Vertical)
+                .valign(Align::Center)
+                .spacing(SPACING.into())
+                .build();
+            buttons.append(&self.tb_advanced);
+            buttons.append(&self.tb_properties);
+            buttons.append(&self.tb_info);
+            self.root.append(&buttons);
             self.root.append(&self.vsep);
-            self.root.append(&self.fb_details);
+            let content = Box::new(Orientation::Vertical, SPACING.into());
+            content.append(&self.errors);
+            content.append(&self.stack);
+            self.root.append(&content);
             self.root.set_parent(&*self.obj());
         }
 
@@ -315,6 +577,7 @@ mod imp {
 
                 self.mode_selector.set_modes(Some(output.modes()));
                 self.mode_selector.set_selected_mode(output.mode());
+                self.mode_timings.set_mode(output.mode());
 
                 Self::select_pos(
                     &self.dd_rotation,
@@ -326,14 +589,21 @@ mod imp {
                     self.dd_reflection_selected_handler.borrow().as_ref(),
                     output.reflection().into(),
                 );
-                Self::set_scale(
-                    &self.scale,
-                    self.scale_value_changed_handler.borrow().as_ref(),
-                    output.scale_x() * 100.,
+                self.scale
+                    .set_scale([output.scale_x(), output.scale_y()], output.rotated_mode_size());
+                Self::select_pos(
+                    &self.dd_mirror,
+                    self.dd_mirror_selected_handler.borrow().as_ref(),
+                    Self::mirror_pos(output),
                 );
+                self.set_gamma(output.gamma(), output.brightness());
+                self.set_icc_profile(output.icc_profile().as_deref());
+                self.panning.set_panning(output.panning(), [output.width(), output.height()]);
+                self.set_transform(output);
             } else {
                 self.mode_selector.set_selected_mode(None::<Mode>);
                 self.mode_selector.set_modes(None::<Modes>);
+                self.mode_timings.set_mode(None::<Mode>);
 
                 Self::select_pos(
                     &self.dd_rotation,
@@ -345,14 +615,37 @@ mod imp {
                     self.dd_reflection_selected_handler.borrow().as_ref(),
                     INVALID_LIST_POSITION,
                 );
-                Self::set_scale(
-                    &self.scale,
-                    self.scale_value_changed_handler.borrow().as_ref(),
-                    100.,
+                self.scale.set_scale([1., 1.], [0, 0]);
+                Self::select_pos(
+                    &self.dd_mirror,
+                    self.dd_mirror_selected_handler.borrow().as_ref(),
+                    INVALID_LIST_POSITION,
                 );
+                self.set_gamma([1.; 3], 1.);
+                self.set_icc_profile(None);
+                self.panning.set_panning(None, [0, 0]);
+                self.transform.set_transform(None, "", [0, 0], [1., 1.]);
+                self.scale.set_sensitive(true);
             }
             self.output.replace(output.cloned());
             self.update_visibility();
+            self.update_errors();
+        }
+
+        /// Shows why the configuration of the current output can't be
+        /// applied.
+        pub(super) fn update_errors(&self) {
+            let errors = self.output.borrow().as_ref().map_or_else(String::new, |output| {
+                self.config_errors
+                    .borrow()
+                    .iter()
+                    .filter(|(id, _)| *id == output.id())
+                    .map(|(_, e)| e.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            });
+            self.errors.set_label(&errors);
+            self.errors.set_visible(!errors.is_empty());
         }
 
         fn connect_output_property_handlers(&self, output: &Output) {
@@ -367,13 +660,28 @@ mod imp {
             self.mode_changed_handler.replace(Some(output.connect_mode_notify(clone!(
                 #[weak(rename_to = this)]
                 self,
-                move |o| this.mode_selector.set_selected_mode(o.mode())
+                move |o| {
+                    this.mode_selector.set_selected_mode(o.mode());
+                    this.mode_timings.set_mode(o.mode());
+                }
             ))));
             self.primary_changed_handler.replace(Some(output.connect_primary_notify(clone!(
                 #[weak(rename_to = this)]
                 self,
                 move |o| this.cb_primary.set_active(o.primary())
             ))));
+            self.mirror_changed_handler.replace(Some(output.connect_mirror_notify(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |o| {
+                    Self::select_pos(
+                        &this.dd_mirror,
+                        this.dd_mirror_selected_handler.borrow().as_ref(),
+                        Self::mirror_pos(o),
+                    );
+                    this.update_visibility();
+                }
+            ))));
             self.pos_changed_handlers.replace([
                 Some(output.connect_pos_x_notify(clone!(
                     #[weak(rename_to = this)]
@@ -407,6 +715,7 @@ mod imp {
                 self.enabled_changed_handler.take(),
                 self.mode_changed_handler.take(),
                 self.primary_changed_handler.take(),
+                self.mirror_changed_handler.take(),
             ] {
                 if let (Some(output), Some(handler_id)) = (self.output.borrow().as_ref(), handler) {
                     output.disconnect(handler_id);
@@ -425,16 +734,52 @@ mod imp {
             while let Some(c) = child {
                 c.set_visible(output.as_ref().is_some_and(|o| match c.widget_name().as_str() {
                     SW_ENABLED_NAME => true,
-                    DD_ROTATION_NAME | DD_REFLECTION_NAME | SC_SCALE_NAME => {
-                        o.enabled() && self.tb_advanced.is_active()
-                    }
+                    DD_ROTATION_NAME
+                    | DD_REFLECTION_NAME
+                    | SE_SCALE_NAME
+                    | EX_TIMINGS_NAME
+                    | GR_GAMMA_NAME
+                    | SC_GAMMA_BRIGHTNESS_NAME
+                    | BX_ICC_PROFILE_NAME
+                    | PE_PANNING_NAME
+                    | TE_TRANSFORM_NAME => o.enabled() && self.tb_advanced.is_active(),
+                    DD_MIRROR_NAME => o.enabled() && o.mirror() > 0,
+                    SC_BRIGHTNESS_NAME => o.enabled() && self.has_backlight.get(),
                     _ => o.enabled(),
                 }));
                 child = c.next_sibling();
             }
             let visible = output.as_ref().is_some_and(|o| o.enabled());
-            self.vsep.set_visible(visible);
             self.tb_advanced.set_visible(visible);
+            self.tb_properties.set_visible(output.is_some());
+            self.tb_info.set_visible(output.is_some());
+            self.vsep.set_visible(output.is_some());
+        }
+
+        fn on_properties_toggle(&self, tb: &ToggleButton) {
+            if tb.is_active() {
+                self.tb_info.set_active(false);
+            }
+            self.update_page();
+        }
+
+        fn on_info_toggle(&self, tb: &ToggleButton) {
+            if tb.is_active() {
+                self.tb_properties.set_active(false);
+            }
+            self.update_page();
+        }
+
+        fn update_page(&self) {
+            let page = if self.tb_properties.is_active() {
+                PROPERTIES_PAGE
+            } else if self.tb_info.is_active() {
+                INFO_PAGE
+            } else {
+                DETAILS_PAGE
+            };
+            self.stack.set_visible_child_name(page);
+            self.tb_advanced.set_sensitive(page == DETAILS_PAGE);
         }
 
         fn on_advanced_toggle(&self, tb: &ToggleButton) {
@@ -444,9 +789,15 @@ mod imp {
             let mut child = self.fb_details.first_child();
             while let Some(c) = child {
                 match c.widget_name().as_str() {
-                    DD_ROTATION_NAME | DD_REFLECTION_NAME | SC_SCALE_NAME => {
-                        c.set_visible(tb.is_active())
-                    }
+                    DD_ROTATION_NAME
+                    | DD_REFLECTION_NAME
+                    | SE_SCALE_NAME
+                    | EX_TIMINGS_NAME
+                    | GR_GAMMA_NAME
+                    | SC_GAMMA_BRIGHTNESS_NAME
+                    | BX_ICC_PROFILE_NAME
+                    | PE_PANNING_NAME
+                    | TE_TRANSFORM_NAME => c.set_visible(tb.is_active()),
                     _ => (),
                 }
                 child = c.next_sibling();
@@ -475,6 +826,7 @@ mod imp {
                     if old_mode.width() != new_mode.width()
                         || old_mode.height() != new_mode.height()
                     {
+                        self.set_transform(output);
                         self.notify_updated(output, Update::Resolution);
                     } else if nearly_eq(old_mode.refresh(), new_mode.refresh()) {
                         self.notify_updated(output, Update::Refresh);
@@ -488,6 +840,7 @@ mod imp {
                 let rotation = Rotation::from(dd.selected());
                 if rotation != output.rotation() {
                     output.set_rotation(rotation);
+                    self.set_transform(output);
                     self.notify_updated(output, Update::Rotation);
                 }
             }
@@ -503,13 +856,144 @@ mod imp {
             }
         }
 
-        fn on_scale_changed(&self, s: &Scale) {
+        fn on_scale_changed(&self, editor: &ScaleEditor) {
             if let Some(output) = self.output.borrow().as_ref() {
-                output.set_scale(s.value() / 100.);
+                let [scale_x, scale_y] = editor.scale();
+                output.set_scale_xy(scale_x, scale_y);
+                self.set_transform(output);
                 self.notify_updated(output, Update::Scale);
             }
         }
 
+        fn on_mirror_selected(&self, dd: &DropDown) {
+            if let Some(output) = self.output.borrow().as_ref() {
+                match dd.selected() {
+                    0 => output.unmirror(),
+                    pos => output.set_mirror_fit(MirrorFit::from(pos - 1)),
+                }
+                self.set_transform(output);
+                self.notify_updated(output, Update::Mirror);
+            }
+        }
+
+        fn on_brightness_changed(&self, s: &Scale) {
+            if let Some(output) = self.output.borrow().as_ref() {
+                self.obj().emit_by_name::<()>("brightness-changed", &[output, &s.value()]);
+            }
+        }
+
+        pub(super) fn set_brightness(&self, percent: Option<f64>) {
+            self.has_backlight.set(percent.is_some());
+            if let Some(percent) = percent {
+                Self::set_scale(
+                    &self.brightness,
+                    self.brightness_value_changed_handler.borrow().as_ref(),
+                    percent,
+                );
+            }
+            self.update_visibility();
+        }
+
+        fn on_gamma_changed(&self) {
+            if let Some(output) = self.output.borrow().as_ref() {
+                output.set_gamma(self.gamma.each_ref().map(RangeExt::value));
+                output.set_brightness(self.gamma_brightness.value());
+                self.notify_updated(output, Update::Gamma);
+            }
+        }
+
+        fn set_gamma(&self, gamma: [f64; 3], brightness: f64) {
+            let handlers = self.gamma_value_changed_handlers.borrow();
+            for ((scale, value), hid) in self
+                .gamma
+                .iter()
+                .chain([&self.gamma_brightness])
+                .zip(gamma.into_iter().chain([brightness]))
+                .zip(handlers.iter())
+            {
+                Self::set_scale(scale, hid.as_ref(), value);
+            }
+        }
+
+        fn choose_icc_profile(&self) {
+            let chooser = FileChooserNative::new(
+                Some(&gettext("Choose ICC profile")),
+                self.obj().root().and_downcast_ref::<gtk::Window>(),
+                FileChooserAction::Open,
+                None,
+                None,
+            );
+            let filter = FileFilter::new();
+            filter.set_name(Some(&gettext("ICC profiles")));
+            filter.add_mime_type("application/vnd.iccprofile");
+            filter.add_suffix("icc");
+            filter.add_suffix("icm");
+            chooser.add_filter(&filter);
+            chooser.connect_response(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |chooser, response| {
+                    if response == ResponseType::Accept {
+                        if let Some(path) = chooser.file().and_then(|f| f.path()) {
+                            this.update_icc_profile(Some(&path.to_string_lossy()));
+                        }
+                    }
+                    this.icc_profile_chooser.take();
+                }
+            ));
+            chooser.show();
+            self.icc_profile_chooser.replace(Some(chooser));
+        }
+
+        fn update_icc_profile(&self, path: Option<&str>) {
+            if let Some(output) = self.output.borrow().as_ref() {
+                output.set_icc_profile(path);
+                self.set_icc_profile(path);
+                self.notify_updated(output, Update::IccProfile);
+            }
+        }
+
+        fn set_icc_profile(&self, path: Option<&str>) {
+            let name = path.and_then(|p| Path::new(p).file_name()).map(|n| n.to_string_lossy());
+            self.icc_profile.set_label(&name.map_or_else(
+                // Translators: No ICC profile assigned
+                || pgettext("ICC profile", "None"),
+                Cow::into_owned,
+            ));
+            self.icc_profile.set_tooltip_text(path);
+            self.icc_profile_clear.set_sensitive(path.is_some());
+            // Calibration curves replace the gamma ramps
+            self.gr_gamma.set_sensitive(path.is_none());
+            self.gamma_brightness.set_sensitive(path.is_none());
+        }
+
+        fn on_panning_changed(&self, editor: &PanningEditor) {
+            if let Some(output) = self.output.borrow().as_ref() {
+                output.set_panning(editor.panning());
+                self.notify_updated(output, Update::Panning);
+            }
+        }
+
+        fn on_transform_changed(&self, editor: &TransformEditor) {
+            if let Some(output) = self.output.borrow().as_ref() {
+                output.set_transform(editor.transform());
+                output.set_filter(editor.filter());
+                // The full transform replaces the scale
+                self.scale.set_sensitive(output.transform().is_none());
+                self.notify_updated(output, Update::Transform);
+            }
+        }
+
+        fn set_transform(&self, output: &Output) {
+            self.transform.set_transform(
+                output.transform(),
+                &output.filter(),
+                output.rotated_mode_size(),
+                [output.scale_x(), output.scale_y()],
+            );
+            self.scale.set_sensitive(output.transform().is_none());
+        }
+
         fn update_position(&self, axis: Axis, coord: I16) {
             let coord = coord.get();
             if let Some(output) = self.output.borrow().as_ref() {
@@ -576,6 +1060,14 @@ mod imp {
             }
         }
 
+        fn mirror_pos(output: &Output) -> u32 {
+            if output.mirror() > 0 {
+                u32::from(output.mirror_fit()) + 1
+            } else {
+                0
+            }
+        }
+
         fn set_scale(scale: &Scale, hid: Option<&SignalHandlerId>, value: f64) {
             if let Some(hid) = hid {
                 scale.block_signal(hid);
@@ -603,6 +1095,51 @@ impl DetailsBox {
         self.imp().screen_max_height.set(screen_max_height);
     }
 
+    /// Marks the current output if it's affected by one of `errors`.
+    pub fn set_config_errors(&self, errors: &[ConfigError]) {
+        let imp = self.imp();
+        imp.config_errors.replace(
+            errors
+                .iter()
+                .flat_map(|e| e.outputs().into_iter().map(|id| (id, e.to_string())))
+                .collect(),
+        );
+        imp.update_errors();
+    }
+
+    pub fn set_properties(&self, properties: &[OutputProperty]) {
+        self.imp().properties.set_properties(properties);
+    }
+
+    /// Shows the monitor information of the current output decoded from its
+    /// raw EDID.
+    pub fn set_edid(&self, data: Option<&[u8]>) {
+        self.imp().info.set_edid(self.output().as_ref(), data.unwrap_or_default());
+    }
+
+    pub fn connect_property_changed(&self, callback: impl Fn(&Self, &Output, Atom, i64) + 'static) {
+        let details = self.downgrade();
+        self.imp().properties.connect_property_changed(move |_, atom, value| {
+            if let Some(details) = details.upgrade() {
+                if let Some(output) = details.output() {
+                    callback(&details, &output, atom, value);
+                }
+            }
+        });
+    }
+
+    /// Shows the backlight brightness of the current output in percent or
+    /// hides it if the output has no backlight.
+    pub fn set_brightness(&self, percent: Option<f64>) { self.imp().set_brightness(percent); }
+
+    pub fn connect_brightness_changed(&self, callback: impl Fn(&Self, &Output, f64) + 'static) {
+        self.connect_closure(
+            "brightness-changed",
+            false,
+            closure_local!(|details, output, percent| callback(details, output, percent)),
+        );
+    }
+
     // TODO connect to Output properties notify signals instead of passing Update
     // enum
     pub fn connect_output_changed(&self, callback: impl Fn(&Self, &Output, Update) + 'static) {
@@ -630,6 +1167,11 @@ pub enum Update {
     Scale,
     Position,
     Primary,
+    Mirror,
+    Gamma,
+    IccProfile,
+    Panning,
+    Transform,
 }
 
 impl From<u8> for Update {
@@ -644,6 +1186,11 @@ impl From<u8> for Update {
             6 => Update::Scale,
             7 => Update::Position,
             8 => Update::Primary,
+            9 => Update::Mirror,
+            10 => Update::Gamma,
+            11 => Update::IccProfile,
+            12 => Update::Panning,
+            13 => Update::Transform,
             x => panic!("Not an update value: {x}"),
         }
     }
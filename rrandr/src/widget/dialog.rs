@@ -25,12 +25,13 @@ mod imp {
         DerivedObjectProperties, ObjectImpl, ObjectImplExt, ObjectSubclass,
     };
     use gtk::subclass::widget::{
-        CompositeTemplateClass, CompositeTemplateInitializingExt, WidgetClassExt, WidgetImpl,
+        CompositeTemplateCallbacksClass, CompositeTemplateClass, CompositeTemplateInitializingExt,
+        WidgetClassExt, WidgetImpl,
     };
     use gtk::subclass::window::WindowImpl;
     use gtk::{
-        glib, Align, Box, Button, CompositeTemplate, EventControllerKey, Label, StringList,
-        StringObject, TemplateChild, Window,
+        glib, template_callbacks, Align, Box, Button, CompositeTemplate, EventControllerKey,
+        Expander, Label, StringList, StringObject, TemplateChild, Window,
     };
 
     #[derive(CompositeTemplate, Properties, Default)]
@@ -44,6 +45,11 @@ mod imp {
         #[property(get = Self::message, set = Self::set_message, type = GString)]
         message: TemplateChild<Label>,
         #[template_child]
+        #[property(get = Self::details, set = Self::set_details, type = GString)]
+        details: TemplateChild<Label>,
+        #[template_child]
+        details_expander: TemplateChild<Expander>,
+        #[template_child]
         buttons: TemplateChild<Box>,
         #[property(set = Self::set_actions, construct, nullable)]
         actions: RefCell<Option<StringList>>,
@@ -58,7 +64,10 @@ mod imp {
 
         const NAME: &'static str = "RrrDialog";
 
-        fn class_init(klass: &mut Self::Class) { klass.bind_template(); }
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
 
         fn instance_init(obj: &InitializingObject<Self>) { obj.init_template(); }
     }
@@ -95,6 +104,12 @@ mod imp {
     impl WidgetImpl for Dialog {}
     impl WindowImpl for Dialog {}
 
+    #[template_callbacks]
+    impl Dialog {
+        #[template_callback]
+        fn on_copy_details(&self) { self.details.clipboard().set_text(&self.details.text()); }
+    }
+
     impl Dialog {
         fn heading(&self) -> GString { self.heading.text() }
 
@@ -104,6 +119,13 @@ mod imp {
 
         fn set_message(&self, text: &str) { self.message.set_label(text) }
 
+        fn details(&self) -> GString { self.details.text() }
+
+        fn set_details(&self, text: &str) {
+            self.details.set_label(text);
+            self.details_expander.set_visible(!text.is_empty());
+        }
+
         fn set_actions(&self, actions: Option<&StringList>) {
             while let Some(button) = self.buttons.first_child() {
                 button.unparent();
@@ -189,6 +211,11 @@ impl Builder {
         Self { builder: self.builder.property("message", message) }
     }
 
+    /// Technical details the user can expand and copy.
+    pub fn details(self, details: &str) -> Self {
+        Self { builder: self.builder.property("details", details) }
+    }
+
     pub fn heading(self, heading: &str) -> Self {
         Self { builder: self.builder.property("heading", heading) }
     }
@@ -3,6 +3,7 @@ use glib::subclass::types::ObjectSubclassIsExt;
 use glib::{closure_local, wrapper, Object};
 use gtk::prelude::{ObjectExt, WidgetExt};
 use gtk::{glib, Accessible, Buildable, ConstraintTarget, DrawingArea, Widget};
+use x11rb::protocol::randr::Output as OutputId;
 
 use super::details_box::Update;
 use crate::data::output::Output;
@@ -32,6 +33,7 @@ mod imp {
         glib, DragSource, DrawingArea, DropControllerMotion, EventControllerMotion, GestureClick,
     };
     use log::error;
+    use x11rb::protocol::randr::Output as OutputId;
 
     use crate::data::enums::{Reflection, Rotation};
     use crate::data::output::Output;
@@ -40,6 +42,9 @@ mod imp {
     use crate::widget::details_box::Update;
     use crate::window::PADDING;
 
+    /// Opacity of outputs that can't be enabled.
+    const BLOCKED_ALPHA: f64 = 0.4;
+
     #[derive(Default, Properties)]
     #[properties(wrapper_type = super::DisabledOutputArea)]
     pub struct DisabledOutputArea {
@@ -47,6 +52,7 @@ mod imp {
         #[property(get, set = Self::set_outputs)]
         outputs: RefCell<Outputs>,
         pub(super) selected_output: RefCell<Option<Output>>,
+        pub(super) blocked_outputs: RefCell<Vec<OutputId>>,
         is_dragging: Cell<bool>,
     }
 
@@ -161,6 +167,10 @@ mod imp {
                         f64::from(width),
                         f64::from(height),
                     );
+                    let blocked = self.blocked_outputs.borrow().contains(&o.id());
+                    if blocked {
+                        cr.push_group();
+                    }
                     context.draw_output(&rect);
                     context.draw_output_label(
                         &rect,
@@ -169,6 +179,11 @@ mod imp {
                         &o.name(),
                         o.product_name().as_deref(),
                     );
+                    if blocked {
+                        // Grey out outputs that can't be enabled with the enabled outputs
+                        cr.pop_group_to_source().unwrap();
+                        cr.paint_with_alpha(BLOCKED_ALPHA).unwrap();
+                    }
                     if let Some(s) = selected.as_ref() {
                         if s.id() == o.id() {
                             context.draw_selected_output(&rect);
@@ -369,6 +384,13 @@ impl DisabledOutputArea {
 
     pub fn selected_output(&self) -> Option<Output> { self.imp().selected_output.borrow().clone() }
 
+    /// Greys out the outputs that can't be enabled together with the enabled
+    /// outputs.
+    pub fn set_blocked_outputs(&self, outputs: Vec<OutputId>) {
+        self.imp().blocked_outputs.replace(outputs);
+        self.queue_draw();
+    }
+
     pub fn select(&self, output: &Output) { self.imp().select(output); }
 
     pub fn deselect(&self) { self.imp().deselect(); }
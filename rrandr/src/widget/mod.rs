@@ -4,7 +4,14 @@ pub mod details_child;
 pub mod dialog;
 pub mod disabled_output_area;
 pub mod icon_text;
+pub mod mode_dialog;
 pub mod mode_selector;
+pub mod mode_timings;
+pub mod monitor_info;
 pub mod output_area;
+pub mod panning_editor;
 pub mod position_entry;
+pub mod properties_box;
+pub mod scale_editor;
 pub mod switch;
+pub mod transform_editor;
@@ -0,0 +1,247 @@
+use glib::object::{IsA, ObjectExt};
+use glib::{closure_local, wrapper, Object};
+use gtk::{
+    glib, Accessible, ApplicationWindow, Buildable, ConstraintTarget, Native, Root,
+    ShortcutManager, Widget,
+};
+use log::warn;
+use x11rb::protocol::randr::Mode as ModeId;
+
+use crate::data::output::Output;
+use crate::timing::Timing;
+
+mod imp {
+    use std::cell::RefCell;
+    use std::sync::OnceLock;
+
+    use gdk::{Key, ModifierType};
+    use gettextrs::gettext;
+    use glib::object::CastNone;
+    use glib::subclass::types::ObjectSubclassExt;
+    use glib::subclass::{InitializingObject, Signal};
+    use glib::{
+        clone, derived_properties, object_subclass, Propagation, Properties, SignalHandlerId, Type,
+    };
+    use gtk::prelude::{
+        BoxExt, ButtonExt, EventControllerExt, GtkWindowExt, ListModelExt, ListModelExtManual,
+        ObjectExt, WidgetExt,
+    };
+    use gtk::subclass::prelude::{
+        DerivedObjectProperties, ObjectImpl, ObjectImplExt, ObjectSubclass,
+    };
+    use gtk::subclass::widget::{
+        CompositeTemplateCallbacksClass, CompositeTemplateClass, CompositeTemplateInitializingExt,
+        WidgetClassExt, WidgetImpl,
+    };
+    use gtk::subclass::window::WindowImpl;
+    use gtk::{
+        glib, template_callbacks, Box, Button, CompositeTemplate, DropDown, EventControllerKey,
+        Label, ListBox, Orientation, SpinButton, TemplateChild, Window,
+    };
+
+    use crate::data::mode::Mode;
+    use crate::data::output::Output;
+    use crate::window::SPACING;
+
+    #[derive(CompositeTemplate, Properties, Default)]
+    #[template(resource = "/com/github/brofi/rrandr/mode_dialog.ui")]
+    #[properties(wrapper_type = super::ModeDialog)]
+    pub struct ModeDialog {
+        #[property(get, set = Self::set_output, nullable)]
+        output: RefCell<Option<Output>>,
+        modes_changed_handler: RefCell<Option<SignalHandlerId>>,
+        #[template_child]
+        mode_list: TemplateChild<ListBox>,
+        #[template_child]
+        width: TemplateChild<SpinButton>,
+        #[template_child]
+        height: TemplateChild<SpinButton>,
+        #[template_child]
+        refresh: TemplateChild<SpinButton>,
+        #[template_child]
+        timing: TemplateChild<DropDown>,
+    }
+
+    #[object_subclass]
+    impl ObjectSubclass for ModeDialog {
+        type ParentType = Window;
+        type Type = super::ModeDialog;
+
+        const NAME: &'static str = "RrrModeDialog";
+
+        fn class_init(klass: &mut Self::Class) {
+            klass.bind_template();
+            klass.bind_template_callbacks();
+        }
+
+        fn instance_init(obj: &InitializingObject<Self>) { obj.init_template(); }
+    }
+
+    #[derived_properties]
+    impl ObjectImpl for ModeDialog {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("create-mode")
+                        .param_types([Type::U32, Type::U32, Type::F64, Type::U32])
+                        .build(),
+                    Signal::builder("delete-mode").param_types([Type::U32]).build(),
+                ]
+            })
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.mode_list.set_placeholder(Some(
+                &Label::builder()
+                    .label(gettext("No custom modes"))
+                    .margin_top(6)
+                    .margin_bottom(6)
+                    .build(),
+            ));
+
+            let eck = EventControllerKey::new();
+            eck.connect_key_pressed(|eck, keyval, _keycode, state| match keyval {
+                Key::Escape => {
+                    eck.widget().and_downcast::<Window>().unwrap().close();
+                    Propagation::Stop
+                }
+                Key::w if state.contains(ModifierType::CONTROL_MASK) => {
+                    eck.widget().and_downcast::<Window>().unwrap().close();
+                    Propagation::Stop
+                }
+                _ => Propagation::Proceed,
+            });
+            self.obj().add_controller(eck);
+        }
+
+        fn dispose(&self) { self.disconnect_modes(); }
+    }
+
+    impl WidgetImpl for ModeDialog {}
+    impl WindowImpl for ModeDialog {}
+
+    #[template_callbacks]
+    impl ModeDialog {
+        fn set_output(&self, output: Option<&Output>) {
+            self.disconnect_modes();
+            if let Some(output) = output {
+                let mode = output.mode().or_else(|| output.modes().first());
+                if let Some(mode) = mode {
+                    self.width.set_value(mode.width().into());
+                    self.height.set_value(mode.height().into());
+                    self.refresh.set_value(mode.refresh());
+                }
+                self.modes_changed_handler.replace(Some(output.modes().connect_items_changed(
+                    clone!(
+                        #[weak(rename_to = this)]
+                        self,
+                        move |_, _, _, _| this.update_modes()
+                    ),
+                )));
+            }
+            self.output.replace(output.cloned());
+            self.update_modes();
+        }
+
+        fn disconnect_modes(&self) {
+            if let (Some(output), Some(handler)) =
+                (self.output.borrow().as_ref(), self.modes_changed_handler.take())
+            {
+                output.modes().disconnect(handler);
+            }
+        }
+
+        fn update_modes(&self) {
+            while let Some(row) = self.mode_list.row_at_index(0) {
+                self.mode_list.remove(&row);
+            }
+            let Some(output) = self.output.borrow().clone() else {
+                return;
+            };
+            for mode in output.modes().iter::<Mode>().map(Result::unwrap).filter(Mode::custom) {
+                let row = Box::new(Orientation::Horizontal, SPACING.into());
+                row.set_margin_start(SPACING.into());
+                row.append(&Label::builder().label(mode.name()).hexpand(true).xalign(0.).build());
+                let delete = Button::builder()
+                    .icon_name("edit-delete-symbolic")
+                    .tooltip_text(gettext("Delete mode"))
+                    .css_classes(["flat"])
+                    .build();
+                delete.connect_clicked(clone!(
+                    #[weak(rename_to = this)]
+                    self,
+                    move |_| this.obj().emit_by_name::<()>("delete-mode", &[&mode.id()])
+                ));
+                row.append(&delete);
+                self.mode_list.append(&row);
+            }
+        }
+
+        #[template_callback]
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        fn on_add_clicked(&self) {
+            self.obj().emit_by_name::<()>(
+                "create-mode",
+                &[
+                    &(self.width.value_as_int() as u32),
+                    &(self.height.value_as_int() as u32),
+                    &self.refresh.value(),
+                    &self.timing.selected(),
+                ],
+            );
+        }
+    }
+}
+
+wrapper! {
+    pub struct ModeDialog(ObjectSubclass<imp::ModeDialog>)
+        @extends gtk::Window, Widget,
+        @implements Accessible, Buildable, ConstraintTarget, Native, Root, ShortcutManager;
+}
+
+impl ModeDialog {
+    pub fn new(window: &impl IsA<ApplicationWindow>, output: &Output) -> Self {
+        Object::builder().property("transient-for", window).property("output", output).build()
+    }
+
+    pub fn connect_create_mode(
+        &self,
+        callback: impl Fn(&Self, &Output, u16, u16, f64, Timing) + 'static,
+    ) {
+        self.connect_closure(
+            "create-mode",
+            false,
+            closure_local!(|dialog: &Self, width: u32, height: u32, refresh: f64, timing: u32| {
+                let Some(output) = dialog.output() else {
+                    return;
+                };
+                match Timing::try_from(timing) {
+                    Ok(timing) => callback(
+                        dialog,
+                        &output,
+                        u16::try_from(width).unwrap_or(u16::MAX),
+                        u16::try_from(height).unwrap_or(u16::MAX),
+                        refresh,
+                        timing,
+                    ),
+                    Err(e) => warn!("{e}"),
+                }
+            }),
+        );
+    }
+
+    pub fn connect_delete_mode(&self, callback: impl Fn(&Self, &Output, ModeId) + 'static) {
+        self.connect_closure(
+            "delete-mode",
+            false,
+            closure_local!(|dialog: &Self, mode: u32| {
+                if let Some(output) = dialog.output() {
+                    callback(dialog, &output, mode);
+                }
+            }),
+        );
+    }
+}
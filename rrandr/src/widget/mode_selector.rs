@@ -17,7 +17,7 @@ mod imp {
     use gtk::subclass::prelude::DerivedObjectProperties;
     use gtk::subclass::widget::{WidgetClassExt, WidgetImpl};
     use gtk::{
-        gio, glib, Align, BinLayout, Box, DropDown, Image, Label, ListItem, Orientation,
+        gio, glib, Align, BinLayout, Box, Button, DropDown, Image, Label, ListItem, Orientation,
         SignalListItemFactory, Widget,
     };
     use x11rb::protocol::randr::ModeFlag;
@@ -93,6 +93,13 @@ mod imp {
                 Box::builder().orientation(Orientation::Horizontal).css_classes(["linked"]).build();
             linkbox.append(&self.resolution);
             linkbox.append(&self.refresh_rate);
+            linkbox.append(
+                &Button::builder()
+                    .icon_name("list-add-symbolic")
+                    .tooltip_text(gettext("Custom modes…"))
+                    .action_name("win.custom-modes")
+                    .build(),
+            );
             linkbox.set_parent(&*self.obj());
 
             self.refresh_rate
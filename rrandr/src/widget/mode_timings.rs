@@ -0,0 +1,154 @@
+use glib::{wrapper, Object};
+use gtk::{glib, Widget};
+
+/// Color depth assumed for the pixel bandwidth.
+const BITS_PER_PIXEL: u32 = 24;
+
+mod imp {
+    use std::cell::RefCell;
+
+    use gettextrs::gettext;
+    use glib::subclass::object::{ObjectImpl, ObjectImplExt};
+    use glib::subclass::types::{ObjectSubclass, ObjectSubclassExt};
+    use glib::{derived_properties, object_subclass, Properties};
+    use gtk::prelude::{GridExt, ObjectExt, WidgetExt};
+    use gtk::subclass::prelude::DerivedObjectProperties;
+    use gtk::subclass::widget::{WidgetClassExt, WidgetImpl};
+    use gtk::{glib, BinLayout, Expander, Grid, Label, Widget};
+
+    use super::BITS_PER_PIXEL;
+    use crate::data::mode::Mode;
+    use crate::window::SPACING;
+
+    #[derive(Properties)]
+    #[properties(wrapper_type = super::ModeTimings)]
+    pub struct ModeTimings {
+        #[property(get, set = Self::set_mode, nullable)]
+        mode: RefCell<Option<Mode>>,
+        expander: Expander,
+        dot_clock: Label,
+        horizontal: Label,
+        hfreq: Label,
+        vertical: Label,
+        vfreq: Label,
+        flags: Label,
+        bandwidth: Label,
+    }
+
+    impl Default for ModeTimings {
+        fn default() -> Self {
+            let value = || Label::builder().xalign(0.).selectable(true).build();
+            Self {
+                mode: RefCell::default(),
+                expander: Expander::default(),
+                dot_clock: value(),
+                horizontal: value(),
+                hfreq: value(),
+                vertical: value(),
+                vfreq: value(),
+                flags: value(),
+                bandwidth: value(),
+            }
+        }
+    }
+
+    #[object_subclass]
+    impl ObjectSubclass for ModeTimings {
+        type ParentType = Widget;
+        type Type = super::ModeTimings;
+
+        const NAME: &'static str = "RrrModeTimings";
+
+        fn class_init(klass: &mut Self::Class) { klass.set_layout_manager_type::<BinLayout>(); }
+    }
+
+    #[derived_properties]
+    impl ObjectImpl for ModeTimings {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let grid = Grid::builder()
+                .row_spacing(SPACING.into())
+                .column_spacing((2 * SPACING).into())
+                .margin_top(SPACING.into())
+                .build();
+            let rows = [
+                (gettext("Pixel clock"), &self.dot_clock),
+                // Translators: Horizontal width, sync start, sync end, total and skew in pixels
+                (gettext("Horizontal"), &self.horizontal),
+                (gettext("Horizontal frequency"), &self.hfreq),
+                // Translators: Vertical height, sync start, sync end and total in lines
+                (gettext("Vertical"), &self.vertical),
+                (gettext("Vertical frequency"), &self.vfreq),
+                (gettext("Flags"), &self.flags),
+                (gettext("Pixel bandwidth"), &self.bandwidth),
+            ];
+            for (row, (name, value)) in (0..).zip(rows) {
+                grid.attach(&Label::builder().label(name).xalign(0.).build(), 0, row, 1, 1);
+                grid.attach(value, 1, row, 1, 1);
+            }
+            self.expander.set_child(Some(&grid));
+            self.expander.set_parent(&*self.obj());
+        }
+
+        fn dispose(&self) { self.expander.unparent(); }
+    }
+
+    impl WidgetImpl for ModeTimings {}
+
+    impl ModeTimings {
+        fn set_mode(&self, mode: Option<&Mode>) {
+            self.mode.replace(mode.cloned());
+            let Some(mode) = mode else {
+                self.expander.set_label(None);
+                return;
+            };
+            self.expander.set_label(Some(&if mode.name().is_empty() {
+                mode.as_resolution_str(None)
+            } else {
+                mode.name()
+            }));
+            self.dot_clock.set_label(&format!(
+                "{:.2}\u{202F}{}",
+                f64::from(mode.dot_clock()) / 1e6,
+                gettext("MHz")
+            ));
+            self.horizontal.set_label(&format!(
+                "{} {} {} {} {}",
+                mode.width(),
+                mode.hsync_start(),
+                mode.hsync_end(),
+                mode.htotal(),
+                mode.hskew()
+            ));
+            self.hfreq.set_label(&format!("{:.2}\u{202F}{}", mode.hfreq() / 1e3, gettext("kHz")));
+            self.vertical.set_label(&format!(
+                "{} {} {} {}",
+                mode.height(),
+                mode.vsync_start(),
+                mode.vsync_end(),
+                mode.vtotal()
+            ));
+            self.vfreq.set_label(&mode.as_refresh_rate_str());
+            self.flags.set_label(&mode.flag_names().join(" "));
+            self.bandwidth.set_label(&format!(
+                "{:.2}\u{202F}{} ({BITS_PER_PIXEL}\u{202F}{})",
+                mode.bandwidth(BITS_PER_PIXEL) / 1e9,
+                gettext("Gbit/s"),
+                // Translators: Bits per pixel
+                gettext("bpp")
+            ));
+        }
+    }
+}
+
+wrapper! {
+    pub struct ModeTimings(ObjectSubclass<imp::ModeTimings>) @extends Widget;
+}
+
+impl ModeTimings {
+    pub fn new() -> Self { Object::new() }
+}
+
+impl Default for ModeTimings {
+    fn default() -> Self { Self::new() }
+}
@@ -0,0 +1,583 @@
+use glib::subclass::types::ObjectSubclassIsExt;
+use glib::{wrapper, Object};
+use gtk::{glib, Widget};
+
+use crate::data::output::Output;
+
+mod imp {
+    use std::cell::RefCell;
+    use std::fs;
+
+    use gettextrs::gettext;
+    use glib::object::CastNone;
+    use glib::subclass::object::{ObjectImpl, ObjectImplExt};
+    use glib::subclass::types::{ObjectSubclass, ObjectSubclassExt};
+    use glib::{clone, object_subclass};
+    use gtk::prelude::{
+        BoxExt, ButtonExt, FileChooserExt, FileExt, NativeDialogExt, TextBufferExt, TextViewExt,
+        ToggleButtonExt, WidgetExt,
+    };
+    use gtk::subclass::widget::{WidgetClassExt, WidgetImpl};
+    use gtk::{
+        glib, pango, Align, ApplicationWindow, BinLayout, Box, Button, FileChooserAction,
+        FileChooserNative, FileFilter, FlowBox, Label, Orientation, PolicyType, ResponseType,
+        ScrolledWindow, SelectionMode, TextView, ToggleButton, Widget, WrapMode,
+    };
+    use log::warn;
+
+    use crate::data::modes::Modes;
+    use crate::data::output::Output;
+    use crate::edid::cta::{Cta, HdrStaticMetadata, ShortAudio, ShortVideo, VideoFormat};
+    use crate::edid::dump::hex_dump;
+    use crate::edid::{DetailedTiming, Edid, Extension, Manufactured, ScreenSize, StandardTiming};
+    use crate::math::MM_PER_INCH;
+    use crate::widget::details_child::DetailsChild;
+    use crate::widget::dialog::Dialog;
+    use crate::window::SPACING;
+
+    pub struct MonitorInfo {
+        root: Box,
+        fb_info: FlowBox,
+        sw_raw: ScrolledWindow,
+        tv_raw: TextView,
+        btn_export: Button,
+        tb_raw: ToggleButton,
+        data: RefCell<Vec<u8>>,
+        file_name: RefCell<String>,
+        export_chooser: RefCell<Option<FileChooserNative>>,
+    }
+
+    impl Default for MonitorInfo {
+        fn default() -> Self {
+            let tv_raw = TextView::builder()
+                .editable(false)
+                .monospace(true)
+                .wrap_mode(WrapMode::None)
+                .build();
+            Self {
+                root: Box::new(Orientation::Vertical, SPACING.into()),
+                fb_info: FlowBox::builder()
+                    .row_spacing(SPACING.into())
+                    .column_spacing(SPACING.into())
+                    .orientation(Orientation::Horizontal)
+                    .selection_mode(SelectionMode::None)
+                    .max_children_per_line(u32::MAX)
+                    .halign(Align::Fill)
+                    .hexpand(true)
+                    .build(),
+                sw_raw: ScrolledWindow::builder()
+                    .child(&tv_raw)
+                    .hscrollbar_policy(PolicyType::Automatic)
+                    .vscrollbar_policy(PolicyType::Never)
+                    .propagate_natural_height(true)
+                    .visible(false)
+                    .build(),
+                tv_raw,
+                btn_export: Button::builder()
+                    .label(gettext("Export EDID\u{2026}"))
+                    .tooltip_text(gettext("Save the raw EDID to a file"))
+                    .build(),
+                tb_raw: ToggleButton::builder()
+                    .label(gettext("Raw data"))
+                    .tooltip_text(gettext("Show the EDID as hexadecimal bytes and decoded text"))
+                    .build(),
+                data: RefCell::default(),
+                file_name: RefCell::default(),
+                export_chooser: RefCell::default(),
+            }
+        }
+    }
+
+    #[object_subclass]
+    impl ObjectSubclass for MonitorInfo {
+        type ParentType = Widget;
+        type Type = super::MonitorInfo;
+
+        const NAME: &'static str = "MonitorInfo";
+
+        fn class_init(klass: &mut Self::Class) { klass.set_layout_manager_type::<BinLayout>(); }
+    }
+
+    impl ObjectImpl for MonitorInfo {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.btn_export.connect_clicked(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_| this.choose_export_file()
+            ));
+            self.tb_raw.connect_toggled(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |tb| {
+                    this.fb_info.set_visible(!tb.is_active());
+                    this.sw_raw.set_visible(tb.is_active());
+                }
+            ));
+            let buttons = Box::new(Orientation::Horizontal, SPACING.into());
+            buttons.set_halign(Align::End);
+            buttons.append(&self.tb_raw);
+            buttons.append(&self.btn_export);
+            self.root.append(&self.fb_info);
+            self.root.append(&self.sw_raw);
+            self.root.append(&buttons);
+            self.root.set_parent(&*self.obj());
+        }
+
+        fn dispose(&self) { self.root.unparent(); }
+    }
+
+    impl WidgetImpl for MonitorInfo {}
+
+    impl MonitorInfo {
+        pub(super) fn set_edid(&self, output: Option<&Output>, data: &[u8]) {
+            self.data.replace(data.to_vec());
+            self.file_name
+                .replace(format!("{}.bin", output.map_or("edid".to_owned(), Output::name)));
+            self.btn_export.set_sensitive(!data.is_empty());
+            self.tb_raw.set_sensitive(!data.is_empty());
+            if data.is_empty() {
+                self.tb_raw.set_active(false);
+            }
+            let edid = (!data.is_empty()).then(|| Edid::parse(data).ok()).flatten();
+            let raw = match &edid {
+                Some(edid) => format!("{}\n{edid}", hex_dump(data)),
+                None => hex_dump(data),
+            };
+            self.tv_raw.buffer().set_text(&raw);
+            self.show_info(edid.as_ref(), output.map(Output::modes).as_ref());
+        }
+
+        fn choose_export_file(&self) {
+            let chooser = FileChooserNative::new(
+                Some(&gettext("Export EDID")),
+                self.obj().root().and_downcast_ref::<gtk::Window>(),
+                FileChooserAction::Save,
+                None,
+                None,
+            );
+            let filter = FileFilter::new();
+            filter.set_name(Some(&gettext("EDID files")));
+            filter.add_suffix("bin");
+            chooser.add_filter(&filter);
+            chooser.set_current_name(&self.file_name.borrow());
+            chooser.connect_response(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |chooser, response| {
+                    if response == ResponseType::Accept {
+                        if let Some(path) = chooser.file().and_then(|f| f.path()) {
+                            if let Err(e) = fs::write(&path, &*this.data.borrow()) {
+                                warn!("Failed to export EDID to {}: {e}", path.display());
+                                if let Some(window) =
+                                    this.obj().root().and_downcast_ref::<ApplicationWindow>()
+                                {
+                                    Dialog::builder(window)
+                                        .title(&gettext("Failure"))
+                                        .heading(&gettext("Failed to export EDID"))
+                                        .message(&e.to_string())
+                                        .build()
+                                        .show();
+                                }
+                            }
+                        }
+                    }
+                    this.export_chooser.take();
+                }
+            ));
+            chooser.show();
+            self.export_chooser.replace(Some(chooser));
+        }
+
+        #[allow(clippy::too_many_lines)]
+        fn show_info(&self, edid: Option<&Edid>, modes: Option<&Modes>) {
+            while let Some(child) = self.fb_info.first_child() {
+                self.fb_info.remove(&child);
+            }
+            let Some(edid) = edid else {
+                self.fb_info.append(
+                    &Label::builder()
+                        .label(gettext("No monitor information"))
+                        .css_classes(["dim-label"])
+                        .build(),
+                );
+                return;
+            };
+            self.append(
+                &gettext("Manufacturer"),
+                &edid.manufacturer_name().map_or_else(
+                    || edid.manufacturer.clone(),
+                    |name| format!("{name} ({})", edid.manufacturer),
+                ),
+                None,
+            );
+            if let Some(name) = &edid.name {
+                self.append(&gettext("Model"), name, None);
+            }
+            self.append(&gettext("Product code"), &format!("{:04X}", edid.product_code), None);
+            if let Some(serial) = &edid.serial {
+                self.append(&gettext("Serial number"), serial, None);
+            } else if edid.serial_number > 0 {
+                self.append(&gettext("Serial number"), &edid.serial_number.to_string(), None);
+            }
+            self.append(
+                &gettext("Manufactured"),
+                &match edid.manufactured {
+                    Manufactured::Week(Some(week), year) => {
+                        gettext!("Week {} of {}", week, year)
+                    }
+                    Manufactured::Week(None, year) => year.to_string(),
+                    // Translators: Year the monitor model was first released
+                    Manufactured::ModelYear(year) => gettext!("Model year {}", year),
+                },
+                None,
+            );
+            self.append(
+                &gettext("EDID version"),
+                &format!("{}.{}", edid.version[0], edid.version[1]),
+                (!edid.extensions.is_empty())
+                    .then(|| {
+                        edid.extensions.iter().map(extension_text).collect::<Vec<_>>().join("\n")
+                    })
+                    .as_deref(),
+            );
+            self.append(&gettext("Input"), &input_text(edid), None);
+            match edid.size {
+                ScreenSize::Size(width, height) => {
+                    let diagonal = f64::from(width).hypot(f64::from(height)) * 10. / MM_PER_INCH;
+                    self.append(
+                        &gettext("Screen size"),
+                        &format!("{width}\u{00D7}{height}\u{202F}cm ({diagonal:.1}\u{2033})"),
+                        None,
+                    );
+                }
+                ScreenSize::AspectRatio(ratio) => {
+                    self.append(&gettext("Aspect ratio"), &format!("{ratio:.2}"), None);
+                }
+                ScreenSize::Undefined => (),
+            }
+            if let Some(gamma) = edid.gamma {
+                self.append(&gettext("Gamma"), &format!("{gamma:.2}"), None);
+            }
+            let chroma = &edid.chromaticity;
+            self.append(
+                // Translators: CIE xy coordinates of the primaries and white point
+                &gettext("Chromaticity"),
+                &[
+                    (gettext("Red"), chroma.red),
+                    (gettext("Green"), chroma.green),
+                    (gettext("Blue"), chroma.blue),
+                    (gettext("White"), chroma.white),
+                ]
+                .iter()
+                .map(|(name, [x, y])| format!("{name} {x:.3}, {y:.3}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+                None,
+            );
+            if let Some(limits) = &edid.range_limits {
+                let [v_min, v_max] = limits.vertical;
+                let [h_min, h_max] = limits.horizontal;
+                let clock = limits
+                    .max_pixel_clock
+                    .map(|clock| format!(", \u{2264}{clock}\u{202F}MHz"))
+                    .unwrap_or_default();
+                self.append(
+                    &gettext("Range limits"),
+                    &format!(
+                        "{v_min}\u{2013}{v_max}\u{202F}Hz, \
+                         {h_min}\u{2013}{h_max}\u{202F}kHz{clock}"
+                    ),
+                    None,
+                );
+            }
+            let detailed_timings = edid.all_detailed_timings().collect::<Vec<_>>();
+            if !detailed_timings.is_empty() {
+                self.append(
+                    &gettext("Detailed timings"),
+                    &detailed_timings
+                        .iter()
+                        .map(|t| detailed_timing_text(t))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    Some(
+                        &detailed_timings
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join("\n"),
+                    ),
+                );
+            }
+            if !edid.standard_timings.is_empty() {
+                self.append(
+                    &gettext("Standard timings"),
+                    &timings_text(&edid.standard_timings),
+                    None,
+                );
+            }
+            if !edid.established_timings.is_empty() {
+                self.append(
+                    &gettext("Established timings"),
+                    &timings_text(&edid.established_timings),
+                    None,
+                );
+            }
+            if !edid.texts.is_empty() {
+                self.append(&gettext("Text"), &edid.texts.join("\n"), None);
+            }
+            for cta in edid.cta() {
+                self.append_cta(cta, modes);
+            }
+            for tile in edid.displayid().filter_map(|displayid| displayid.tile.as_ref()) {
+                let [h_tiles, v_tiles] = tile.tiles;
+                let [x, y] = tile.location;
+                let [width, height] = tile.size;
+                self.append(
+                    // Translators: Monitor made of several tiles, each connected to an output
+                    &gettext("Tiled display"),
+                    &gettext!(
+                        "Tile {} of {} ({}\u{00D7}{})",
+                        format!("{x},{y}"),
+                        format!("{h_tiles}\u{00D7}{v_tiles}"),
+                        width,
+                        height
+                    ),
+                    Some(&format!(
+                        "{}-{:04X}-{:08X}",
+                        tile.manufacturer, tile.product_code, tile.serial_number
+                    )),
+                );
+            }
+        }
+
+        fn append_cta(&self, cta: &Cta, modes: Option<&Modes>) {
+            let mut features = vec![gettext!("Revision {}", cta.revision)];
+            if cta.underscan {
+                features.push(gettext("underscan"));
+            }
+            if cta.basic_audio {
+                features.push(gettext("basic audio"));
+            }
+            for (supported, name) in cta.ycbcr.into_iter().zip(["YCbCr 4:4:4", "YCbCr 4:2:2"]) {
+                if supported {
+                    features.push(name.to_owned());
+                }
+            }
+            self.append("CTA-861", &features.join(", "), None);
+
+            // Formats only supported in YCbCr 4:2:0 might not be listed in the video data
+            // block
+            let video = cta.video.iter().copied().chain(
+                cta.ycbcr420_only
+                    .iter()
+                    .filter(|&&vic| !cta.video.iter().any(|v| v.vic == vic))
+                    .map(|&vic| ShortVideo { vic, native: false }),
+            );
+            let video = video.map(|svd| video_text(cta, svd, modes)).collect::<Vec<_>>();
+            if !video.is_empty() {
+                self.append(
+                    &gettext("Video formats"),
+                    &video.join("\n"),
+                    Some(&gettext(
+                        "Formats without a matching mode are not available, e.g. because they \
+                         exceed the bandwidth of the connection or need YCbCr 4:2:0",
+                    )),
+                );
+            }
+            if !cta.audio.is_empty() {
+                let audio = cta.audio.iter().map(audio_text);
+                self.append(&gettext("Audio"), &audio.collect::<Vec<_>>().join("\n"), None);
+            }
+            let hdmi = hdmi_text(cta);
+            if !hdmi.is_empty() {
+                self.append("HDMI", &hdmi.join("\n"), None);
+            }
+            if let Some(hdr) = &cta.hdr {
+                self.append("HDR", &hdr_text(hdr), None);
+            }
+            if !cta.colorimetry.is_empty() {
+                self.append(&gettext("Colorimetry"), &cta.colorimetry.join(", "), None);
+            }
+        }
+
+        fn append(&self, name: &str, value: &str, tooltip: Option<&str>) {
+            let label = Label::builder()
+                .label(value)
+                .selectable(true)
+                .wrap(true)
+                .wrap_mode(pango::WrapMode::WordChar)
+                .max_width_chars(40)
+                .xalign(0.)
+                .build();
+            label.set_tooltip_text(tooltip);
+            let child = DetailsChild::new(name, &label, "");
+            child.set_visible(true);
+            self.fb_info.append(&child);
+        }
+    }
+
+    fn input_text(edid: &Edid) -> String {
+        if !edid.input.digital {
+            return gettext("Analog");
+        }
+        let mut text = gettext("Digital");
+        if let Some(depth) = edid.input.bit_depth {
+            text += ", ";
+            text += &gettext!("{} bits per color", depth);
+        }
+        if let Some(interface) = edid.input.interface {
+            text += ", ";
+            text += interface;
+        }
+        text
+    }
+
+    fn detailed_timing_text(timing: &DetailedTiming) -> String {
+        let [width, height] = timing.size;
+        format!(
+            "{}\u{00D7}{}{} @ {:.2}\u{202F}Hz{}",
+            timing.horizontal[0],
+            timing.vertical[0],
+            if timing.interlaced { "i" } else { "" },
+            timing.refresh(),
+            if width > 0 && height > 0 {
+                format!(" ({width}\u{00D7}{height}\u{202F}mm)")
+            } else {
+                String::new()
+            }
+        )
+    }
+
+    fn video_text(cta: &Cta, svd: ShortVideo, modes: Option<&Modes>) -> String {
+        let Some(format) = svd.format() else {
+            return gettext!("Unknown format {}", svd.vic);
+        };
+        let mut notes = Vec::new();
+        if svd.native {
+            notes.push(gettext("native"));
+        }
+        if cta.ycbcr420_only.contains(&svd.vic) {
+            notes.push(gettext("YCbCr 4:2:0 only"));
+        }
+        if modes.is_some_and(|modes| !has_mode(modes, &format)) {
+            notes.push(gettext("not available"));
+        }
+        format!(
+            "{}\u{00D7}{}{}@{} {}{}",
+            format.width,
+            format.height,
+            if format.interlaced { "i" } else { "" },
+            format.refresh,
+            format.aspect_ratio,
+            if notes.is_empty() { String::new() } else { format!(" ({})", notes.join(", ")) }
+        )
+    }
+
+    fn audio_text(sad: &ShortAudio) -> String {
+        let rates = sad
+            .sample_rates
+            .iter()
+            .map(|&rate| format!("{}", f64::from(rate) / 1000.))
+            .collect::<Vec<_>>()
+            .join("/");
+        let mut parts = vec![
+            format!("{} {}\u{202F}ch", sad.format, sad.channels),
+            format!("{rates}\u{202F}kHz"),
+        ];
+        if !sad.sample_sizes.is_empty() {
+            let sizes = sad.sample_sizes.iter().map(u8::to_string).collect::<Vec<_>>();
+            parts.push(format!("{}\u{202F}bit", sizes.join("/")));
+        }
+        if let Some(rate) = sad.max_bit_rate {
+            parts.push(format!("\u{2264}{rate}\u{202F}kbit/s"));
+        }
+        parts.join(", ")
+    }
+
+    fn hdmi_text(cta: &Cta) -> Vec<String> {
+        let mut hdmi = Vec::new();
+        if let Some(vsdb) = &cta.hdmi {
+            let [a, b, c, d] = vsdb.physical_address;
+            hdmi.push(gettext!("Physical address {}", format!("{a}.{b}.{c}.{d}")));
+            if let Some(clock) = vsdb.max_tmds_clock {
+                hdmi.push(format!("TMDS \u{2264}{clock}\u{202F}MHz"));
+            }
+            if !vsdb.deep_color.is_empty() {
+                let bits = vsdb.deep_color.iter().map(u8::to_string).collect::<Vec<_>>();
+                hdmi.push(gettext!("Deep color {} bit", bits.join("/")));
+            }
+        }
+        if let Some(vsdb) = &cta.hdmi_forum {
+            hdmi.push(format!("HDMI Forum {}", vsdb.version));
+            if let Some(rate) = vsdb.max_tmds_rate {
+                hdmi.push(format!("TMDS \u{2264}{rate}\u{202F}MHz"));
+            }
+            if vsdb.scdc {
+                hdmi.push("SCDC".to_owned());
+            }
+        }
+        hdmi
+    }
+
+    /// Supported transfer functions and luminances of the HDR static metadata.
+    fn hdr_text(hdr: &HdrStaticMetadata) -> String {
+        let luminance = [
+            (gettext("max"), hdr.max_luminance),
+            (gettext("max frame average"), hdr.max_frame_average),
+            (gettext("min"), hdr.min_luminance),
+        ];
+        std::iter::once(hdr.eotfs.join(", "))
+            .chain(luminance.into_iter().filter_map(|(name, value)| {
+                value.map(|value| format!("{name} {value:.2}\u{202F}cd/m\u{00B2}"))
+            }))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn extension_text(extension: &Extension) -> String {
+        match extension {
+            Extension::Cta(cta) => format!("CTA-861 ({})", gettext!("Revision {}", cta.revision)),
+            Extension::DisplayId(displayid) => {
+                format!("DisplayID {}.{}", displayid.version[0], displayid.version[1])
+            }
+            Extension::Other(tag) => format!("{tag:#04X}"),
+        }
+    }
+
+    /// Whether the output has a mode with the resolution and refresh rate of
+    /// `format`, also matching rates divided by 1.001.
+    fn has_mode(modes: &Modes, format: &VideoFormat) -> bool {
+        let refresh = f64::from(format.refresh);
+        modes
+            .find_by_res(format.width, format.height, Some(refresh))
+            .is_some_and(|mode| (mode.refresh() - refresh).abs() < 0.5)
+    }
+
+    fn timings_text(timings: &[StandardTiming]) -> String {
+        timings
+            .iter()
+            .map(|t| format!("{}\u{00D7}{}@{}", t.width, t.height, t.refresh))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+wrapper! {
+    pub struct MonitorInfo(ObjectSubclass<imp::MonitorInfo>) @extends Widget;
+}
+
+impl MonitorInfo {
+    pub fn new() -> Self { Object::new() }
+
+    /// Shows the information decoded from the raw EDID `data` of the monitor
+    /// connected to `output`. Video formats without a matching mode of
+    /// `output` are marked as not available.
+    pub fn set_edid(&self, output: Option<&Output>, data: &[u8]) {
+        self.imp().set_edid(output, data);
+    }
+}
+
+impl Default for MonitorInfo {
+    fn default() -> Self { Self::new() }
+}
@@ -5,9 +5,11 @@ use glib::{closure_local, wrapper, Object};
 use gtk::prelude::{ListModelExtManual, WidgetExt};
 use gtk::subclass::drawing_area::DrawingAreaImpl;
 use gtk::{glib, Accessible, Buildable, ConstraintTarget, DrawingArea, Widget};
+use x11rb::protocol::randr::Output as OutputId;
 
 use super::details_box::Update;
 use crate::data::output::Output;
+use crate::x11::monitor::Monitor;
 
 mod imp {
     use std::cell::{Cell, RefCell};
@@ -18,7 +20,7 @@ mod imp {
     use gdk::{DragAction, Key, ModifierType};
     use glib::subclass::object::{ObjectImpl, ObjectImplExt};
     use glib::subclass::Signal;
-    use glib::{clone, derived_properties, object_subclass, Propagation, Properties, Value};
+    use glib::{clone, derived_properties, object_subclass, Propagation, Properties, Type, Value};
     use gtk::prelude::{
         DrawingAreaExtManual, GestureDragExt, ListModelExt, ListModelExtManual, ObjectExt,
         StaticType, WidgetExt,
@@ -30,6 +32,7 @@ mod imp {
         glib, DrawingArea, DropTarget, EventControllerKey, EventControllerMotion,
         EventControllerScroll, EventControllerScrollFlags, GestureClick, GestureDrag,
     };
+    use x11rb::protocol::randr::Output as OutputId;
 
     use crate::data::output::Output;
     use crate::data::outputs::Outputs;
@@ -37,6 +40,11 @@ mod imp {
     use crate::math::{Point, Rect};
     use crate::widget::details_box::Update;
     use crate::window::PADDING;
+    use crate::x11::monitor::Monitor;
+
+    /// Distance in pixels at which the edges of a drawn monitor snap to the
+    /// edges and centers of outputs.
+    const MONITOR_SNAP_DIST: f64 = 8.;
 
     #[derive(Default, Properties)]
     #[properties(wrapper_type = super::OutputArea)]
@@ -51,6 +59,10 @@ mod imp {
         scale: Cell<f64>,
         translate: Cell<[i16; 2]>,
         bounds: RefCell<Rect>,
+        pub(super) monitors: RefCell<Vec<Monitor>>,
+        pub(super) invalid_outputs: RefCell<Vec<OutputId>>,
+        drawing_monitor: Cell<bool>,
+        monitor_region: RefCell<Option<Rect>>,
     }
 
     #[object_subclass]
@@ -69,6 +81,9 @@ mod imp {
                 vec![
                     Signal::builder("output-selected").param_types([Output::static_type()]).build(),
                     Signal::builder("output-deselected").build(),
+                    Signal::builder("monitor-drawn")
+                        .param_types([Type::I32, Type::I32, Type::U32, Type::U32])
+                        .build(),
                 ]
             })
         }
@@ -232,7 +247,9 @@ mod imp {
         }
 
         fn get_bounds(outputs: &Outputs) -> Rect {
-            Rect::bounds(outputs.iter::<Output>().map(Result::unwrap).map(|o| o.rect()).collect())
+            Rect::bounds(
+                outputs.iter::<Output>().map(Result::unwrap).map(|o| o.panning_rect()).collect(),
+            )
         }
 
         fn on_draw(&self, cr: &cairo::Context, w: i32, h: i32) {
@@ -244,31 +261,88 @@ mod imp {
             let screen_rect = bounds.transform(scale, translate);
             context.draw_screen(&screen_rect, self.get_screen_line_width(w, h));
 
-            for o in self.outputs.borrow().iter::<Output>().map(Result::unwrap) {
+            for stack in Self::get_stacks(&self.outputs.borrow()) {
+                let o = &stack[0];
+                if o.panning().is_some() {
+                    context.draw_panning(&o.panning_rect().transform(scale, translate));
+                }
                 let output_rect = o.rect().transform(scale, translate);
                 context.draw_output(&output_rect);
+                if stack.iter().any(|o| self.invalid_outputs.borrow().contains(&o.id())) {
+                    context.draw_invalid_output(&output_rect);
+                }
                 if let Some(selected) = self.selected_output.borrow().as_ref() {
-                    if o == *selected {
+                    if stack.contains(selected) {
                         context.draw_selected_output(&output_rect);
                     }
                 }
-                let mut name = o.name();
-                let mut product_name = o.product_name();
-                if o.primary() {
-                    name = format!("[{name}]");
-                    product_name = product_name.map(|s| format!("[{s}]"));
+                if let [o] = stack.as_slice() {
+                    let mut name = o.name();
+                    let mut product_name = o.product_name();
+                    if o.primary() {
+                        name = format!("[{name}]");
+                        product_name = product_name.map(|s| format!("[{s}]"));
+                    }
+                    context.draw_output_label(
+                        &output_rect,
+                        o.rotation(),
+                        o.reflection(),
+                        &name,
+                        product_name.as_deref(),
+                    );
+                } else {
+                    // Clones share one rectangle, list all of them
+                    let names = stack
+                        .iter()
+                        .map(|o| {
+                            let name = o.product_name().unwrap_or_else(|| o.name());
+                            if o.primary() {
+                                format!("[{name}]")
+                            } else {
+                                name
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    context.draw_output_label(
+                        &output_rect,
+                        o.rotation(),
+                        o.reflection(),
+                        &names,
+                        None,
+                    );
+                }
+            }
+
+            for monitor in self.monitors.borrow().iter().filter(|m| !m.automatic) {
+                context.draw_monitor(&monitor.rect.transform(scale, translate), &monitor.name);
+            }
+            if let Some(region) = self.monitor_region.borrow().as_ref() {
+                context.draw_monitor(&region.transform(scale, translate), "");
+            }
+        }
+
+        /// Groups clones into stacks, ordered by the last drawn output of each
+        /// stack.
+        fn get_stacks(outputs: &Outputs) -> Vec<Vec<Output>> {
+            let mut stacks: Vec<Vec<Output>> = Vec::new();
+            for output in outputs.iter::<Output>().map(Result::unwrap) {
+                if let Some(i) = stacks.iter().position(|s| output.is_clone_of(&s[0])) {
+                    let mut stack = stacks.remove(i);
+                    stack.push(output);
+                    stacks.push(stack);
+                } else {
+                    stacks.push(vec![output]);
                 }
-                context.draw_output_label(
-                    &output_rect,
-                    o.rotation(),
-                    o.reflection(),
-                    &name,
-                    product_name.as_deref(),
-                );
             }
+            stacks
         }
 
         fn on_drag_begin(&self, _g: &GestureDrag, start_x: f64, start_y: f64) {
+            if self.drawing_monitor.get() {
+                self.monitor_region.replace(None);
+                return;
+            }
             if let Some(output) = self.get_output_at(start_x, start_y) {
                 let scale = self.scale.get();
                 let [dx, dy] = self.translate.get().map(f64::from);
@@ -296,6 +370,14 @@ mod imp {
 
         #[allow(clippy::cast_possible_truncation)]
         fn on_drag_update(&self, g: &GestureDrag, offset_x: f64, offset_y: f64) {
+            if self.drawing_monitor.get() {
+                if let Some((x, y)) = g.start_point() {
+                    self.monitor_region
+                        .replace(self.get_monitor_region([x, y], [x + offset_x, y + offset_y]));
+                    self.obj().queue_draw();
+                }
+                return;
+            }
             if let Some(output) = self.selected_output.borrow().as_ref() {
                 let outputs = self.outputs.borrow();
 
@@ -341,8 +423,13 @@ mod imp {
 
                 // Update new position
                 if new_x != output.x() || new_y != output.y() {
+                    if output.mirror() > 0 {
+                        // Dragging a mirror away stops mirroring
+                        output.unmirror();
+                    }
                     output.set_x(new_x);
                     output.set_y(new_y);
+                    outputs.update_mirrors();
                     self.resize(self.obj().width(), self.obj().height());
                     self.obj().queue_draw();
                 }
@@ -398,6 +485,14 @@ mod imp {
             let bounds = Self::get_bounds(outputs);
             let bc = bounds.center();
 
+            // Clones are moved as one, only the first output of a stack is considered
+            let mut stacked = HashMap::new();
+            for stack in Self::get_stacks(outputs) {
+                for o in &stack[1..] {
+                    stacked.insert(o.id(), stack[0].id());
+                }
+            }
+
             for output in outputs.iter::<Output>().map(Result::unwrap) {
                 let r = output.rect();
                 let c = r.center();
@@ -415,6 +510,9 @@ mod imp {
             let mut max_loops = (f64::from(bounds.width().max(bounds.height())) / step) as u16;
             loop {
                 for i in 0..(outputs.n_items() as usize) {
+                    if stacked.contains_key(&outputs.index(i).id()) {
+                        continue;
+                    }
                     // Current position
                     let mut r = data[&outputs.index(i).id()].0.clone();
                     // Unit direction
@@ -448,7 +546,8 @@ mod imp {
 
                     // Check if move has caused an overlap with other rects
                     for other in outputs.iter::<Output>().map(Result::unwrap) {
-                        if other.id() == outputs.index(i).id() {
+                        if other.id() == outputs.index(i).id() || stacked.contains_key(&other.id())
+                        {
                             continue;
                         }
                         if let Some(intersect) = r.intersect(&data[&other.id()].0) {
@@ -498,11 +597,12 @@ mod imp {
                 }
             }
             for output in outputs.iter::<Output>().map(Result::unwrap) {
-                let x = data[&output.id()].0.x();
+                let id = stacked.get(&output.id()).copied().unwrap_or(output.id());
+                let x = data[&id].0.x();
                 if x != output.x() {
                     output.set_x(x);
                 }
-                let y = data[&output.id()].0.y();
+                let y = data[&id].0.y();
                 if y != output.y() {
                     output.set_y(y);
                 }
@@ -510,7 +610,38 @@ mod imp {
         }
 
         fn on_drag_end(&self, g: &GestureDrag, offset_x: f64, offset_y: f64) {
+            if self.drawing_monitor.get() {
+                self.stop_drawing_monitor();
+                if let Some(region) = self.monitor_region.take() {
+                    self.obj().emit_by_name::<()>(
+                        "monitor-drawn",
+                        &[
+                            &i32::from(region.x()),
+                            &i32::from(region.y()),
+                            &u32::from(region.width()),
+                            &u32::from(region.height()),
+                        ],
+                    );
+                }
+                self.obj().queue_draw();
+                return;
+            }
             self.grab_offset.set([0., 0.]);
+            // An output dropped onto the origin of a differently sized output mirrors it
+            let selected = self.selected_output.borrow().clone();
+            if let Some(output) = selected {
+                let source = self.outputs.borrow().iter::<Output>().map(Result::unwrap).find(|o| {
+                    *o != output
+                        && o.mirror() == 0
+                        && o.x() == output.x()
+                        && o.y() == output.y()
+                        && o.rect() != output.rect()
+                });
+                if let Some(source) = source {
+                    output.mirror_of(&source);
+                    self.obj().update(&output, Update::Mirror);
+                }
+            }
             // Update cursor
             if let Some((x, y)) = g.start_point() {
                 match self.get_output_at(x + offset_x, y + offset_y) {
@@ -523,7 +654,9 @@ mod imp {
         fn on_motion(&self, _ecm: &EventControllerMotion, x: f64, y: f64) {
             // TODO if not is_dragging instead
             let [dx, dy] = self.grab_offset.get();
-            if dx == 0. || dy == 0. {
+            if self.drawing_monitor.get() {
+                self.obj().set_cursor_from_name(Some("crosshair"));
+            } else if dx == 0. || dy == 0. {
                 // Update cursor
                 match self.get_output_at(x, y) {
                     Some(_) => self.obj().set_cursor_from_name(Some("pointer")),
@@ -544,10 +677,16 @@ mod imp {
 
             let scale = self.scale.get();
             let [dx, dy] = self.translate.get().map(f64::from);
-            output.enable_at(
-                ((x - dx).max(0.) / scale).round() as i16,
-                ((y - dy).max(0.) / scale).round() as i16,
-            );
+            if let Some(source) = self.get_output_at(x, y).filter(|o| o.mirror() == 0) {
+                // Dropped onto another output, mirror it
+                output.enable_at(source.x(), source.y());
+                output.mirror_of(&source);
+            } else {
+                output.enable_at(
+                    ((x - dx).max(0.) / scale).round() as i16,
+                    ((y - dy).max(0.) / scale).round() as i16,
+                );
+            }
             self.obj().update(&output, Update::Enabled);
 
             true
@@ -556,7 +695,7 @@ mod imp {
         fn on_drop_motion(_dt: &DropTarget, _x: f64, _y: f64) -> DragAction { DragAction::MOVE }
 
         fn on_click(&self, _gc: &GestureClick, n_press: i32, x: f64, y: f64) {
-            if n_press == 2 {
+            if n_press == 2 && !self.drawing_monitor.get() {
                 if let Some(output) = self.get_output_at(x, y) {
                     output.set_primary(!output.primary());
                     self.obj().update(&output, Update::Primary);
@@ -598,6 +737,12 @@ mod imp {
             _keycode: u32,
             _state: ModifierType,
         ) -> Propagation {
+            if keyval == Key::Escape && self.drawing_monitor.get() {
+                self.stop_drawing_monitor();
+                self.monitor_region.replace(None);
+                self.obj().queue_draw();
+                return Propagation::Stop;
+            }
             if let Some(selected) = self.selected_output.borrow().as_ref() {
                 let [x, y] = [selected.x(), selected.y()];
                 let move_dist = self.config.borrow().display.pos_move_dist;
@@ -621,6 +766,10 @@ mod imp {
                     _ => false,
                 };
                 if update_pos {
+                    if selected.mirror() > 0 {
+                        selected.unmirror();
+                    }
+                    self.outputs.borrow().update_mirrors();
                     self.resize(self.obj().width(), self.obj().height());
                     self.obj().queue_draw();
                     return Propagation::Stop;
@@ -629,6 +778,61 @@ mod imp {
             Propagation::Proceed
         }
 
+        pub(super) fn start_drawing_monitor(&self) {
+            self.deselect();
+            self.obj().emit_by_name::<()>("output-deselected", &[]);
+            self.drawing_monitor.set(true);
+            self.obj().grab_focus();
+            self.obj().set_cursor_from_name(Some("crosshair"));
+            self.obj().queue_draw();
+        }
+
+        fn stop_drawing_monitor(&self) {
+            self.drawing_monitor.set(false);
+            self.obj().set_cursor_from_name(Some("default"));
+        }
+
+        /// Region of the screen spanned by two points of the widget, snapped to
+        /// the edges and centers of outputs and clamped to the screen.
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        fn get_monitor_region(&self, start: [f64; 2], end: [f64; 2]) -> Option<Rect> {
+            let scale = self.scale.get();
+            if scale <= 0. {
+                return None;
+            }
+            let [dx, dy] = self.translate.get().map(f64::from);
+            let bounds = self.bounds.borrow();
+            let outputs = self.outputs.borrow();
+            let rects = outputs.iter::<Output>().map(Result::unwrap).map(|o| o.rect());
+            let mut snap_x = Vec::new();
+            let mut snap_y = Vec::new();
+            for r in rects {
+                snap_x.extend([r.left(), r.center().x, r.right()].map(f64::from));
+                snap_y.extend([r.top(), r.center().y, r.bottom()].map(f64::from));
+            }
+            let snap = |v: f64, to: &[f64]| {
+                to.iter()
+                    .copied()
+                    .filter(|s| (s - v).abs() * scale < MONITOR_SNAP_DIST)
+                    .min_by(|a, b| (a - v).abs().total_cmp(&(b - v).abs()))
+                    .unwrap_or(v)
+            };
+            let [x1, x2] = [start[0], end[0]].map(|x| {
+                snap(((x - dx) / scale).round(), &snap_x)
+                    .clamp(f64::from(bounds.left()), f64::from(bounds.right()))
+            });
+            let [y1, y2] = [start[1], end[1]].map(|y| {
+                snap(((y - dy) / scale).round(), &snap_y)
+                    .clamp(f64::from(bounds.top()), f64::from(bounds.bottom()))
+            });
+            let [width, height] = [(x2 - x1).abs(), (y2 - y1).abs()];
+            if width < 1. || height < 1. {
+                return None;
+            }
+            Some(Rect::new(x1.min(x2) as i16, y1.min(y2) as i16, width as u16, height as u16))
+        }
+
         fn get_output_at(&self, x: f64, y: f64) -> Option<Output> {
             let scale = self.scale.get();
             let [dx, dy] = self.translate.get();
@@ -687,6 +891,21 @@ impl OutputArea {
         );
     }
 
+    /// Shows the user defined monitors of `monitors`.
+    pub fn set_monitors(&self, monitors: &[Monitor]) {
+        self.imp().monitors.replace(monitors.to_vec());
+        self.queue_draw();
+    }
+
+    /// Marks the outputs that prevent the configuration from being applied.
+    pub fn set_invalid_outputs(&self, outputs: Vec<OutputId>) {
+        self.imp().invalid_outputs.replace(outputs);
+        self.queue_draw();
+    }
+
+    /// Lets the user draw a region for a new monitor with the next drag.
+    pub fn draw_monitor(&self) { self.imp().start_drawing_monitor(); }
+
     pub fn update(&self, output: &Output, update: Update) {
         // Add/Remove
         match update {
@@ -700,13 +919,17 @@ impl OutputArea {
                 o.set_primary(o == *output && output.primary());
             }
         }
+        // Keep mirrors on top of their source
+        self.outputs().update_mirrors();
         // Mind the gap
         match update {
             Update::Enabled
             | Update::Disabled
             | Update::Resolution
             | Update::Rotation
-            | Update::Scale => {
+            | Update::Scale
+            | Update::Transform
+            | Update::Mirror => {
                 imp::OutputArea::mind_the_gap_and_overlap(&self.outputs());
             }
             _ => (),
@@ -718,14 +941,17 @@ impl OutputArea {
             | Update::Resolution
             | Update::Rotation
             | Update::Scale
-            | Update::Position => {
+            | Update::Position
+            | Update::Mirror
+            | Update::Panning
+            | Update::Transform => {
                 self.imp().resize(self.width(), self.height());
             }
             _ => (),
         }
         // Redraw
         match update {
-            Update::Refresh => (),
+            Update::Refresh | Update::Gamma => (),
             _ => self.queue_draw(),
         }
     }
@@ -0,0 +1,200 @@
+use glib::subclass::types::ObjectSubclassIsExt;
+use glib::{closure_local, wrapper, Object};
+use gtk::prelude::ObjectExt;
+use gtk::{glib, Widget};
+
+use crate::data::panning::Panning;
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+    use std::sync::OnceLock;
+
+    use gettextrs::gettext;
+    use glib::subclass::object::{ObjectImpl, ObjectImplExt};
+    use glib::subclass::types::{ObjectSubclass, ObjectSubclassExt};
+    use glib::subclass::Signal;
+    use glib::{clone, object_subclass};
+    use gtk::prelude::{CheckButtonExt, EditableExt, GridExt, ObjectExt, WidgetExt};
+    use gtk::subclass::widget::{WidgetClassExt, WidgetImpl};
+    use gtk::{glib, BinLayout, CheckButton, Grid, Label, SpinButton, Widget};
+
+    use crate::data::panning::Panning;
+    use crate::window::SPACING;
+
+    pub struct PanningEditor {
+        grid: Grid,
+        cb_enabled: CheckButton,
+        /// Width and height of the panning area.
+        size: [SpinButton; 2],
+        /// Width, height, x and y of the tracking area.
+        tracking: [SpinButton; 4],
+        /// Left, top, right and bottom border.
+        border: [SpinButton; 4],
+        /// Size of the output used when panning gets enabled.
+        pub(super) output_size: Cell<[u16; 2]>,
+        panning: RefCell<Option<Panning>>,
+        updating: Cell<bool>,
+    }
+
+    impl Default for PanningEditor {
+        fn default() -> Self {
+            let spin = |min: i16| {
+                let spin = SpinButton::with_range(min.into(), i16::MAX.into(), 1.);
+                spin.set_width_chars(5);
+                spin
+            };
+            Self {
+                grid: Grid::builder()
+                    .row_spacing(SPACING.into())
+                    .column_spacing(SPACING.into())
+                    .build(),
+                cb_enabled: CheckButton::with_label(&gettext("Pan over a larger area")),
+                size: [(); 2].map(|()| spin(0)),
+                tracking: [(); 4].map(|()| spin(0)),
+                border: [(); 4].map(|()| spin(i16::MIN)),
+                output_size: Cell::default(),
+                panning: RefCell::default(),
+                updating: Cell::default(),
+            }
+        }
+    }
+
+    #[object_subclass]
+    impl ObjectSubclass for PanningEditor {
+        type ParentType = Widget;
+        type Type = super::PanningEditor;
+
+        const NAME: &'static str = "PanningEditor";
+
+        fn class_init(klass: &mut Self::Class) { klass.set_layout_manager_type::<BinLayout>(); }
+    }
+
+    impl ObjectImpl for PanningEditor {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("panning-changed").build()])
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.grid.attach(&self.cb_enabled, 0, 0, 8, 1);
+            let rows: [(String, &[SpinButton], &[&str]); 3] = [
+                // Translators: Width and height of the panning area
+                (gettext("Area"), &self.size, &["\u{00D7}"]),
+                // Translators: Area the pointer pans the output in, empty for the whole screen
+                (gettext("Tracking"), &self.tracking, &["\u{00D7}", "+", "+"]),
+                // Translators: Left, top, right and bottom distance at which panning starts
+                (gettext("Border"), &self.border, &["/", "/", "/"]),
+            ];
+            for (row, (name, spins, separators)) in (1..).zip(rows) {
+                self.grid.attach(&Label::builder().label(name).xalign(0.).build(), 0, row, 1, 1);
+                for (i, spin) in (0..).zip(spins) {
+                    if i > 0 {
+                        let sep = separators[usize::try_from(i - 1).unwrap_or_default()];
+                        self.grid.attach(&Label::new(Some(sep)), 2 * i, row, 1, 1);
+                    }
+                    self.grid.attach(spin, 2 * i + 1, row, 1, 1);
+                    spin.connect_value_changed(clone!(
+                        #[weak(rename_to = this)]
+                        self,
+                        move |_| this.on_changed()
+                    ));
+                }
+            }
+            self.cb_enabled.connect_toggled(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_| this.on_changed()
+            ));
+            self.grid.set_parent(&*self.obj());
+            self.update_sensitivity();
+        }
+
+        fn dispose(&self) { self.grid.unparent(); }
+    }
+
+    impl WidgetImpl for PanningEditor {}
+
+    impl PanningEditor {
+        pub(super) fn panning(&self) -> Option<Panning> { *self.panning.borrow() }
+
+        pub(super) fn set_panning(&self, panning: Option<Panning>) {
+            self.updating.set(true);
+            let p = panning.unwrap_or_else(|| {
+                let [width, height] = self.output_size.get();
+                Panning::new(width, height)
+            });
+            self.cb_enabled.set_active(panning.is_some());
+            for (spin, v) in self.size.iter().zip([p.width, p.height]) {
+                spin.set_value(v.into());
+            }
+            for (spin, v) in self.tracking.iter().zip([
+                i32::from(p.track_width),
+                i32::from(p.track_height),
+                i32::from(p.track_x),
+                i32::from(p.track_y),
+            ]) {
+                spin.set_value(v.into());
+            }
+            for (spin, v) in self.border.iter().zip(p.border) {
+                spin.set_value(v.into());
+            }
+            self.panning.replace(panning);
+            self.update_sensitivity();
+            self.updating.set(false);
+        }
+
+        fn on_changed(&self) {
+            if self.updating.get() {
+                return;
+            }
+            let value = |spin: &SpinButton| spin.value_as_int();
+            let unsigned = |spin: &SpinButton| u16::try_from(value(spin)).unwrap_or_default();
+            let signed = |spin: &SpinButton| i16::try_from(value(spin)).unwrap_or_default();
+            let panning = self.cb_enabled.is_active().then(|| Panning {
+                width: unsigned(&self.size[0]),
+                height: unsigned(&self.size[1]),
+                track_width: unsigned(&self.tracking[0]),
+                track_height: unsigned(&self.tracking[1]),
+                track_x: signed(&self.tracking[2]),
+                track_y: signed(&self.tracking[3]),
+                border: self.border.each_ref().map(signed),
+            });
+            self.panning.replace(panning);
+            self.update_sensitivity();
+            self.obj().emit_by_name::<()>("panning-changed", &[]);
+        }
+
+        fn update_sensitivity(&self) {
+            let active = self.cb_enabled.is_active();
+            for spin in self.size.iter().chain(&self.tracking).chain(&self.border) {
+                spin.set_sensitive(active);
+            }
+        }
+    }
+}
+
+wrapper! {
+    pub struct PanningEditor(ObjectSubclass<imp::PanningEditor>) @extends Widget;
+}
+
+impl PanningEditor {
+    pub fn new() -> Self { Object::new() }
+
+    pub fn panning(&self) -> Option<Panning> { self.imp().panning() }
+
+    /// Shows `panning` without emitting `panning-changed`. Enabling panning
+    /// starts from `output_size`.
+    pub fn set_panning(&self, panning: Option<Panning>, output_size: [u16; 2]) {
+        self.imp().output_size.set(output_size);
+        self.imp().set_panning(panning);
+    }
+
+    pub fn connect_panning_changed(&self, callback: impl Fn(&Self) + 'static) {
+        self.connect_closure("panning-changed", false, closure_local!(|editor| callback(editor)));
+    }
+}
+
+impl Default for PanningEditor {
+    fn default() -> Self { Self::new() }
+}
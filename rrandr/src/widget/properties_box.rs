@@ -0,0 +1,194 @@
+use glib::subclass::types::ObjectSubclassIsExt;
+use glib::{closure_local, wrapper, Object};
+use gtk::prelude::ObjectExt;
+use gtk::{glib, Widget};
+use x11rb::protocol::xproto::Atom;
+
+use crate::x11::property::OutputProperty;
+
+/// Ranges with more values than this are edited with a spin button.
+const MAX_RANGE_DROPDOWN: i64 = 32;
+
+mod imp {
+    use std::sync::OnceLock;
+
+    use gettextrs::gettext;
+    use glib::subclass::object::{ObjectImpl, ObjectImplExt};
+    use glib::subclass::types::{ObjectSubclass, ObjectSubclassExt};
+    use glib::subclass::Signal;
+    use glib::{clone, object_subclass, Type};
+    use gtk::prelude::{ObjectExt, WidgetExt};
+    use gtk::subclass::widget::{WidgetClassExt, WidgetImpl};
+    use gtk::{
+        glib, pango, Align, BinLayout, DropDown, FlowBox, Label, Orientation, SelectionMode,
+        SpinButton, Widget,
+    };
+    use x11rb::protocol::xproto::Atom;
+
+    use super::MAX_RANGE_DROPDOWN;
+    use crate::widget::details_child::DetailsChild;
+    use crate::window::SPACING;
+    use crate::x11::property::{OutputProperty, PropertyValues};
+
+    pub struct PropertiesBox {
+        fb_properties: FlowBox,
+    }
+
+    impl Default for PropertiesBox {
+        fn default() -> Self {
+            Self {
+                fb_properties: FlowBox::builder()
+                    .row_spacing(SPACING.into())
+                    .column_spacing(SPACING.into())
+                    .orientation(Orientation::Horizontal)
+                    .selection_mode(SelectionMode::None)
+                    .max_children_per_line(u32::MAX)
+                    .halign(Align::Fill)
+                    .hexpand(true)
+                    .build(),
+            }
+        }
+    }
+
+    #[object_subclass]
+    impl ObjectSubclass for PropertiesBox {
+        type ParentType = Widget;
+        type Type = super::PropertiesBox;
+
+        const NAME: &'static str = "PropertiesBox";
+
+        fn class_init(klass: &mut Self::Class) { klass.set_layout_manager_type::<BinLayout>(); }
+    }
+
+    impl ObjectImpl for PropertiesBox {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![Signal::builder("property-changed")
+                    .param_types([Type::U32, Type::I64])
+                    .build()]
+            })
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.fb_properties.set_parent(&*self.obj());
+        }
+
+        fn dispose(&self) { self.fb_properties.unparent(); }
+    }
+
+    impl WidgetImpl for PropertiesBox {}
+
+    impl PropertiesBox {
+        pub(super) fn set_properties(&self, properties: &[OutputProperty]) {
+            while let Some(child) = self.fb_properties.first_child() {
+                self.fb_properties.remove(&child);
+            }
+            if properties.is_empty() {
+                self.fb_properties.append(
+                    &Label::builder()
+                        .label(gettext("No properties"))
+                        .css_classes(["dim-label"])
+                        .build(),
+                );
+            }
+            for property in properties {
+                let control = self.create_control(property);
+                control.set_sensitive(!property.immutable);
+                if property.pending {
+                    control.set_tooltip_text(Some(&gettext(
+                        "Changes take effect with the next configuration change",
+                    )));
+                }
+                // Property names can contain underscores, don't use them as mnemonics
+                let label = format!("_{}", property.name.replace('_', "__"));
+                let child = DetailsChild::new(&label, &control, "");
+                child.set_visible(true);
+                self.fb_properties.append(&child);
+            }
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        fn create_control(&self, property: &OutputProperty) -> Widget {
+            let Some(value) = property.value.filter(|_| property.is_editable()) else {
+                return Label::builder()
+                    .label(&property.text)
+                    .selectable(true)
+                    .ellipsize(pango::EllipsizeMode::End)
+                    .max_width_chars(40)
+                    .tooltip_text(&property.text)
+                    .build()
+                    .into();
+            };
+            let atom = property.atom;
+            match &property.values {
+                PropertyValues::Range(min, max) if max - min >= MAX_RANGE_DROPDOWN => {
+                    let spin = SpinButton::with_range(*min as f64, *max as f64, 1.);
+                    spin.set_value(value as f64);
+                    spin.connect_value_changed(clone!(
+                        #[weak(rename_to = this)]
+                        self,
+                        move |spin| this.notify_changed(atom, spin.value_as_int().into())
+                    ));
+                    spin.into()
+                }
+                PropertyValues::Range(min, max) => {
+                    let values = (*min..=*max).map(|v| (v, v.to_string())).collect::<Vec<_>>();
+                    self.create_dropdown(atom, value, values)
+                }
+                PropertyValues::Enum(values) => self.create_dropdown(atom, value, values.clone()),
+                PropertyValues::Any => unreachable!("property should be editable"),
+            }
+        }
+
+        fn create_dropdown(&self, atom: Atom, value: i64, values: Vec<(i64, String)>) -> Widget {
+            let dd = DropDown::from_strings(
+                &values.iter().map(|(_, name)| name.as_str()).collect::<Vec<_>>(),
+            );
+            if let Some(pos) = values.iter().position(|(v, _)| *v == value) {
+                dd.set_selected(u32::try_from(pos).unwrap_or(gtk::INVALID_LIST_POSITION));
+            }
+            dd.connect_selected_notify(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |dd| {
+                    if let Some((v, _)) =
+                        usize::try_from(dd.selected()).ok().and_then(|i| values.get(i))
+                    {
+                        this.notify_changed(atom, *v);
+                    }
+                }
+            ));
+            dd.into()
+        }
+
+        fn notify_changed(&self, atom: Atom, value: i64) {
+            self.obj().emit_by_name::<()>("property-changed", &[&atom, &value]);
+        }
+    }
+}
+
+wrapper! {
+    pub struct PropertiesBox(ObjectSubclass<imp::PropertiesBox>) @extends Widget;
+}
+
+impl PropertiesBox {
+    pub fn new() -> Self { Object::new() }
+
+    pub fn set_properties(&self, properties: &[OutputProperty]) {
+        self.imp().set_properties(properties);
+    }
+
+    pub fn connect_property_changed(&self, callback: impl Fn(&Self, Atom, i64) + 'static) {
+        self.connect_closure(
+            "property-changed",
+            false,
+            closure_local!(|properties, atom, value| callback(properties, atom, value)),
+        );
+    }
+}
+
+impl Default for PropertiesBox {
+    fn default() -> Self { Self::new() }
+}
@@ -0,0 +1,267 @@
+use glib::subclass::types::ObjectSubclassIsExt;
+use glib::{closure_local, wrapper, Object};
+use gtk::prelude::ObjectExt;
+use gtk::{glib, Widget};
+
+mod imp {
+    use std::cell::Cell;
+    use std::sync::OnceLock;
+
+    use gettextrs::gettext;
+    use glib::subclass::object::{ObjectImpl, ObjectImplExt};
+    use glib::subclass::types::{ObjectSubclass, ObjectSubclassExt};
+    use glib::subclass::Signal;
+    use glib::{clone, object_subclass};
+    use gtk::prelude::{
+        BoxExt, ButtonExt, EditableExt, GridExt, ObjectExt, RangeExt, ScaleExt, ToggleButtonExt,
+        WidgetExt,
+    };
+    use gtk::subclass::widget::{WidgetClassExt, WidgetImpl};
+    use gtk::{
+        glib, BinLayout, Box, Button, Grid, Label, Orientation, PositionType, Scale, SpinButton,
+        Stack, ToggleButton, Widget,
+    };
+
+    use crate::utils::nearly_eq;
+    use crate::window::SPACING;
+
+    const LOCKED_PAGE: &str = "locked";
+    const UNLOCKED_PAGE: &str = "unlocked";
+
+    pub struct ScaleEditor {
+        grid: Grid,
+        stack: Stack,
+        /// Scale of both axes in percent while the aspect ratio is locked.
+        slider: Scale,
+        /// Scale of the X and Y axis while the aspect ratio is unlocked.
+        spins: [SpinButton; 2],
+        tb_lock: ToggleButton,
+        /// Resolution to scale the output to.
+        target: [SpinButton; 2],
+        fit: Button,
+        /// Size of the rotated mode of the output.
+        pub(super) mode_size: Cell<[u16; 2]>,
+        scale: Cell<[f64; 2]>,
+        updating: Cell<bool>,
+    }
+
+    impl Default for ScaleEditor {
+        fn default() -> Self {
+            let slider = Scale::with_range(Orientation::Horizontal, 1., 200., 1.);
+            slider.set_slider_size_fixed(true);
+            slider.set_draw_value(true);
+            slider.set_value_pos(PositionType::Right);
+            slider.set_value(100.);
+            slider.add_mark(100., PositionType::Bottom, None);
+            slider.set_flippable(true);
+            slider.set_format_value_func(|_, v| format!("{:.2}", v / 100.));
+            slider.set_hexpand(true);
+            slider.set_width_request(200);
+            let spin = || {
+                let spin = SpinButton::with_range(0.01, 8., 0.01);
+                spin.set_digits(3);
+                spin.set_value(1.);
+                spin.set_width_chars(5);
+                spin
+            };
+            let target = || {
+                let spin = SpinButton::with_range(1., f64::from(i16::MAX), 1.);
+                spin.set_width_chars(5);
+                spin
+            };
+            Self {
+                grid: Grid::builder()
+                    .row_spacing(SPACING.into())
+                    .column_spacing(SPACING.into())
+                    .build(),
+                stack: Stack::new(),
+                slider,
+                spins: [(); 2].map(|()| spin()),
+                tb_lock: ToggleButton::builder()
+                    .icon_name("changes-prevent-symbolic")
+                    .tooltip_text(gettext("Keep aspect ratio"))
+                    .active(true)
+                    .build(),
+                target: [(); 2].map(|()| target()),
+                fit: Button::builder()
+                    .label(gettext("Fit"))
+                    .tooltip_text(gettext("Scale to this resolution"))
+                    .build(),
+                mode_size: Cell::default(),
+                scale: Cell::new([1., 1.]),
+                updating: Cell::default(),
+            }
+        }
+    }
+
+    #[object_subclass]
+    impl ObjectSubclass for ScaleEditor {
+        type ParentType = Widget;
+        type Type = super::ScaleEditor;
+
+        const NAME: &'static str = "ScaleEditor";
+
+        fn class_init(klass: &mut Self::Class) { klass.set_layout_manager_type::<BinLayout>(); }
+    }
+
+    impl ObjectImpl for ScaleEditor {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("scale-changed").build()])
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.stack.add_named(&self.slider, Some(LOCKED_PAGE));
+            let bx_spins = Box::new(Orientation::Horizontal, SPACING.into());
+            bx_spins.append(&self.spins[0]);
+            bx_spins.append(&Label::new(Some("\u{00D7}")));
+            bx_spins.append(&self.spins[1]);
+            self.stack.add_named(&bx_spins, Some(UNLOCKED_PAGE));
+            self.grid.attach(&self.stack, 0, 0, 3, 1);
+            self.grid.attach(&self.tb_lock, 3, 0, 1, 1);
+            self.grid.attach(&self.target[0], 0, 1, 1, 1);
+            self.grid.attach(&Label::new(Some("\u{00D7}")), 1, 1, 1, 1);
+            self.grid.attach(&self.target[1], 2, 1, 1, 1);
+            self.grid.attach(&self.fit, 3, 1, 1, 1);
+
+            self.slider.connect_value_changed(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |s| this.on_slider_changed(s)
+            ));
+            for (axis, spin) in self.spins.iter().enumerate() {
+                spin.connect_value_changed(clone!(
+                    #[weak(rename_to = this)]
+                    self,
+                    move |s| this.on_spin_changed(axis, s)
+                ));
+            }
+            self.tb_lock.connect_toggled(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |tb| this.on_lock_toggled(tb)
+            ));
+            self.fit.connect_clicked(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_| this.on_fit_clicked()
+            ));
+
+            self.grid.set_parent(&*self.obj());
+        }
+
+        fn dispose(&self) { self.grid.unparent(); }
+    }
+
+    impl WidgetImpl for ScaleEditor {}
+
+    impl ScaleEditor {
+        pub(super) fn scale(&self) -> [f64; 2] { self.scale.get() }
+
+        pub(super) fn set_scale(&self, scale: [f64; 2]) {
+            self.updating.set(true);
+            self.scale.set(scale);
+            self.tb_lock.set_active(nearly_eq(scale[0], scale[1]));
+            self.update_widgets();
+            self.updating.set(false);
+        }
+
+        fn update_widgets(&self) {
+            let updating = self.updating.replace(true);
+            let scale = self.scale.get();
+            let locked = self.tb_lock.is_active();
+            self.stack.set_visible_child_name(if locked { LOCKED_PAGE } else { UNLOCKED_PAGE });
+            self.tb_lock.set_icon_name(if locked {
+                "changes-prevent-symbolic"
+            } else {
+                "changes-allow-symbolic"
+            });
+            self.slider.set_value(scale[0] * 100.);
+            for (spin, v) in self.spins.iter().zip(scale) {
+                spin.set_value(v);
+            }
+            for ((spin, size), s) in self.target.iter().zip(self.mode_size.get()).zip(scale) {
+                spin.set_value((f64::from(size) * s).round());
+            }
+            self.updating.set(updating);
+        }
+
+        fn update_scale(&self, scale: [f64; 2]) {
+            self.scale.set(scale);
+            self.update_widgets();
+            self.obj().emit_by_name::<()>("scale-changed", &[]);
+        }
+
+        fn on_slider_changed(&self, s: &Scale) {
+            if !self.updating.get() {
+                let scale = s.value() / 100.;
+                self.update_scale([scale, scale]);
+            }
+        }
+
+        fn on_spin_changed(&self, axis: usize, s: &SpinButton) {
+            if !self.updating.get() {
+                let mut scale = self.scale.get();
+                scale[axis] = s.value();
+                self.update_scale(scale);
+            }
+        }
+
+        fn on_lock_toggled(&self, tb: &ToggleButton) {
+            if self.updating.get() {
+                self.update_widgets();
+            } else if tb.is_active() {
+                let [x, _] = self.scale.get();
+                self.update_scale([x, x]);
+            } else {
+                self.update_widgets();
+            }
+        }
+
+        fn on_fit_clicked(&self) {
+            let mode_size = self.mode_size.get();
+            if mode_size.contains(&0) {
+                return;
+            }
+            let mut scale = [0.; 2];
+            for ((s, spin), size) in scale.iter_mut().zip(&self.target).zip(mode_size) {
+                *s = spin.value() / f64::from(size);
+            }
+            if !nearly_eq(scale[0], scale[1]) {
+                self.updating.set(true);
+                self.tb_lock.set_active(false);
+                self.updating.set(false);
+            }
+            self.update_scale(scale);
+        }
+    }
+}
+
+wrapper! {
+    pub struct ScaleEditor(ObjectSubclass<imp::ScaleEditor>) @extends Widget;
+}
+
+impl ScaleEditor {
+    pub fn new() -> Self { Object::new() }
+
+    /// Scale of the X and Y axis.
+    pub fn scale(&self) -> [f64; 2] { self.imp().scale() }
+
+    /// Shows `scale` without emitting `scale-changed`, locking the aspect
+    /// ratio if both axes are scaled the same. `mode_size` is the size of the
+    /// rotated mode before scaling, which gives the target resolution.
+    pub fn set_scale(&self, scale: [f64; 2], mode_size: [u16; 2]) {
+        self.imp().mode_size.set(mode_size);
+        self.imp().set_scale(scale);
+    }
+
+    pub fn connect_scale_changed(&self, callback: impl Fn(&Self) + 'static) {
+        self.connect_closure("scale-changed", false, closure_local!(|editor| callback(editor)));
+    }
+}
+
+impl Default for ScaleEditor {
+    fn default() -> Self { Self::new() }
+}
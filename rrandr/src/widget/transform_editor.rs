@@ -0,0 +1,380 @@
+use glib::subclass::types::ObjectSubclassIsExt;
+use glib::{closure_local, wrapper, Object};
+use gtk::prelude::ObjectExt;
+use gtk::{glib, Widget};
+
+use crate::data::transform::Transform;
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+    use std::f64::consts::PI;
+    use std::sync::OnceLock;
+
+    use gettextrs::gettext;
+    use glib::subclass::object::{ObjectImpl, ObjectImplExt};
+    use glib::subclass::types::{ObjectSubclass, ObjectSubclassExt};
+    use glib::subclass::Signal;
+    use glib::{clone, object_subclass, SignalHandlerId};
+    use gtk::prelude::{
+        BoxExt, ButtonExt, CastNone, DrawingAreaExtManual, EditableExt, GdkCairoContextExt,
+        GestureDragExt, GridExt, ListModelExt, ObjectExt, StyleContextExt, WidgetExt,
+    };
+    use gtk::subclass::widget::{WidgetClassExt, WidgetImpl};
+    use gtk::{
+        cairo, glib, Align, BinLayout, Box, Button, DrawingArea, DropDown, GestureDrag, Grid,
+        Label, Orientation, SpinButton, StringList, StringObject, Widget,
+    };
+
+    use crate::data::transform::{Transform, FILTERS};
+    use crate::window::{PADDING, SPACING};
+
+    const HANDLE_RADIUS: f64 = 5.;
+
+    pub struct TransformEditor {
+        root: Box,
+        /// Shows the output with the corners the screen is mapped to.
+        keystone: DrawingArea,
+        /// Row-major matrix of the transform.
+        matrix: [SpinButton; 9],
+        dd_filter: DropDown,
+        filters: StringList,
+        filter_selected_handler: RefCell<Option<SignalHandlerId>>,
+        reset: Button,
+        /// Size of the rotated mode of the output.
+        pub(super) size: Cell<[f64; 2]>,
+        pub(super) scale: Cell<[f64; 2]>,
+        transform: Cell<Option<Transform>>,
+        filter: RefCell<String>,
+        /// Index of the dragged corner and its position when the drag began.
+        drag: Cell<Option<(usize, [f64; 2])>>,
+        updating: Cell<bool>,
+    }
+
+    impl Default for TransformEditor {
+        fn default() -> Self {
+            let spin = || {
+                let spin = SpinButton::with_range(-32767., 32767., 0.01);
+                spin.set_digits(4);
+                spin.set_width_chars(7);
+                spin
+            };
+            let filters = StringList::new(&FILTERS);
+            Self {
+                root: Box::new(Orientation::Vertical, SPACING.into()),
+                keystone: DrawingArea::builder()
+                    .content_width(240)
+                    .content_height(160)
+                    .tooltip_text(gettext("Drag the corners to correct keystone distortion"))
+                    .build(),
+                matrix: [(); 9].map(|()| spin()),
+                dd_filter: DropDown::builder().model(&filters).build(),
+                filters,
+                filter_selected_handler: RefCell::default(),
+                reset: Button::with_mnemonic(&gettext("_Reset")),
+                size: Cell::new([1., 1.]),
+                scale: Cell::new([1., 1.]),
+                transform: Cell::default(),
+                filter: RefCell::default(),
+                drag: Cell::default(),
+                updating: Cell::default(),
+            }
+        }
+    }
+
+    #[object_subclass]
+    impl ObjectSubclass for TransformEditor {
+        type ParentType = Widget;
+        type Type = super::TransformEditor;
+
+        const NAME: &'static str = "TransformEditor";
+
+        fn class_init(klass: &mut Self::Class) { klass.set_layout_manager_type::<BinLayout>(); }
+    }
+
+    impl ObjectImpl for TransformEditor {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("transform-changed").build()])
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+
+            self.keystone.set_draw_func(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_, cr, w, h| this.on_draw(cr, w, h)
+            ));
+            let gesture_drag = GestureDrag::new();
+            gesture_drag.connect_drag_begin(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_, x, y| this.on_drag_begin(x, y)
+            ));
+            gesture_drag.connect_drag_update(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_, dx, dy| this.on_drag_update(dx, dy)
+            ));
+            gesture_drag.connect_drag_end(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_, _, _| this.drag.set(None)
+            ));
+            self.keystone.add_controller(gesture_drag);
+            self.root.append(&self.keystone);
+
+            let grid = Grid::builder()
+                .row_spacing(SPACING.into())
+                .column_spacing(SPACING.into())
+                .halign(Align::Start)
+                .build();
+            for (i, spin) in (0..).zip(&self.matrix) {
+                grid.attach(spin, i % 3, i / 3, 1, 1);
+                spin.connect_value_changed(clone!(
+                    #[weak(rename_to = this)]
+                    self,
+                    move |_| this.on_matrix_changed()
+                ));
+            }
+            self.root.append(&grid);
+
+            let bx_filter = Box::new(Orientation::Horizontal, SPACING.into());
+            // Translators: Filter used to sample the screen of a transformed output
+            bx_filter.append(&Label::new(Some(&gettext("Filter"))));
+            self.filter_selected_handler.replace(Some(self.dd_filter.connect_selected_notify(
+                clone!(
+                    #[weak(rename_to = this)]
+                    self,
+                    move |dd| this.on_filter_selected(dd)
+                ),
+            )));
+            bx_filter.append(&self.dd_filter);
+            self.reset.set_hexpand(true);
+            self.reset.set_halign(Align::End);
+            self.reset.connect_clicked(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_| this.update_transform(None)
+            ));
+            bx_filter.append(&self.reset);
+            self.root.append(&bx_filter);
+
+            self.root.set_parent(&*self.obj());
+        }
+
+        fn dispose(&self) { self.root.unparent(); }
+    }
+
+    impl WidgetImpl for TransformEditor {}
+
+    impl TransformEditor {
+        pub(super) fn transform(&self) -> Option<Transform> { self.transform.get() }
+
+        pub(super) fn filter(&self) -> String { self.filter.borrow().clone() }
+
+        pub(super) fn set_transform(&self, transform: Option<Transform>, filter: &str) {
+            self.transform.set(transform);
+            self.filter.replace(filter.to_owned());
+            self.update_filters(filter);
+            self.update_matrix();
+            self.keystone.queue_draw();
+        }
+
+        /// Lists the filters every CRTC supports and the current `filter`.
+        fn update_filters(&self, filter: &str) {
+            let hid = self.filter_selected_handler.borrow();
+            if let Some(hid) = hid.as_ref() {
+                self.dd_filter.block_signal(hid);
+            }
+            let n_builtin = u32::try_from(FILTERS.len()).unwrap_or_default();
+            self.filters.splice(n_builtin, self.filters.n_items().saturating_sub(n_builtin), &[]);
+            if !filter.is_empty() && !FILTERS.contains(&filter) {
+                self.filters.append(filter);
+            }
+            let pos = (0..self.filters.n_items())
+                .find(|&i| self.filters.string(i).is_some_and(|f| f == filter))
+                .unwrap_or_default();
+            self.dd_filter.set_selected(pos);
+            if let Some(hid) = hid.as_ref() {
+                self.dd_filter.unblock_signal(hid);
+            }
+        }
+
+        fn update_matrix(&self) {
+            self.updating.set(true);
+            let matrix = self.transform.get().unwrap_or_else(|| self.scale_transform());
+            for (spin, v) in self.matrix.iter().zip(matrix.0.as_flattened()) {
+                spin.set_value(*v);
+            }
+            self.reset.set_sensitive(self.transform.get().is_some());
+            self.updating.set(false);
+        }
+
+        fn update_transform(&self, transform: Option<Transform>) {
+            // A transform that only scales is no different from the scale
+            let transform = transform.filter(|t| *t != self.scale_transform());
+            self.transform.set(transform);
+            self.update_matrix();
+            self.keystone.queue_draw();
+            self.obj().emit_by_name::<()>("transform-changed", &[]);
+        }
+
+        fn on_matrix_changed(&self) {
+            if self.updating.get() {
+                return;
+            }
+            let mut matrix = [[0.; 3]; 3];
+            for (v, spin) in matrix.as_flattened_mut().iter_mut().zip(&self.matrix) {
+                *v = spin.value();
+            }
+            self.update_transform(Some(Transform(matrix)));
+        }
+
+        fn on_filter_selected(&self, dd: &DropDown) {
+            if let Some(filter) = dd.selected_item().and_downcast::<StringObject>() {
+                self.filter.replace(filter.string().into());
+                self.obj().emit_by_name::<()>("transform-changed", &[]);
+            }
+        }
+
+        fn scale_transform(&self) -> Transform {
+            let [sx, sy] = self.scale.get();
+            Transform::scale(sx, sy)
+        }
+
+        /// Corners of the screen area shown by the output before it is
+        /// transformed, i.e. the area of the scaled mode.
+        fn screen_corners(&self) -> [[f64; 2]; 4] {
+            let [width, height] = self.size.get();
+            let [sx, sy] = self.scale.get();
+            corners(width * sx, height * sy)
+        }
+
+        /// Corners of the output the corners of the screen area end up at.
+        fn output_corners(&self) -> [[f64; 2]; 4] {
+            let [width, height] = self.size.get();
+            let screen = self.screen_corners();
+            self.transform
+                .get()
+                .and_then(|t| t.inverse())
+                .and_then(|inverse| {
+                    let mapped = screen.map(|p| inverse.map(p));
+                    mapped
+                        .iter()
+                        .all(Option::is_some)
+                        .then(|| mapped.map(Option::unwrap_or_default))
+                })
+                .unwrap_or_else(|| corners(width, height))
+        }
+
+        /// Scale and offset fitting the output into the drawing area.
+        fn frame(&self, w: i32, h: i32) -> (f64, [f64; 2]) {
+            let [ow, oh] = self.size.get().map(|v| v.max(1.));
+            let pad = f64::from(PADDING) + HANDLE_RADIUS;
+            let [aw, ah] = [f64::from(w) - 2. * pad, f64::from(h) - 2. * pad].map(|v| v.max(1.));
+            let scale = (aw / ow).min(ah / oh);
+            (scale, [(f64::from(w) - ow * scale) / 2., (f64::from(h) - oh * scale) / 2.])
+        }
+
+        fn on_draw(&self, cr: &cairo::Context, w: i32, h: i32) {
+            let (scale, [dx, dy]) = self.frame(w, h);
+            let [ow, oh] = self.size.get();
+            #[allow(deprecated)]
+            let color = self.obj().style_context().color();
+            cr.set_source_color(&color);
+            cr.set_line_width(1.);
+            cr.rectangle(dx + 0.5, dy + 0.5, ow * scale - 1., oh * scale - 1.);
+            cr.stroke().unwrap();
+
+            let corners = self.output_corners().map(|[x, y]| [dx + x * scale, dy + y * scale]);
+            for [x, y] in corners {
+                cr.line_to(x, y);
+            }
+            cr.close_path();
+            cr.save().unwrap();
+            cr.set_source_rgba(
+                f64::from(color.red()),
+                f64::from(color.green()),
+                f64::from(color.blue()),
+                0.2,
+            );
+            cr.fill_preserve().unwrap();
+            cr.restore().unwrap();
+            cr.set_line_width(2.);
+            cr.stroke().unwrap();
+            for [x, y] in corners {
+                cr.arc(x, y, HANDLE_RADIUS, 0., 2. * PI);
+                cr.fill().unwrap();
+            }
+        }
+
+        fn on_drag_begin(&self, x: f64, y: f64) {
+            let (scale, [dx, dy]) = self.frame(self.keystone.width(), self.keystone.height());
+            let corner = self
+                .output_corners()
+                .map(|[cx, cy]| [dx + cx * scale, dy + cy * scale])
+                .into_iter()
+                .enumerate()
+                .map(|(i, c)| (i, c, (c[0] - x).hypot(c[1] - y)))
+                .filter(|(_, _, dist)| *dist <= 2. * HANDLE_RADIUS)
+                .min_by(|a, b| a.2.total_cmp(&b.2));
+            self.drag.set(corner.map(|(i, c, _)| (i, c)));
+        }
+
+        fn on_drag_update(&self, offset_x: f64, offset_y: f64) {
+            let Some((i, [start_x, start_y])) = self.drag.get() else {
+                return;
+            };
+            let (scale, [dx, dy]) = self.frame(self.keystone.width(), self.keystone.height());
+            let [w, h] = self.size.get();
+            let mut corners = self.output_corners();
+            corners[i] = [
+                ((start_x + offset_x - dx) / scale).clamp(0., w),
+                ((start_y + offset_y - dy) / scale).clamp(0., h),
+            ];
+            if let Some(transform) = Transform::from_corners(corners, self.screen_corners()) {
+                self.update_transform(Some(transform));
+            }
+        }
+    }
+
+    /// Top left, top right, bottom right and bottom left corner of a `w` x `h`
+    /// rectangle at the origin.
+    fn corners(w: f64, h: f64) -> [[f64; 2]; 4] { [[0., 0.], [w, 0.], [w, h], [0., h]] }
+}
+
+wrapper! {
+    pub struct TransformEditor(ObjectSubclass<imp::TransformEditor>) @extends Widget;
+}
+
+impl TransformEditor {
+    pub fn new() -> Self { Object::new() }
+
+    pub fn transform(&self) -> Option<Transform> { self.imp().transform() }
+
+    pub fn filter(&self) -> String { self.imp().filter() }
+
+    /// Shows `transform` and `filter` without emitting `transform-changed`.
+    /// Without a transform the output is only scaled by `scale`. `size` is
+    /// the size of the rotated mode.
+    pub fn set_transform(
+        &self,
+        transform: Option<Transform>,
+        filter: &str,
+        size: [u16; 2],
+        scale: [f64; 2],
+    ) {
+        self.imp().size.set(size.map(f64::from));
+        self.imp().scale.set(scale);
+        self.imp().set_transform(transform, filter);
+    }
+
+    pub fn connect_transform_changed(&self, callback: impl Fn(&Self) + 'static) {
+        self.connect_closure("transform-changed", false, closure_local!(|editor| callback(editor)));
+    }
+}
+
+impl Default for TransformEditor {
+    fn default() -> Self { Self::new() }
+}
@@ -1,7 +1,7 @@
 use gio::{ActionEntry, ActionGroup, ActionMap};
 use glib::object::IsA;
 use glib::subclass::types::ObjectSubclassIsExt;
-use glib::{wrapper, Object};
+use glib::{wrapper, Object, Variant, VariantTy};
 use gtk::prelude::ActionMapExtManual;
 use gtk::{
     gio, glib, Accessible, Application, ApplicationWindow, Buildable, ConstraintTarget, Native,
@@ -13,23 +13,26 @@ pub const SPACING: u16 = 6;
 
 mod imp {
     use std::cell::{Cell, RefCell};
+    use std::error::Error;
+    use std::path::PathBuf;
     use std::rc::Rc;
     use std::time::Duration;
 
     use config::Config;
     use gdk::{Key, ModifierType, Texture};
-    use gettextrs::{gettext, ngettext};
+    use gettextrs::{gettext, ngettext, pgettext};
+    use gio::SimpleAction;
     use glib::object::CastNone;
     use glib::subclass::object::{ObjectImpl, ObjectImplExt};
     use glib::subclass::types::{ObjectSubclass, ObjectSubclassExt};
     use glib::subclass::InitializingObject;
     use glib::{
-        clone, object_subclass, spawn_future_local, timeout_future, timeout_future_seconds,
-        Propagation,
+        clone, idle_add_local_once, object_subclass, spawn_future_local, timeout_add_seconds_local,
+        timeout_future, timeout_future_seconds, ControlFlow, Propagation, SourceId,
     };
     use gtk::prelude::{
-        GtkWindowExt, ListModelExt, ListModelExtManual, ObjectExt, StaticTypeExt, ToggleButtonExt,
-        WidgetExt,
+        ActionMapExt, BoxExt, EditableExt, GtkWindowExt, ListModelExt, ListModelExtManual,
+        ObjectExt, StaticTypeExt, ToVariant, ToggleButtonExt, WidgetExt,
     };
     use gtk::subclass::application_window::ApplicationWindowImpl;
     use gtk::subclass::widget::{
@@ -38,32 +41,53 @@ mod imp {
     };
     use gtk::subclass::window::WindowImpl;
     use gtk::{
-        glib, template_callbacks, AboutDialog, ApplicationWindow, Box, Button, CompositeTemplate,
-        EventControllerKey, GestureClick, Label, License, Paned, Separator, TemplateChild,
-        ToggleButton,
+        gio, glib, template_callbacks, AboutDialog, Align, ApplicationWindow, Box, Button,
+        CompositeTemplate, DropDown, Entry, EventControllerKey, GestureClick, Label, License,
+        ListBox, MenuButton, Orientation, Paned, Separator, Stack, TemplateChild, ToggleButton,
     };
-    use log::warn;
+    use log::{error, info, warn};
+    use x11rb::protocol::randr::Provider as ProviderId;
 
+    use super::SPACING;
     use crate::app::{APP_NAME, APP_NAME_LOC};
     use crate::data::output::Output;
     use crate::data::outputs::Outputs;
     use crate::hook::{self};
+    use crate::icc::IccProfiles;
+    use crate::math::Rect;
+    use crate::profile::{Profile, Profiles};
     use crate::widget::details_box::{DetailsBox, Update};
     use crate::widget::dialog::Dialog;
     use crate::widget::disabled_output_area::DisabledOutputArea;
     use crate::widget::icon_text::IconText;
+    use crate::widget::mode_dialog::ModeDialog;
     use crate::widget::output_area::OutputArea;
+    use crate::x11::error::ConfigError;
+    use crate::x11::monitor::Monitor;
     use crate::x11::popup::show_popup_windows;
+    use crate::x11::provider::Provider;
     use crate::x11::randr::{self, Randr, ScreenSizeRange, Snapshot};
 
     const COPY_OVERLAY_SHOW_SECS: f64 = 1.5;
+    /// Time between attempts to reconnect after the configuration got lost.
+    const RECONNECT_SECS: u32 = 3;
 
     #[derive(CompositeTemplate, Default)]
     #[template(resource = "/com/github/brofi/rrandr/window.ui")]
     pub struct Window {
         config: RefCell<Config>,
-        randr: Rc<Randr>,
+        randr: RefCell<Option<Rc<Randr>>>,
         snapshot: RefCell<Option<Snapshot>>,
+        listening: Cell<bool>,
+        reconnect_source: RefCell<Option<SourceId>>,
+        #[template_child]
+        stack: TemplateChild<Stack>,
+        #[template_child]
+        main_page: TemplateChild<Box>,
+        #[template_child]
+        error_page: TemplateChild<Box>,
+        #[template_child]
+        error_message: TemplateChild<Label>,
         #[template_child]
         paned: TemplateChild<Paned>,
         #[template_child]
@@ -77,6 +101,10 @@ mod imp {
         #[template_child]
         actions: TemplateChild<Box>,
         #[template_child]
+        apply_error: TemplateChild<Label>,
+        #[template_child]
+        apply_button: TemplateChild<Button>,
+        #[template_child]
         xrandr_container: TemplateChild<Box>,
         #[template_child]
         overlay: TemplateChild<Label>,
@@ -84,6 +112,20 @@ mod imp {
         xrandr: TemplateChild<Label>,
         #[template_child]
         tb_show_xrandr: TemplateChild<ToggleButton>,
+        #[template_child]
+        profiles_button: TemplateChild<MenuButton>,
+        #[template_child]
+        profile_list: TemplateChild<ListBox>,
+        #[template_child]
+        profile_name: TemplateChild<Entry>,
+        #[template_child]
+        monitors_button: TemplateChild<MenuButton>,
+        #[template_child]
+        monitor_list: TemplateChild<ListBox>,
+        #[template_child]
+        monitor_name: TemplateChild<Entry>,
+        #[template_child]
+        provider_list: TemplateChild<ListBox>,
         last_handle_pos: Cell<i32>,
     }
 
@@ -112,8 +154,9 @@ mod imp {
             obj.setup_actions();
 
             self.set_config();
-            self.set_screen_max_size();
-            self.set_outputs();
+            self.update_profiles(&Profiles::named());
+            self.monitor_list.set_placeholder(Some(&Label::new(Some(&gettext("No monitors")))));
+            self.provider_list.set_placeholder(Some(&Label::new(Some(&gettext("No providers")))));
 
             self.hsep.set_visible(!self.config.borrow().show_xrandr);
 
@@ -137,10 +180,50 @@ mod imp {
                 #[weak(rename_to = this)]
                 self,
                 move |_, output, update| {
+                    if let Update::IccProfile = update {
+                        this.save_icc_profile(output);
+                    }
                     this.enabled_area.update(output, update);
                     this.disabled_area.update(output, update);
                 }
             ));
+            self.details.connect_output_notify(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_| this.update_properties()
+            ));
+            self.details.connect_brightness_changed(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_, output, percent| {
+                    let Some(randr) = this.randr() else {
+                        return;
+                    };
+                    if let Some(backlight) = randr.backlight(output.id()) {
+                        if let Err(e) = randr.set_backlight(output.id(), &backlight, percent) {
+                            warn!("Failed to set backlight: {e}");
+                        }
+                    }
+                }
+            ));
+            self.details.connect_property_changed(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |_, output, property, value| {
+                    let Some(randr) = this.randr() else {
+                        return;
+                    };
+                    if let Err(e) = randr.set_output_property(output.id(), property, value) {
+                        Dialog::builder(&*this.obj())
+                            .title(&gettext("Failure"))
+                            .heading(&gettext("Failed to set property"))
+                            .message(&e.to_string())
+                            .build()
+                            .show();
+                        this.update_properties();
+                    }
+                }
+            ));
 
             let gc = GestureClick::new();
             gc.connect_pressed(clone!(
@@ -158,7 +241,7 @@ mod imp {
                 } + "\u{2026}"),
             ));
 
-            self.setup_randr_notify();
+            self.reconnect();
         }
     }
 
@@ -175,8 +258,78 @@ mod imp {
             self.config.replace(cfg);
         }
 
+        fn randr(&self) -> Option<Rc<Randr>> { self.randr.borrow().clone() }
+
+        /// Queries the screen configuration and shows it, or shows why it
+        /// failed. Returns whether it succeeded.
+        fn connect(&self) -> bool {
+            match Randr::new() {
+                Ok(randr) => {
+                    self.randr.replace(Some(Rc::new(randr)));
+                    if !self.listening.get() {
+                        self.setup_randr_notify();
+                    }
+                    self.set_screen_max_size();
+                    self.set_outputs();
+                    self.stack.set_visible_child(&*self.main_page);
+                    true
+                }
+                Err(e) => {
+                    error!("{e}");
+                    self.disconnect(&e.to_string());
+                    false
+                }
+            }
+        }
+
+        /// Drops the screen configuration and shows `message` instead.
+        fn disconnect(&self, message: &str) {
+            self.randr.replace(None);
+            self.snapshot.replace(None);
+            self.error_message.set_text(message);
+            self.stack.set_visible_child(&*self.error_page);
+        }
+
+        /// Tries to query the screen configuration again until it succeeds.
+        fn reconnect(&self) {
+            if self.connect() || self.reconnect_source.borrow().is_some() {
+                return;
+            }
+            let source = timeout_add_seconds_local(
+                RECONNECT_SECS,
+                clone!(
+                    #[weak(rename_to = this)]
+                    self,
+                    #[upgrade_or]
+                    ControlFlow::Break,
+                    move || {
+                        info!("Reconnecting");
+                        if this.connect() {
+                            this.reconnect_source.take();
+                            ControlFlow::Break
+                        } else {
+                            ControlFlow::Continue
+                        }
+                    }
+                ),
+            );
+            self.reconnect_source.replace(Some(source));
+        }
+
+        #[template_callback]
+        fn on_retry_clicked(&self) {
+            if self.connect() {
+                if let Some(source) = self.reconnect_source.take() {
+                    source.remove();
+                }
+            }
+        }
+
         fn set_screen_max_size(&self) {
-            let ScreenSizeRange { max_width, max_height, .. } = self.randr.screen_size_range();
+            let Some(randr) = self.randr() else {
+                return;
+            };
+            let ScreenSizeRange { max_width, max_height, .. } = randr.screen_size_range();
             self.enabled_area.set_screen_max_width(max_width);
             self.enabled_area.set_screen_max_height(max_height);
             self.details.set_screen_max_width(max_width);
@@ -184,7 +337,15 @@ mod imp {
         }
 
         fn set_outputs(&self) {
-            let outputs = self.randr.output_model();
+            let Some(randr) = self.randr() else {
+                return;
+            };
+            self.show_outputs(&randr.output_model());
+            self.update_monitors();
+            self.update_providers();
+        }
+
+        fn show_outputs(&self, outputs: &Outputs) {
             let enabled = Outputs::new();
             let disabled = Outputs::new();
             for output in outputs.iter::<Output>().map(Result::unwrap) {
@@ -198,6 +359,14 @@ mod imp {
                         move |_, _| view.set_text(&randr::gen_xrandr_command(&outputs))
                     ),
                 );
+                output.connect_notify_local(
+                    None,
+                    clone!(
+                        #[weak(rename_to = this)]
+                        self,
+                        move |_, _| this.check_config()
+                    ),
+                );
                 if output.enabled() { enabled.append(&output) } else { disabled.append(&output) }
             }
             // Keep selection when outputs move from enabled to disabled and vice versa
@@ -216,7 +385,7 @@ mod imp {
             self.enabled_area.set_outputs(&enabled);
             self.disabled_area.set_outputs(&disabled);
 
-            self.xrandr.set_text(&randr::gen_xrandr_command(&outputs));
+            self.xrandr.set_text(&randr::gen_xrandr_command(outputs));
             enabled.connect_items_changed(clone!(
                 #[strong]
                 outputs,
@@ -231,6 +400,42 @@ mod imp {
                 self.xrandr,
                 move |_, _, _, _| view.set_text(&randr::gen_xrandr_command(&outputs))
             ));
+            for list in [&enabled, &disabled] {
+                list.connect_items_changed(clone!(
+                    #[weak(rename_to = this)]
+                    self,
+                    move |_, _, _, _| this.check_config()
+                ));
+            }
+            self.check_config();
+        }
+
+        /// Marks the outputs that prevent the configuration from being applied
+        /// and disables applying it until they're changed. Greys out outputs
+        /// that can't be enabled in addition.
+        fn check_config(&self) {
+            let outputs = self.get_outputs();
+            let (errors, blocked) = self
+                .randr()
+                .map(|randr| (randr.check_config(&outputs), randr.blocked_outputs(&outputs)))
+                .unwrap_or_default();
+            self.disabled_area.set_blocked_outputs(blocked);
+            self.enabled_area
+                .set_invalid_outputs(errors.iter().flat_map(ConfigError::outputs).collect());
+            self.details.set_config_errors(&errors);
+
+            let message = errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n");
+            self.apply_error.set_label(message.lines().next().unwrap_or_default());
+            self.apply_error.set_tooltip_text(Some(&message));
+            self.apply_error.set_visible(!errors.is_empty());
+            self.apply_button.set_tooltip_text(Some(&if errors.is_empty() {
+                gettext("Apply changes")
+            } else {
+                message
+            }));
+            if let Some(action) = self.obj().lookup_action("apply").and_downcast::<SimpleAction>() {
+                action.set_enabled(errors.is_empty());
+            }
         }
 
         fn on_key_pressed(
@@ -307,10 +512,30 @@ mod imp {
         }
 
         pub(super) fn apply(&self) {
+            let Some(randr) = self.randr() else {
+                return;
+            };
             let obj = self.obj();
-            self.snapshot.replace(Some(self.randr.snapshot()));
-            if self.randr.apply(&self.get_outputs()) {
+            self.snapshot.replace(Some(randr.snapshot()));
+            let outputs = self.get_outputs();
+            if let Err(e) = randr.apply(&outputs) {
+                error!("{e}");
+                self.revert();
+                if let Err(e) = hook::spawn(&self.config.borrow().revert_hook) {
+                    warn!("{e}");
+                }
+                Dialog::builder(&*obj)
+                    .title(&gettext("Failure"))
+                    .heading(&gettext("Failed to apply changes"))
+                    .message(&gettext("Changes have been reverted."))
+                    .details(&format!("{e}\n\n{}", e.details()))
+                    .build()
+                    .show();
+            } else {
                 let cfg = self.config.borrow();
+                if cfg.xft.update {
+                    randr.update_xft(&outputs, cfg.xft.dpi());
+                }
                 if let Err(e) = hook::spawn(&cfg.apply_hook) {
                     warn!("{e}");
                 }
@@ -366,21 +591,460 @@ mod imp {
                 ));
 
                 dialog.show();
-            } else {
-                self.revert();
-                if let Err(e) = hook::spawn(&self.config.borrow().revert_hook) {
-                    warn!("{e}");
-                }
-                Dialog::builder(&*obj)
+            }
+        }
+
+        pub(super) fn reset(&self) { self.set_outputs(); }
+
+        /// Remembers the ICC profile of the monitor connected to `output`.
+        fn save_icc_profile(&self, output: &Output) {
+            let mut icc_profiles = IccProfiles::load();
+            icc_profiles.set(&output.monitor_id(), output.icc_profile().map(PathBuf::from));
+            if let Err(e) = icc_profiles.save() {
+                warn!("Failed to save ICC profiles: {e}");
+                Dialog::builder(&*self.obj())
                     .title(&gettext("Failure"))
-                    .heading(&gettext("Failure"))
-                    .message(&gettext("Changes have been reverted."))
+                    .heading(&gettext("Failed to save ICC profile"))
+                    .message(&e.to_string())
                     .build()
                     .show();
             }
         }
 
-        pub(super) fn reset(&self) { self.set_outputs(); }
+        fn update_profiles(&self, profiles: &Profiles) {
+            while let Some(row) = self.profile_list.row_at_index(0) {
+                self.profile_list.remove(&row);
+            }
+            let placeholder = match profiles.error() {
+                Some(e) => Label::builder()
+                    .label(gettext("Failed to load profiles"))
+                    .tooltip_text(e)
+                    .css_classes(["error"])
+                    .build(),
+                None => Label::new(Some(&gettext("No profiles"))),
+            };
+            self.profile_list.set_placeholder(Some(&placeholder));
+            for name in profiles.names() {
+                let row = Box::new(Orientation::Horizontal, SPACING.into());
+                row.append(&Label::builder().label(name).hexpand(true).xalign(0.).build());
+                row.append(
+                    &Button::builder()
+                        .icon_name("document-open-symbolic")
+                        .tooltip_text(gettext("Load profile"))
+                        .action_name("win.load-profile")
+                        .action_target(&name.to_variant())
+                        .css_classes(["flat"])
+                        .build(),
+                );
+                row.append(
+                    &Button::builder()
+                        .icon_name("edit-delete-symbolic")
+                        .tooltip_text(gettext("Delete profile"))
+                        .action_name("win.delete-profile")
+                        .action_target(&name.to_variant())
+                        .css_classes(["flat"])
+                        .build(),
+                );
+                self.profile_list.append(&row);
+            }
+        }
+
+        #[template_callback]
+        fn on_profile_name_activate(&self) { self.save_profile(); }
+
+        pub(super) fn save_profile(&self) {
+            let name = self.profile_name.text();
+            let name = name.trim();
+            if name.is_empty() {
+                return;
+            }
+            let mut profiles = Profiles::named();
+            profiles.insert(name, Profile::from_outputs(&self.get_outputs()));
+            if let Err(e) = profiles.save() {
+                self.show_profile_error(&gettext("Failed to save profile"), &e.to_string());
+                return;
+            }
+            self.profile_name.set_text("");
+            self.update_profiles(&profiles);
+        }
+
+        pub(super) fn load_profile(&self, name: &str) {
+            let Some(profile) = Profiles::named().get(name).cloned() else {
+                warn!("No profile named: {name}");
+                return;
+            };
+            let Some(randr) = self.randr() else {
+                return;
+            };
+            let outputs = randr.output_model();
+            match profile.apply_to(&outputs) {
+                Ok(()) => {
+                    self.show_outputs(&outputs);
+                    self.profiles_button.popdown();
+                }
+                Err(e) => {
+                    self.show_profile_error(&gettext("Failed to load profile"), &e.to_string());
+                }
+            }
+        }
+
+        pub(super) fn delete_profile(&self, name: &str) {
+            let mut profiles = Profiles::named();
+            if profiles.remove(name).is_some() {
+                if let Err(e) = profiles.save() {
+                    self.show_profile_error(&gettext("Failed to delete profile"), &e.to_string());
+                    return;
+                }
+            }
+            self.update_profiles(&profiles);
+        }
+
+        fn show_profile_error(&self, heading: &str, message: &str) {
+            self.profiles_button.popdown();
+            Dialog::builder(&*self.obj())
+                .title(&gettext("Failure"))
+                .heading(heading)
+                .message(message)
+                .build()
+                .show();
+        }
+
+        fn update_monitors(&self) {
+            let Some(randr) = self.randr() else {
+                return;
+            };
+            let monitors = randr.monitors();
+            self.enabled_area.set_monitors(&monitors);
+            while let Some(row) = self.monitor_list.row_at_index(0) {
+                self.monitor_list.remove(&row);
+            }
+            let outputs = self.get_outputs();
+            for monitor in &monitors {
+                let names = monitor
+                    .outputs
+                    .iter()
+                    .filter_map(|&o| outputs.find_by_id(o))
+                    .map(|o| o.name())
+                    .collect::<Vec<_>>();
+                let mut name = monitor.name.clone();
+                if monitor.primary {
+                    name = format!("[{name}]");
+                }
+                let r = &monitor.rect;
+                let geometry = format!("{}x{}+{}+{}", r.width(), r.height(), r.x(), r.y());
+                let details = if names.is_empty() {
+                    geometry
+                } else {
+                    format!("{geometry} ({})", names.join(", "))
+                };
+                let labels = Box::new(Orientation::Vertical, 0);
+                labels.append(&Label::builder().label(name).xalign(0.).build());
+                labels.append(
+                    &Label::builder()
+                        .label(details)
+                        .xalign(0.)
+                        .css_classes(["dim-label", "caption"])
+                        .build(),
+                );
+                let row = Box::new(Orientation::Horizontal, SPACING.into());
+                labels.set_hexpand(true);
+                row.append(&labels);
+                if !monitor.automatic {
+                    row.append(
+                        &Button::builder()
+                            .icon_name("edit-delete-symbolic")
+                            .tooltip_text(gettext("Delete monitor"))
+                            .action_name("win.delete-monitor")
+                            .action_target(&monitor.name.to_variant())
+                            .css_classes(["flat"])
+                            .valign(Align::Center)
+                            .build(),
+                    );
+                }
+                self.monitor_list.append(&row);
+            }
+        }
+
+        fn update_providers(&self) {
+            while let Some(row) = self.provider_list.row_at_index(0) {
+                self.provider_list.remove(&row);
+            }
+            let Some(randr) = self.randr() else {
+                return;
+            };
+            let providers = randr.providers();
+            for provider in &providers {
+                let mut details = provider.capability_names();
+                details.push(ngettext!(
+                    "{} CRTC",
+                    "{} CRTCs",
+                    u32::try_from(provider.crtcs).unwrap_or(u32::MAX),
+                    provider.crtcs
+                ));
+                details.push(ngettext!(
+                    "{} output",
+                    "{} outputs",
+                    u32::try_from(provider.outputs).unwrap_or(u32::MAX),
+                    provider.outputs
+                ));
+                let row = Box::new(Orientation::Vertical, SPACING.into());
+                let labels = Box::new(Orientation::Vertical, 0);
+                labels.append(&Label::builder().label(&provider.name).xalign(0.).build());
+                labels.append(
+                    &Label::builder()
+                        .label(details.join(", "))
+                        .xalign(0.)
+                        .css_classes(["dim-label", "caption"])
+                        .build(),
+                );
+                row.append(&labels);
+
+                let id = provider.id;
+                let others = providers.iter().filter(|p| p.id != provider.id);
+                let sources = others.clone().filter(|p| p.can_source_output()).collect::<Vec<_>>();
+                if provider.can_sink_output() && !sources.is_empty() {
+                    row.append(&self.provider_choice(
+                        // Provider rendering the images shown on the outputs of another provider
+                        &gettext("Output source"),
+                        &sources,
+                        provider.output_source,
+                        move |randr, source| randr.set_provider_output_source(id, source),
+                    ));
+                }
+                let sinks = others.filter(|p| p.can_sink_offload()).collect::<Vec<_>>();
+                if provider.can_source_offload() && !sinks.is_empty() {
+                    row.append(&self.provider_choice(
+                        // Provider another provider offloads rendering to
+                        &gettext("Offload sink"),
+                        &sinks,
+                        provider.offload_sink,
+                        move |randr, sink| randr.set_provider_offload_sink(id, sink),
+                    ));
+                }
+                self.provider_list.append(&row);
+            }
+        }
+
+        /// Creates a labeled drop down to choose one of `candidates` or none,
+        /// preselecting `current`. Applies the choice with `set` and shows the
+        /// outputs that became available.
+        fn provider_choice(
+            &self,
+            label: &str,
+            candidates: &[&Provider],
+            current: Option<ProviderId>,
+            set: impl Fn(&Randr, Option<ProviderId>) -> Result<(), std::boxed::Box<dyn Error>> + 'static,
+        ) -> Box {
+            let ids =
+                [None].into_iter().chain(candidates.iter().map(|p| Some(p.id))).collect::<Vec<_>>();
+            let names = [pgettext("Provider", "None")]
+                .into_iter()
+                .chain(candidates.iter().map(|p| p.name.clone()))
+                .collect::<Vec<_>>();
+            let dd = DropDown::from_strings(&names.iter().map(String::as_str).collect::<Vec<_>>());
+            dd.set_selected(
+                ids.iter()
+                    .position(|&id| id == current)
+                    .and_then(|i| u32::try_from(i).ok())
+                    .unwrap_or_default(),
+            );
+            dd.connect_selected_notify(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |dd| {
+                    let Some(randr) = this.randr() else {
+                        return;
+                    };
+                    let id = ids.get(dd.selected() as usize).copied().flatten();
+                    if let Err(e) = set(&randr, id) {
+                        Dialog::builder(&*this.obj())
+                            .title(&gettext("Failure"))
+                            .heading(&gettext("Failed to change provider"))
+                            .message(&e.to_string())
+                            .build()
+                            .show();
+                    } else if let Err(e) = randr.refresh() {
+                        warn!("{e}, reconnecting");
+                        this.reconnect();
+                        return;
+                    }
+                    // Rebuild the provider list once this drop down is done
+                    idle_add_local_once(clone!(
+                        #[weak]
+                        this,
+                        move || this.set_outputs()
+                    ));
+                }
+            ));
+            let row = Box::new(Orientation::Horizontal, SPACING.into());
+            row.append(&Label::builder().label(label).xalign(0.).hexpand(true).build());
+            row.append(&dd);
+            row
+        }
+
+        #[template_callback]
+        fn on_monitor_name_activate(&self) { self.draw_monitor(); }
+
+        pub(super) fn draw_monitor(&self) {
+            let Some(randr) = self.randr() else {
+                return;
+            };
+            // Monitors are set right away, so they're drawn on the applied layout
+            let layout = |outputs: &Outputs| {
+                let mut layout = Profile::from_outputs(outputs).outputs;
+                layout.sort_by(|a, b| a.output.cmp(&b.output));
+                layout
+            };
+            if layout(&self.get_outputs()) != layout(&randr.output_model()) {
+                self.show_monitor_error(
+                    &gettext("Failed to create monitor"),
+                    &gettext("Apply or reset the changes to the outputs first."),
+                );
+                return;
+            }
+            self.monitors_button.popdown();
+            self.enabled_area.draw_monitor();
+        }
+
+        #[template_callback]
+        fn on_monitor_drawn(&self, x: i32, y: i32, width: u32, height: u32) {
+            let (Ok(x), Ok(y), Ok(width), Ok(height)) =
+                (i16::try_from(x), i16::try_from(y), u16::try_from(width), u16::try_from(height))
+            else {
+                return;
+            };
+            let Some(randr) = self.randr() else {
+                return;
+            };
+            let monitors = randr.monitors();
+            let name = self.monitor_name.text();
+            let name = match name.trim() {
+                "" => (1..=monitors.len() + 1)
+                    .map(|i| format!("Monitor-{i}"))
+                    .find(|n| monitors.iter().all(|m| m.name != *n))
+                    .unwrap_or_default(),
+                name => name.to_owned(),
+            };
+            let monitor = Monitor::from_region(
+                &name,
+                Rect::new(x, y, width, height),
+                &randr.output_model().to_vec(),
+                &monitors,
+            );
+            match randr.set_monitor(&monitor) {
+                Ok(()) => self.monitor_name.set_text(""),
+                Err(e) => self.show_monitor_error(&gettext("Failed to create monitor"), &e),
+            }
+            self.update_monitors();
+        }
+
+        pub(super) fn delete_monitor(&self, name: &str) {
+            let Some(randr) = self.randr() else {
+                return;
+            };
+            if let Err(e) = randr.delete_monitor(name) {
+                self.show_monitor_error(&gettext("Failed to delete monitor"), &e);
+            }
+            self.update_monitors();
+        }
+
+        fn show_monitor_error(&self, heading: &str, message: &impl ToString) {
+            self.monitors_button.popdown();
+            Dialog::builder(&*self.obj())
+                .title(&gettext("Failure"))
+                .heading(heading)
+                .message(&message.to_string())
+                .build()
+                .show();
+        }
+
+        pub(super) fn show_custom_modes(&self) {
+            let Some(output) = self.details.output() else {
+                return;
+            };
+            let dialog = ModeDialog::new(&*self.obj(), &output);
+            dialog.connect_create_mode(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |dialog, output, width, height, refresh, timing| {
+                    let Some(randr) = this.randr() else {
+                        return;
+                    };
+                    let (mode_info, name) = match timing.gen_mode(width, height, refresh) {
+                        Ok(mode) => mode,
+                        Err(e) => {
+                            this.show_mode_error(dialog, &gettext("Failed to create mode"), &e);
+                            return;
+                        }
+                    };
+                    match randr.create_mode(output.id(), mode_info, &name) {
+                        Ok(mode) => {
+                            output.modes().append(&mode);
+                            this.details.set_output(Some(output));
+                        }
+                        Err(e) => {
+                            this.show_mode_error(dialog, &gettext("Failed to create mode"), &e);
+                        }
+                    }
+                }
+            ));
+            dialog.connect_delete_mode(clone!(
+                #[weak(rename_to = this)]
+                self,
+                move |dialog, output, mode| {
+                    if output.mode().is_some_and(|m| m.id() == mode) {
+                        this.show_mode_error(
+                            dialog,
+                            &gettext("Failed to delete mode"),
+                            &gettext("The mode is in use by the output."),
+                        );
+                        return;
+                    }
+                    let Some(randr) = this.randr() else {
+                        return;
+                    };
+                    match randr.delete_mode(output.id(), mode) {
+                        Ok(()) => {
+                            output.modes().remove(mode);
+                            this.details.set_output(Some(output));
+                        }
+                        Err(e) => {
+                            this.show_mode_error(dialog, &gettext("Failed to delete mode"), &e);
+                        }
+                    }
+                }
+            ));
+            dialog.present();
+        }
+
+        fn show_mode_error(&self, dialog: &ModeDialog, heading: &str, message: &impl ToString) {
+            let error = Dialog::builder(&*self.obj())
+                .title(&gettext("Failure"))
+                .heading(heading)
+                .message(&message.to_string())
+                .build();
+            error.set_transient_for(Some(dialog));
+            error.show();
+        }
+
+        fn update_properties(&self) {
+            let Some(randr) = self.randr() else {
+                return;
+            };
+            let output = self.details.output();
+            self.details.set_brightness(
+                output.as_ref().and_then(|o| randr.backlight(o.id())).map(|b| b.percent()),
+            );
+            self.details.set_edid(output.as_ref().and_then(|o| randr.edid_data(o.id())).as_deref());
+            let properties = output.map_or(Ok(Vec::new()), |o| randr.output_properties(o.id()));
+            match properties {
+                Ok(properties) => self.details.set_properties(&properties),
+                Err(e) => {
+                    warn!("Failed to get output properties: {e}");
+                    self.details.set_properties(&[]);
+                }
+            }
+        }
 
         pub(super) fn redraw(&self) {
             self.enabled_area.queue_draw();
@@ -465,25 +1129,44 @@ mod imp {
         }
 
         fn revert(&self) {
-            if let Some(snapshot) = self.snapshot.take() {
-                self.randr.revert(snapshot);
+            if let (Some(randr), Some(snapshot)) = (self.randr(), self.snapshot.take()) {
+                randr.revert(snapshot);
                 self.set_outputs();
             }
         }
 
         fn setup_randr_notify(&self) {
             let (sender, receiver) = async_channel::unbounded();
-            if randr::run_event_loop(sender).is_ok() {
+            if randr::run_event_loop(sender, true).is_ok() {
+                self.listening.set(true);
                 spawn_future_local(clone!(
                     #[weak(rename_to = this)]
                     self,
                     async move {
+                        let mut changed = false;
                         while let Ok(event) = receiver.recv().await {
-                            this.randr.handle_event(&event);
-                            if receiver.is_empty() {
+                            // Property changes don't affect the layout
+                            if let Some(output) = randr::get_property_notify_output(&event) {
+                                if this.details.output().is_some_and(|o| o.id() == output) {
+                                    this.update_properties();
+                                }
+                            } else if let Some(randr) = this.randr() {
+                                if let Err(e) = randr.handle_event(&event) {
+                                    warn!("{e}, reconnecting");
+                                    this.reconnect();
+                                    changed = false;
+                                    continue;
+                                }
+                                changed = true;
+                            }
+                            if changed && receiver.is_empty() {
+                                changed = false;
                                 this.set_outputs();
                             }
                         }
+                        this.listening.set(false);
+                        this.disconnect(&gettext("Lost connection to the X server."));
+                        this.reconnect();
                     }
                 ));
             }
@@ -513,6 +1196,39 @@ impl Window {
             ActionEntry::builder("redraw")
                 .activate(|window: &Self, _, _| window.imp().redraw())
                 .build(),
+            ActionEntry::builder("custom-modes")
+                .activate(|window: &Self, _, _| window.imp().show_custom_modes())
+                .build(),
+            ActionEntry::builder("save-profile")
+                .activate(|window: &Self, _, _| window.imp().save_profile())
+                .build(),
+            ActionEntry::builder("load-profile")
+                .parameter_type(Some(VariantTy::STRING))
+                .activate(|window: &Self, _, param| {
+                    if let Some(name) = param.and_then(Variant::str) {
+                        window.imp().load_profile(name);
+                    }
+                })
+                .build(),
+            ActionEntry::builder("delete-profile")
+                .parameter_type(Some(VariantTy::STRING))
+                .activate(|window: &Self, _, param| {
+                    if let Some(name) = param.and_then(Variant::str) {
+                        window.imp().delete_profile(name);
+                    }
+                })
+                .build(),
+            ActionEntry::builder("draw-monitor")
+                .activate(|window: &Self, _, _| window.imp().draw_monitor())
+                .build(),
+            ActionEntry::builder("delete-monitor")
+                .parameter_type(Some(VariantTy::STRING))
+                .activate(|window: &Self, _, param| {
+                    if let Some(name) = param.and_then(Variant::str) {
+                        window.imp().delete_monitor(name);
+                    }
+                })
+                .build(),
         ]);
     }
 }
@@ -0,0 +1,317 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+use gettextrs::{gettext, ngettext};
+use x11rb::errors::{ConnectError, ConnectionError, ReplyError};
+use x11rb::protocol::randr::{Crtc as CrtcId, Mode as ModeId, Output as OutputId, SetConfig};
+
+use super::x_error_to_string;
+use crate::data::output::Output;
+
+/// Failure to connect to the X server or to query its screen configuration.
+#[derive(Debug)]
+pub enum RandrError {
+    Connect(ConnectError),
+    Reply(ReplyError),
+}
+
+impl Display for RandrError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RandrError::Connect(e) => write!(f, "Failed to connect to the X server: {e}"),
+            RandrError::Reply(ReplyError::ConnectionError(e)) => {
+                write!(f, "Lost connection to the X server: {e}")
+            }
+            RandrError::Reply(ReplyError::X11Error(e)) => {
+                write!(f, "Failed to query the screen configuration: {}", x_error_to_string(e))
+            }
+        }
+    }
+}
+
+impl Error for RandrError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RandrError::Connect(e) => Some(e),
+            RandrError::Reply(e) => Some(e),
+        }
+    }
+}
+
+impl From<ConnectError> for RandrError {
+    fn from(e: ConnectError) -> Self { Self::Connect(e) }
+}
+
+impl From<ConnectionError> for RandrError {
+    fn from(e: ConnectionError) -> Self { Self::Reply(ReplyError::ConnectionError(e)) }
+}
+
+impl From<ReplyError> for RandrError {
+    fn from(e: ReplyError) -> Self { Self::Reply(e) }
+}
+
+/// Reason a screen configuration can't be applied, found before any request
+/// is sent.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The outputs reach beyond the maximum screen size.
+    ScreenSize { outputs: Vec<Output>, max: [u16; 2] },
+    /// The output doesn't support its mode or has none.
+    Mode(Output),
+    /// None of the CRTCs of the output supports its rotation and reflection.
+    Rotation(Output),
+    /// Not enough CRTCs are left to drive the outputs.
+    NoCrtc(Vec<Output>),
+}
+
+impl ConfigError {
+    /// Outputs that need to be changed to resolve the error.
+    pub fn outputs(&self) -> Vec<OutputId> {
+        match self {
+            ConfigError::ScreenSize { outputs, .. } | ConfigError::NoCrtc(outputs) => {
+                outputs.iter().map(Output::id).collect()
+            }
+            ConfigError::Mode(output) | ConfigError::Rotation(output) => vec![output.id()],
+        }
+    }
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let names = |outputs: &[Output]| outputs.iter().map(Output::name).collect::<Vec<_>>();
+        match self {
+            ConfigError::ScreenSize { outputs, max: [width, height] } => write!(
+                f,
+                "{}",
+                // Translators: '{}' get replaced with output names and the maximum screen width
+                // and height.
+                ngettext!(
+                    "{} exceeds the maximum screen size of {}x{}",
+                    "{} exceed the maximum screen size of {}x{}",
+                    u32::try_from(outputs.len()).unwrap_or(u32::MAX),
+                    names(outputs).join(", "),
+                    width,
+                    height
+                )
+            ),
+            ConfigError::Mode(output) => match output.mode() {
+                Some(mode) => write!(
+                    f,
+                    "{}",
+                    gettext!(
+                        "{} doesn't support mode {} {}",
+                        output.name(),
+                        mode.as_resolution_str(None),
+                        mode.as_refresh_rate_str()
+                    )
+                ),
+                None => write!(f, "{}", gettext!("{} has no mode", output.name())),
+            },
+            ConfigError::Rotation(output) => write!(
+                f,
+                "{}",
+                gettext!("{} doesn't support this rotation and reflection", output.name())
+            ),
+            ConfigError::NoCrtc(outputs) => write!(
+                f,
+                "{}",
+                // Translators: CRTCs are the display controllers of the graphics card.
+                gettext!("Not enough CRTCs to enable {}", names(outputs).join(", "))
+            ),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+/// Reason a request sent while applying a screen configuration failed.
+#[derive(Debug)]
+pub enum Cause {
+    /// The server refused the configuration with this status.
+    Status(SetConfig),
+    /// The server replied with an error or the connection broke.
+    Reply(ReplyError),
+}
+
+impl Display for Cause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Cause::Status(SetConfig::FAILED) => write!(f, "{}", gettext("Request failed")),
+            Cause::Status(SetConfig::INVALID_CONFIG_TIME) => {
+                write!(f, "{}", gettext("Configuration is outdated (invalid config time)"))
+            }
+            Cause::Status(SetConfig::INVALID_TIME) => {
+                write!(f, "{}", gettext("Configuration is outdated (invalid time)"))
+            }
+            Cause::Status(status) => write!(f, "{}", gettext!("Status {}", format!("{status:?}"))),
+            Cause::Reply(ReplyError::X11Error(e)) => write!(f, "{}", x_error_to_string(e)),
+            Cause::Reply(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<ReplyError> for Cause {
+    fn from(e: ReplyError) -> Self { Self::Reply(e) }
+}
+
+impl From<ConnectionError> for Cause {
+    fn from(e: ConnectionError) -> Self { Self::Reply(ReplyError::ConnectionError(e)) }
+}
+
+/// Step of applying a screen configuration that failed, together with the
+/// outputs, CRTC and mode it affected.
+#[derive(Debug)]
+pub enum ApplyError {
+    DisableCrtc {
+        crtc: CrtcId,
+        outputs: Vec<String>,
+        cause: Cause,
+    },
+    SetScreenSize {
+        size: [u16; 2],
+        mm: [u32; 2],
+        cause: Cause,
+    },
+    Config(Vec<ConfigError>),
+    SetTransform {
+        crtc: CrtcId,
+        outputs: Vec<String>,
+        cause: Cause,
+    },
+    SetCrtcConfig {
+        crtc: CrtcId,
+        outputs: Vec<String>,
+        mode: ModeId,
+        mode_name: String,
+        cause: Cause,
+    },
+    SetPanning {
+        crtc: CrtcId,
+        outputs: Vec<String>,
+        cause: Cause,
+    },
+    SetGamma {
+        crtc: CrtcId,
+        outputs: Vec<String>,
+        cause: Cause,
+    },
+    SetPrimary {
+        output: Option<String>,
+        cause: Cause,
+    },
+}
+
+impl ApplyError {
+    fn step(&self) -> String {
+        match self {
+            // Translators: Steps of applying a screen configuration, completing
+            // "Failed to {}".
+            ApplyError::DisableCrtc { .. } => gettext("disable CRTC"),
+            ApplyError::SetScreenSize { .. } => gettext("set screen size"),
+            ApplyError::Config(_) => gettext("validate the configuration"),
+            ApplyError::SetTransform { .. } => gettext("set CRTC transform"),
+            ApplyError::SetCrtcConfig { .. } => gettext("set CRTC config"),
+            ApplyError::SetPanning { .. } => gettext("set panning"),
+            ApplyError::SetGamma { .. } => gettext("set CRTC gamma"),
+            ApplyError::SetPrimary { .. } => gettext("set primary output"),
+        }
+    }
+
+    fn outputs(&self) -> &[String] {
+        match self {
+            ApplyError::DisableCrtc { outputs, .. }
+            | ApplyError::SetTransform { outputs, .. }
+            | ApplyError::SetCrtcConfig { outputs, .. }
+            | ApplyError::SetPanning { outputs, .. }
+            | ApplyError::SetGamma { outputs, .. } => outputs,
+            ApplyError::SetPrimary { output, .. } => output.as_slice(),
+            ApplyError::SetScreenSize { .. } | ApplyError::Config(_) => &[],
+        }
+    }
+
+    fn crtc(&self) -> Option<CrtcId> {
+        match self {
+            ApplyError::DisableCrtc { crtc, .. }
+            | ApplyError::SetTransform { crtc, .. }
+            | ApplyError::SetCrtcConfig { crtc, .. }
+            | ApplyError::SetPanning { crtc, .. }
+            | ApplyError::SetGamma { crtc, .. } => Some(*crtc),
+            _ => None,
+        }
+    }
+
+    fn cause(&self) -> Option<&Cause> {
+        match self {
+            ApplyError::DisableCrtc { cause, .. }
+            | ApplyError::SetScreenSize { cause, .. }
+            | ApplyError::SetTransform { cause, .. }
+            | ApplyError::SetCrtcConfig { cause, .. }
+            | ApplyError::SetPanning { cause, .. }
+            | ApplyError::SetGamma { cause, .. }
+            | ApplyError::SetPrimary { cause, .. } => Some(cause),
+            ApplyError::Config(_) => None,
+        }
+    }
+
+    /// Technical description of the failed request, one property per line.
+    pub fn details(&self) -> String {
+        let mut details = vec![gettext!("Step: {}", self.step())];
+        if !self.outputs().is_empty() {
+            details.push(gettext!("Outputs: {}", self.outputs().join(", ")));
+        }
+        if let Some(crtc) = self.crtc() {
+            details.push(gettext!("CRTC: {}", crtc));
+        }
+        match self {
+            ApplyError::SetCrtcConfig { mode, mode_name, .. } => {
+                details.push(gettext!("Mode: {} ({})", mode_name, format!("{mode:#x}")));
+            }
+            ApplyError::SetScreenSize { size: [width, height], mm: [mwidth, mheight], .. } => {
+                details.push(gettext!(
+                    "Screen size: {}x{} px, {}x{} mm",
+                    width,
+                    height,
+                    mwidth,
+                    mheight
+                ));
+            }
+            ApplyError::Config(errors) => {
+                details.extend(errors.iter().map(|e| gettext!("Error: {}", e)));
+            }
+            _ => (),
+        }
+        match self.cause() {
+            Some(Cause::Reply(ReplyError::X11Error(e))) => {
+                details.push(gettext!("Cause: {}", x_error_to_string(e)));
+                details.push(gettext!("X11 error: {}", format!("{e:?}")));
+            }
+            Some(cause) => details.push(gettext!("Cause: {}", cause)),
+            None => (),
+        }
+        details.join("\n")
+    }
+}
+
+impl Display for ApplyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // Translators: '{}' gets replaced with a step of applying a screen configuration.
+        let mut message = gettext!("Failed to {}", self.step());
+        if !self.outputs().is_empty() {
+            // Translators: '{}' get replaced with the failure and output names.
+            message = gettext!("{} for {}", message, self.outputs().join(", "));
+        }
+        if let Some(crtc) = self.crtc() {
+            // Translators: '{}' get replaced with the failure and the CRTC number.
+            message = gettext!("{} (CRTC {})", message, crtc);
+        }
+        if let ApplyError::Config(errors) = self {
+            let errors = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+            message = gettext!("{}: {}", message, errors.join("; "));
+        } else if let Some(cause) = self.cause() {
+            message = gettext!("{}: {}", message, cause);
+        }
+        write!(f, "{message}")
+    }
+}
+
+impl Error for ApplyError {}
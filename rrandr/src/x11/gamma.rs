@@ -0,0 +1,137 @@
+use crate::utils::nearly_eq_abs;
+
+/// Gamma and brightness are estimated to two decimals.
+pub const TOLERANCE: f64 = 0.005;
+
+/// Values below this fraction of the maximum are considered black.
+const BLACK: f64 = 1e-4;
+
+/// Red, green and blue gamma ramps of a CRTC.
+pub type Ramps = [Vec<u16>; 3];
+
+/// Gamma per red, green and blue channel and brightness of a CRTC gamma ramp.
+#[derive(Clone, Copy)]
+pub struct Gamma {
+    pub rgb: [f64; 3],
+    pub brightness: f64,
+}
+
+impl Default for Gamma {
+    fn default() -> Self { Self { rgb: [1.; 3], brightness: 1. } }
+}
+
+impl Gamma {
+    /// Generates ramps of `size` entries per channel the way xrandr does for
+    /// `--gamma` and `--brightness`: `(i / (size - 1))^(1 / gamma) *
+    /// brightness`, clamped to the maximum.
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn ramps(&self, size: u16) -> Ramps {
+        let last = f64::from(size.saturating_sub(1).max(1));
+        self.rgb.map(|gamma| {
+            (0..size)
+                .map(|i| {
+                    let v = (f64::from(i) / last).powf(1. / gamma) * self.brightness;
+                    (v.clamp(0., 1.) * f64::from(u16::MAX)).round() as u16
+                })
+                .collect()
+        })
+    }
+
+    /// Approximates the gamma and brightness of a ramp assuming it was
+    /// generated by [`Gamma::ramps`]. Each channel is fitted with the last
+    /// value that isn't clamped and the value halfway to it. Estimates are
+    /// rounded to two decimals.
+    pub fn estimate(ramps: &Ramps) -> Self {
+        let fits = ramps.each_ref().map(|ramp| fit(ramp));
+        let brightness = fits.iter().filter_map(|f| f.map(|(_, b)| b)).fold(0., f64::max);
+        if fits.iter().all(Option::is_none) {
+            return Self::default();
+        }
+        if brightness < BLACK {
+            return Self { brightness: 0., ..Self::default() };
+        }
+        Self { rgb: fits.map(|f| f.map_or(1., |(g, _)| round(g))), brightness: round(brightness) }
+    }
+
+    /// Whether both are equal within the precision of an estimate.
+    pub fn nearly_eq(&self, other: &Self) -> bool {
+        self.rgb.iter().zip(other.rgb).all(|(a, b)| nearly_eq_abs(*a, b, TOLERANCE))
+            && nearly_eq_abs(self.brightness, other.brightness, TOLERANCE)
+    }
+}
+
+/// Fits `v = x^(1 / gamma) * brightness` to `ramp` and returns gamma and
+/// brightness.
+#[allow(clippy::cast_precision_loss)]
+fn fit(ramp: &[u16]) -> Option<(f64, f64)> {
+    if ramp.len() < 3 {
+        return None;
+    }
+    let last = (ramp.len() - 1) as f64;
+    let value = |i: usize| f64::from(ramp[i]) / f64::from(u16::MAX);
+    let k = ramp.iter().rposition(|&v| v < u16::MAX).unwrap_or(0).max(2);
+    let m = k / 2;
+    let [xk, xm] = [k, m].map(|i| i as f64 / last);
+    let [vk, vm] = [value(k), value(m)];
+    if vk < BLACK || vm < BLACK {
+        return Some((1., vk.max(vm)));
+    }
+    let exponent = (vk / vm).ln() / (xk / xm).ln();
+    if !exponent.is_normal() || exponent <= 0. {
+        return None;
+    }
+    Some((1. / exponent, vk / xk.powf(exponent)))
+}
+
+fn round(v: f64) -> f64 { (v * 100.).round() / 100. }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity_ramps() {
+        let ramps = Gamma::default().ramps(256);
+        let identity = (0..=255).map(|i| i * 257).collect::<Vec<_>>();
+        assert_eq!(ramps, [identity.clone(), identity.clone(), identity]);
+    }
+
+    #[test]
+    fn gamma_ramps() {
+        // --gamma 2:1:0.5 with a ramp size of 5
+        let ramps = Gamma { rgb: [2., 1., 0.5], brightness: 1. }.ramps(5);
+        assert_eq!(
+            ramps,
+            [
+                vec![0, 32768, 46340, 56755, 65535],
+                vec![0, 16384, 32768, 49151, 65535],
+                vec![0, 4096, 16384, 36863, 65535],
+            ]
+        );
+        // --brightness 0.5
+        let ramps = Gamma { brightness: 0.5, ..Gamma::default() }.ramps(3);
+        assert_eq!(ramps[0], [0, 16384, 32768]);
+    }
+
+    #[test]
+    fn estimate_generated_ramps() {
+        for gamma in [
+            Gamma { rgb: [0.8, 1., 1.2], brightness: 0.7 },
+            Gamma { rgb: [1.5, 1.5, 1.5], brightness: 1. },
+            // Clamped at the maximum towards the end of the ramps
+            Gamma { rgb: [1., 2.2, 1.], brightness: 1.3 },
+        ] {
+            let estimate = Gamma::estimate(&gamma.ramps(1024));
+            assert!(estimate.nearly_eq(&gamma), "{:?} {}", estimate.rgb, estimate.brightness);
+        }
+    }
+
+    #[test]
+    fn estimate_black_ramps() {
+        let estimate = Gamma::estimate(&[vec![0; 256], vec![0; 256], vec![0; 256]]);
+        assert!(estimate.nearly_eq(&Gamma { brightness: 0., ..Gamma::default() }));
+        // Too short to fit
+        assert!(Gamma::estimate(&[vec![0, 1], vec![0, 1], vec![0, 1]]).nearly_eq(&Gamma::default()));
+    }
+}
@@ -0,0 +1,163 @@
+/// Matches each row to at most one column of `costs`, where `costs[row][col]`
+/// is the cost of matching them or `None` if they can't be matched. Finds a
+/// matching with the most pairs and among those one with the lowest total
+/// cost. Returns the matched column of each row.
+pub fn min_cost_matching(costs: &[Vec<Option<u32>>]) -> Vec<Option<usize>> {
+    let cols = costs.iter().map(Vec::len).max().unwrap_or_default();
+    let mut row_match: Vec<Option<usize>> = vec![None; costs.len()];
+    let mut col_match: Vec<Option<usize>> = vec![None; cols];
+    // Each shortest augmenting path adds a pair at the lowest additional cost
+    while let Some(path) = shortest_augmenting_path(costs, &row_match, &col_match) {
+        for (row, col) in path {
+            row_match[row] = Some(col);
+            col_match[col] = Some(row);
+        }
+    }
+    row_match
+}
+
+/// Finds the path from an unmatched row to an unmatched column alternating
+/// between unmatched and matched pairs with the lowest total cost, counting
+/// matched pairs negatively. Returns the unmatched pairs of the path.
+fn shortest_augmenting_path(
+    costs: &[Vec<Option<u32>>],
+    row_match: &[Option<usize>],
+    col_match: &[Option<usize>],
+) -> Option<Vec<(usize, usize)>> {
+    let cost = |row: usize, col: usize| costs[row].get(col).copied().flatten().map(i64::from);
+    let mut row_dist: Vec<Option<i64>> =
+        row_match.iter().map(|m| m.map_or(Some(0), |_| None)).collect();
+    let mut col_dist: Vec<Option<i64>> = vec![None; col_match.len()];
+    let mut col_prev: Vec<usize> = vec![0; col_match.len()];
+    // Bellman-Ford, there are no negative cycles as long as the matching has
+    // the lowest cost for its size
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for row in 0..costs.len() {
+            let Some(dist) = row_dist[row] else {
+                continue;
+            };
+            for col in 0..col_match.len() {
+                let Some(c) = cost(row, col).filter(|_| row_match[row] != Some(col)) else {
+                    continue;
+                };
+                if col_dist[col].is_none_or(|d| dist + c < d) {
+                    col_dist[col] = Some(dist + c);
+                    col_prev[col] = row;
+                    changed = true;
+                }
+            }
+        }
+        for (col, matched) in col_match.iter().enumerate() {
+            let (Some(dist), Some(row)) = (col_dist[col], *matched) else {
+                continue;
+            };
+            let d = dist - cost(row, col).unwrap_or_default();
+            if row_dist[row].is_none_or(|rd| d < rd) {
+                row_dist[row] = Some(d);
+                changed = true;
+            }
+        }
+    }
+
+    let end = (0..col_match.len())
+        .filter(|&col| col_match[col].is_none())
+        .filter_map(|col| col_dist[col].map(|d| (d, col)))
+        .min()?
+        .1;
+    let mut path = Vec::new();
+    let mut col = end;
+    loop {
+        let row = col_prev[col];
+        path.push((row, col));
+        match row_match[row] {
+            Some(prev) => col = prev,
+            None => return Some(path),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Number of pairs and total cost of the best matching found by trying
+    /// every assignment.
+    fn brute_force(costs: &[Vec<Option<u32>>], row: usize, used: &mut Vec<bool>) -> (usize, u32) {
+        let Some(cols) = costs.get(row) else {
+            return (0, 0);
+        };
+        let mut best = brute_force(costs, row + 1, used);
+        for (col, cost) in cols.iter().enumerate() {
+            let Some(cost) = cost.filter(|_| !used[col]) else {
+                continue;
+            };
+            used[col] = true;
+            let (pairs, total) = brute_force(costs, row + 1, used);
+            used[col] = false;
+            // More pairs first, then lower cost
+            if (pairs + 1, best.1) > (best.0, total + cost) {
+                best = (pairs + 1, total + cost);
+            }
+        }
+        best
+    }
+
+    fn pairs_and_cost(costs: &[Vec<Option<u32>>], matching: &[Option<usize>]) -> (usize, u32) {
+        matching
+            .iter()
+            .enumerate()
+            .filter_map(|(row, col)| costs[row][(*col)?])
+            .fold((0, 0), |(pairs, total), cost| (pairs + 1, total + cost))
+    }
+
+    #[test]
+    fn match_around_blocked_pair() {
+        // Row 0 can only use column 0, so row 1 has to give it up
+        let costs = vec![vec![Some(1), None], vec![Some(0), Some(1)]];
+        assert_eq!(min_cost_matching(&costs), [Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn prefer_lower_cost() {
+        let costs = vec![vec![Some(1), Some(0)], vec![Some(0), Some(1)]];
+        assert_eq!(min_cost_matching(&costs), [Some(1), Some(0)]);
+    }
+
+    #[test]
+    fn leave_unmatchable_rows() {
+        let costs = vec![vec![Some(0)], vec![Some(0)], vec![None]];
+        let matching = min_cost_matching(&costs);
+        assert_eq!(matching.iter().flatten().count(), 1);
+        assert_eq!(matching[2], None);
+        assert_eq!(min_cost_matching(&[]), []);
+    }
+
+    #[test]
+    fn match_like_brute_force() {
+        // Deterministic pseudo-random cost matrices of up to 4x4
+        let mut seed = 1_u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) % 4
+        };
+        for _ in 0..500 {
+            let rows = next() as usize + 1;
+            let cols = next() as usize + 1;
+            let costs = (0..rows)
+                .map(|_| (0..cols).map(|_| Some(next()).filter(|&c| c < 3)).collect())
+                .collect::<Vec<Vec<_>>>();
+            let matching = min_cost_matching(&costs);
+            let mut used = matching.iter().flatten().collect::<Vec<_>>();
+            used.sort_unstable();
+            used.dedup();
+            assert_eq!(used.len(), matching.iter().flatten().count(), "{costs:?}");
+            assert_eq!(
+                pairs_and_cost(&costs, &matching),
+                brute_force(&costs, 0, &mut vec![false; cols]),
+                "{costs:?}"
+            );
+        }
+    }
+}
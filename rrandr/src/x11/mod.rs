@@ -1,5 +1,12 @@
+pub mod error;
+pub mod gamma;
+mod matching;
+pub mod monitor;
 pub mod popup;
+pub mod property;
+pub mod provider;
 pub mod randr;
+pub mod resources;
 
 fn x_error_to_string(e: &x11rb::x11_utils::X11Error) -> String {
     format!(
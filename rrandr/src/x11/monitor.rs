@@ -0,0 +1,185 @@
+use std::error::Error;
+
+use x11rb::connection::RequestConnection;
+use x11rb::protocol::randr::{self, MonitorInfo, Output as OutputId};
+use x11rb::protocol::xproto::{get_atom_name, intern_atom, Atom, AtomEnum, Window as WindowId};
+
+use crate::data::enums::Rotation;
+use crate::data::output::{Output, PPI_DEFAULT};
+use crate::math::{Rect, MM_PER_INCH};
+
+/// A logical monitor, i.e. an area of the screen window managers treat as one
+/// monitor. Usually there is one per output but an output can be split into
+/// several monitors and several outputs can be joined into one.
+#[derive(Clone)]
+pub struct Monitor {
+    pub name: String,
+    pub primary: bool,
+    /// Created by the server for an output that isn't part of any other
+    /// monitor. These can't be deleted.
+    pub automatic: bool,
+    pub rect: Rect,
+    pub mm_width: u32,
+    pub mm_height: u32,
+    pub outputs: Vec<OutputId>,
+}
+
+impl Monitor {
+    /// Creates a monitor covering `rect` of the screen. It is made up of the
+    /// `outputs` intersecting `rect` unless they already are part of another
+    /// monitor in `monitors`. Its physical size is derived from the output it
+    /// covers the most.
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn from_region(name: &str, rect: Rect, outputs: &[Output], monitors: &[Monitor]) -> Self {
+        let taken = |o: &Output| {
+            monitors.iter().any(|m| !m.automatic && m.name != name && m.outputs.contains(&o.id()))
+        };
+        let covered = outputs
+            .iter()
+            .filter(|o| o.enabled() && !taken(o))
+            .filter_map(|o| {
+                rect.intersect(&o.rect()).map(|r| (o, u32::from(r.width()) * u32::from(r.height())))
+            })
+            .collect::<Vec<_>>();
+        let [ppi_x, ppi_y] =
+            covered.iter().max_by_key(|(_, area)| *area).map_or(PPI_DEFAULT, |(o, _)| {
+                let [x, y] = o.ppi();
+                if matches!(o.rotation(), Rotation::Left | Rotation::Right) {
+                    [y, x]
+                } else {
+                    [x, y]
+                }
+            });
+        Self {
+            name: name.to_owned(),
+            primary: false,
+            automatic: false,
+            mm_width: (f64::from(rect.width()) * MM_PER_INCH / ppi_x).round() as u32,
+            mm_height: (f64::from(rect.height()) * MM_PER_INCH / ppi_y).round() as u32,
+            rect,
+            outputs: covered.iter().map(|(o, _)| o.id()).collect(),
+        }
+    }
+}
+
+pub fn get_monitors(
+    conn: &impl RequestConnection,
+    root: WindowId,
+) -> Result<Vec<Monitor>, Box<dyn Error>> {
+    let reply = randr::get_monitors(conn, root, false)?.reply()?;
+    let mut monitors = Vec::with_capacity(reply.monitors.len());
+    for info in reply.monitors {
+        monitors.push(Monitor {
+            name: String::from_utf8_lossy(&get_atom_name(conn, info.name)?.reply()?.name)
+                .into_owned(),
+            primary: info.primary,
+            automatic: info.automatic,
+            rect: Rect::new(info.x, info.y, info.width, info.height),
+            mm_width: info.width_in_millimeters,
+            mm_height: info.height_in_millimeters,
+            outputs: info.outputs,
+        });
+    }
+    Ok(monitors)
+}
+
+/// Creates `monitor` or replaces the monitor with the same name.
+pub fn set_monitor(
+    conn: &impl RequestConnection,
+    root: WindowId,
+    monitor: &Monitor,
+) -> Result<(), Box<dyn Error>> {
+    let name = intern_atom(conn, false, monitor.name.as_bytes())?.reply()?.atom;
+    let info = MonitorInfo {
+        name,
+        primary: monitor.primary,
+        automatic: false,
+        x: monitor.rect.x(),
+        y: monitor.rect.y(),
+        width: monitor.rect.width(),
+        height: monitor.rect.height(),
+        width_in_millimeters: monitor.mm_width,
+        height_in_millimeters: monitor.mm_height,
+        outputs: monitor.outputs.clone(),
+    };
+    randr::set_monitor(conn, root, info)?.check()?;
+    Ok(())
+}
+
+pub fn delete_monitor(
+    conn: &impl RequestConnection,
+    root: WindowId,
+    name: &str,
+) -> Result<(), Box<dyn Error>> {
+    let atom = intern_atom(conn, true, name.as_bytes())?.reply()?.atom;
+    if atom == Atom::from(AtomEnum::NONE) {
+        return Err(format!("No monitor named {name}").into());
+    }
+    randr::delete_monitor(conn, root, atom)?.check()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::output::tests::output;
+
+    /// Two 1920x1080 outputs side by side and a disabled one.
+    fn outputs() -> Vec<Output> {
+        let outputs = vec![
+            output(1, "DP-1", "A", 1920),
+            output(2, "DP-2", "B", 1920),
+            output(3, "DP-3", "C", 1920),
+        ];
+        outputs[0].enable_at(0, 0);
+        outputs[1].enable_at(1920, 0);
+        outputs
+    }
+
+    fn monitor(name: &str, automatic: bool, outputs: Vec<OutputId>) -> Monitor {
+        Monitor {
+            name: name.to_owned(),
+            primary: false,
+            automatic,
+            rect: Rect::default(),
+            mm_width: 0,
+            mm_height: 0,
+            outputs,
+        }
+    }
+
+    #[test]
+    fn join_outputs() {
+        let rect = Rect::new(0, 0, 3840, 1080);
+        let monitor = Monitor::from_region("Joined", rect.clone(), &outputs(), &[]);
+        assert_eq!(monitor.rect, rect);
+        assert_eq!(monitor.outputs, [1, 2]);
+        // 96 PPI without a physical size
+        assert_eq!([monitor.mm_width, monitor.mm_height], [1016, 286]);
+    }
+
+    #[test]
+    fn split_output() {
+        let rect = Rect::new(1920, 0, 960, 1080);
+        let monitor = Monitor::from_region("Left", rect.clone(), &outputs(), &[]);
+        assert_eq!(monitor.rect, rect);
+        assert_eq!(monitor.outputs, [2]);
+        assert_eq!([monitor.mm_width, monitor.mm_height], [254, 286]);
+
+        let monitor =
+            Monitor::from_region("Nothing", Rect::new(0, 2000, 100, 100), &outputs(), &[]);
+        assert!(monitor.outputs.is_empty());
+    }
+
+    #[test]
+    fn skip_outputs_of_other_monitors() {
+        let rect = Rect::new(0, 0, 3840, 1080);
+        let monitors = [monitor("DP-1", true, vec![1]), monitor("Other", false, vec![2])];
+        let monitor = Monitor::from_region("New", rect.clone(), &outputs(), &monitors);
+        assert_eq!(monitor.outputs, [1]);
+        // Replacing a monitor keeps its outputs
+        let monitor = Monitor::from_region("Other", rect, &outputs(), &monitors);
+        assert_eq!(monitor.outputs, [1, 2]);
+    }
+}
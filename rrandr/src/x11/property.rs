@@ -0,0 +1,227 @@
+use std::error::Error;
+
+use gettextrs::ngettext;
+use x11rb::connection::RequestConnection;
+use x11rb::protocol::randr::{
+    change_output_property, get_output_property, list_output_properties, query_output_property,
+    GetOutputPropertyReply, Output as OutputId,
+};
+use x11rb::protocol::xproto::{get_atom_name, intern_atom, Atom, AtomEnum, PropMode};
+
+/// Maximum number of items shown for properties with multiple values.
+const MAX_SHOWN_ITEMS: usize = 8;
+
+/// Values accepted by an output property as announced by the driver.
+pub enum PropertyValues {
+    /// Any value between minimum and maximum inclusive.
+    Range(i64, i64),
+    /// One of the listed values with their display names.
+    Enum(Vec<(i64, String)>),
+    /// Values aren't restricted or the property can't be edited.
+    Any,
+}
+
+/// An output property like "Broadcast RGB" or "max bpc".
+pub struct OutputProperty {
+    pub atom: Atom,
+    pub name: String,
+    /// The current value if the property holds a single number or atom.
+    pub value: Option<i64>,
+    /// The current value for display.
+    pub text: String,
+    pub values: PropertyValues,
+    /// Whether a new value takes effect with the next mode set only.
+    pub pending: bool,
+    pub immutable: bool,
+}
+
+impl OutputProperty {
+    pub fn is_editable(&self) -> bool {
+        !self.immutable && self.value.is_some() && !matches!(self.values, PropertyValues::Any)
+    }
+}
+
+pub fn get_output_properties(
+    conn: &impl RequestConnection,
+    output: OutputId,
+) -> Result<Vec<OutputProperty>, Box<dyn Error>> {
+    let atoms = list_output_properties(conn, output)?.reply()?.atoms;
+    let mut properties = Vec::with_capacity(atoms.len());
+    for atom in atoms {
+        properties.push(get_output_property_info(conn, output, atom)?);
+    }
+    properties.sort_by_key(|p| p.name.to_lowercase());
+    Ok(properties)
+}
+
+fn get_output_property_info(
+    conn: &impl RequestConnection,
+    output: OutputId,
+    atom: Atom,
+) -> Result<OutputProperty, Box<dyn Error>> {
+    let name = get_name(conn, atom)?;
+    let info = query_output_property(conn, output, atom)?.reply()?;
+    let reply =
+        get_output_property(conn, output, atom, AtomEnum::ANY, 0, 1024, false, info.pending)?
+            .reply()?;
+    let items = get_items(&reply);
+    let is_atom = reply.type_ == Atom::from(AtomEnum::ATOM);
+
+    let text = if is_atom {
+        items
+            .iter()
+            .map(|&a| get_name(conn, item_to_atom(a)))
+            .collect::<Result<Vec<_>, _>>()?
+            .join(", ")
+    } else if reply.type_ == Atom::from(AtomEnum::STRING) {
+        String::from_utf8_lossy(&reply.data).into_owned()
+    } else if items.len() > MAX_SHOWN_ITEMS {
+        // Translators: Number of values of a property too long to be shown
+        ngettext!(
+            "{} value",
+            "{} values",
+            u32::try_from(items.len()).unwrap_or(u32::MAX),
+            items.len()
+        )
+    } else {
+        items.iter().map(i64::to_string).collect::<Vec<_>>().join(", ")
+    };
+
+    let values = if info.range && info.valid_values.len() == 2 {
+        PropertyValues::Range(info.valid_values[0].into(), info.valid_values[1].into())
+    } else if !info.range && !info.valid_values.is_empty() {
+        let mut values = Vec::with_capacity(info.valid_values.len());
+        for v in info.valid_values.iter().map(|&v| i64::from(v)) {
+            let name = if is_atom { get_name(conn, item_to_atom(v))? } else { v.to_string() };
+            values.push((v, name));
+        }
+        PropertyValues::Enum(values)
+    } else {
+        PropertyValues::Any
+    };
+
+    Ok(OutputProperty {
+        atom,
+        name,
+        value: if items.len() == 1 { items.first().copied() } else { None },
+        text,
+        values,
+        pending: info.pending,
+        immutable: info.immutable,
+    })
+}
+
+/// Sets a single value of an output property keeping its type and format.
+pub fn set_output_property(
+    conn: &impl RequestConnection,
+    output: OutputId,
+    property: Atom,
+    value: i64,
+) -> Result<(), Box<dyn Error>> {
+    let GetOutputPropertyReply { type_, format, .. } =
+        get_output_property(conn, output, property, AtomEnum::ANY, 0, 0, false, false)?.reply()?;
+    let signed = type_ == Atom::from(AtomEnum::INTEGER);
+    let data = match format {
+        8 if signed => i8::try_from(value).map(|v| v.to_ne_bytes().to_vec()).ok(),
+        8 => u8::try_from(value).map(|v| vec![v]).ok(),
+        16 if signed => i16::try_from(value).map(|v| v.to_ne_bytes().to_vec()).ok(),
+        16 => u16::try_from(value).map(|v| v.to_ne_bytes().to_vec()).ok(),
+        32 if signed => i32::try_from(value).map(|v| v.to_ne_bytes().to_vec()).ok(),
+        32 => u32::try_from(value).map(|v| v.to_ne_bytes().to_vec()).ok(),
+        _ => None,
+    }
+    .ok_or(format!("Invalid value {value} for property {}", get_name(conn, property)?))?;
+    change_output_property(conn, output, property, type_, format, PropMode::REPLACE, 1, &data)?
+        .check()?;
+    Ok(())
+}
+
+fn get_name(conn: &impl RequestConnection, atom: Atom) -> Result<String, Box<dyn Error>> {
+    Ok(String::from_utf8_lossy(&get_atom_name(conn, atom)?.reply()?.name).into_owned())
+}
+
+/// Numeric values of a property. Integers are signed while cardinals and atoms
+/// are unsigned.
+#[allow(clippy::cast_possible_wrap)]
+fn get_items(reply: &GetOutputPropertyReply) -> Vec<i64> {
+    let signed = reply.type_ == Atom::from(AtomEnum::INTEGER);
+    match reply.format {
+        8 => reply.data.iter().map(|&v| if signed { (v as i8).into() } else { v.into() }).collect(),
+        16 => reply
+            .data
+            .chunks_exact(2)
+            .map(|c| u16::from_ne_bytes([c[0], c[1]]))
+            .map(|v| if signed { (v as i16).into() } else { v.into() })
+            .collect(),
+        32 => reply
+            .data
+            .chunks_exact(4)
+            .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+            .map(|v| if signed { (v as i32).into() } else { v.into() })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn item_to_atom(item: i64) -> Atom { Atom::try_from(item).unwrap_or_default() }
+
+/// Backlight of an internal panel as exposed by the `Backlight` output
+/// property or its legacy name `BACKLIGHT`.
+#[derive(Clone, Copy)]
+pub struct Backlight {
+    atom: Atom,
+    value: i64,
+    min: i64,
+    max: i64,
+}
+
+impl Backlight {
+    /// Brightness in percent.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn percent(&self) -> f64 {
+        if self.max > self.min {
+            (self.value - self.min) as f64 * 100. / (self.max - self.min) as f64
+        } else {
+            100.
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_precision_loss)]
+    fn value_of(&self, percent: f64) -> i64 {
+        let value = self.min as f64 + percent.clamp(0., 100.) / 100. * (self.max - self.min) as f64;
+        value.round() as i64
+    }
+}
+
+pub fn get_backlight(
+    conn: &impl RequestConnection,
+    output: OutputId,
+) -> Result<Option<Backlight>, Box<dyn Error>> {
+    let properties = list_output_properties(conn, output)?.reply()?.atoms;
+    for name in ["Backlight", "BACKLIGHT"] {
+        let atom = intern_atom(conn, true, name.as_bytes())?.reply()?.atom;
+        if atom == Atom::from(AtomEnum::NONE) || !properties.contains(&atom) {
+            continue;
+        }
+        let info = query_output_property(conn, output, atom)?.reply()?;
+        let reply = get_output_property(conn, output, atom, AtomEnum::INTEGER, 0, 1, false, false)?
+            .reply()?;
+        if let (Some(&value), true, &[min, max]) =
+            (get_items(&reply).first(), info.range, info.valid_values.as_slice())
+        {
+            return Ok(Some(Backlight { atom, value, min: min.into(), max: max.into() }));
+        }
+    }
+    Ok(None)
+}
+
+/// Sets the brightness of `output` in percent.
+pub fn set_backlight(
+    conn: &impl RequestConnection,
+    output: OutputId,
+    backlight: &Backlight,
+    percent: f64,
+) -> Result<(), Box<dyn Error>> {
+    set_output_property(conn, output, backlight.atom, backlight.value_of(percent))
+}
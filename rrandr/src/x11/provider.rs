@@ -0,0 +1,124 @@
+use std::error::Error;
+
+use gettextrs::gettext;
+use x11rb::connection::RequestConnection;
+use x11rb::protocol::randr::{
+    self, get_screen_resources_current, Provider as ProviderId, ProviderCapability,
+};
+use x11rb::protocol::xproto::{Timestamp, Window as WindowId};
+
+/// A device providing CRTCs, outputs or rendering to the screen, e.g. one of
+/// the GPUs of a laptop with hybrid graphics.
+#[derive(Clone)]
+pub struct Provider {
+    pub id: ProviderId,
+    pub name: String,
+    pub capabilities: ProviderCapability,
+    pub crtcs: usize,
+    pub outputs: usize,
+    /// Provider rendering the images this provider shows on its outputs.
+    pub output_source: Option<ProviderId>,
+    /// Provider this provider offloads rendering to.
+    pub offload_sink: Option<ProviderId>,
+}
+
+impl Provider {
+    /// Whether the outputs of this provider can show the images of another
+    /// provider.
+    pub fn can_sink_output(&self) -> bool {
+        self.capabilities.contains(ProviderCapability::SINK_OUTPUT)
+    }
+
+    /// Whether this provider can render the images shown by another provider.
+    pub fn can_source_output(&self) -> bool {
+        self.capabilities.contains(ProviderCapability::SOURCE_OUTPUT)
+    }
+
+    /// Whether this provider can offload rendering to another provider.
+    pub fn can_source_offload(&self) -> bool {
+        self.capabilities.contains(ProviderCapability::SOURCE_OFFLOAD)
+    }
+
+    /// Whether this provider can render for another provider.
+    pub fn can_sink_offload(&self) -> bool {
+        self.capabilities.contains(ProviderCapability::SINK_OFFLOAD)
+    }
+
+    /// Names of the capabilities of this provider.
+    pub fn capability_names(&self) -> Vec<String> {
+        [
+            (self.can_source_output(), gettext("Source Output")),
+            (self.can_sink_output(), gettext("Sink Output")),
+            (self.can_source_offload(), gettext("Source Offload")),
+            (self.can_sink_offload(), gettext("Sink Offload")),
+        ]
+        .into_iter()
+        .filter_map(|(capable, name)| capable.then_some(name))
+        .collect()
+    }
+}
+
+pub fn get_providers(
+    conn: &impl RequestConnection,
+    root: WindowId,
+) -> Result<Vec<Provider>, Box<dyn Error>> {
+    let timestamp = config_timestamp(conn, root)?;
+    let reply = randr::get_providers(conn, root)?.reply()?;
+    let mut providers = Vec::with_capacity(reply.providers.len());
+    for id in reply.providers {
+        let info = randr::get_provider_info(conn, id, timestamp)?.reply()?;
+        // Associated providers are listed with the capability they use
+        let associated = |capability| {
+            info.associated_providers
+                .iter()
+                .zip(&info.associated_capability)
+                .find(|(_, &c)| ProviderCapability::from(c).contains(capability))
+                .map(|(&p, _)| p)
+        };
+        providers.push(Provider {
+            id,
+            name: String::from_utf8_lossy(&info.name).into_owned(),
+            capabilities: info.capabilities,
+            crtcs: info.crtcs.len(),
+            outputs: info.outputs.len(),
+            output_source: associated(ProviderCapability::SOURCE_OUTPUT),
+            offload_sink: associated(ProviderCapability::SINK_OFFLOAD),
+        });
+    }
+    Ok(providers)
+}
+
+/// Shows the images rendered by `source` on the outputs of `provider` or
+/// stops it if `source` is `None`.
+pub fn set_output_source(
+    conn: &impl RequestConnection,
+    root: WindowId,
+    provider: ProviderId,
+    source: Option<ProviderId>,
+) -> Result<(), Box<dyn Error>> {
+    let timestamp = config_timestamp(conn, root)?;
+    randr::set_provider_output_source(conn, provider, source.unwrap_or_default(), timestamp)?
+        .check()?;
+    Ok(())
+}
+
+/// Lets `provider` offload rendering to `sink` or stops it if `sink` is
+/// `None`.
+pub fn set_offload_sink(
+    conn: &impl RequestConnection,
+    root: WindowId,
+    provider: ProviderId,
+    sink: Option<ProviderId>,
+) -> Result<(), Box<dyn Error>> {
+    let timestamp = config_timestamp(conn, root)?;
+    randr::set_provider_offload_sink(conn, provider, sink.unwrap_or_default(), timestamp)?
+        .check()?;
+    Ok(())
+}
+
+fn config_timestamp(
+    conn: &impl RequestConnection,
+    root: WindowId,
+) -> Result<Timestamp, Box<dyn Error>> {
+    Ok(get_screen_resources_current(conn, root)?.reply()?.config_timestamp)
+}
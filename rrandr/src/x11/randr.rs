@@ -1,6 +1,7 @@
 use std::cell::{Cell, RefCell};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
+use std::path::{Path, PathBuf};
 use std::thread::{self, JoinHandle};
 
 use async_channel::Sender;
@@ -10,29 +11,45 @@ use x11rb::connection::{Connection as XConnection, RequestConnection};
 use x11rb::cookie::{Cookie, VoidCookie};
 use x11rb::errors::{ConnectionError, ReplyError};
 use x11rb::protocol::randr::{
-    self, get_crtc_info, get_crtc_transform, get_output_info, get_output_primary,
+    self, add_output_mode, create_mode, delete_output_mode, destroy_mode, get_crtc_gamma,
+    get_crtc_gamma_size, get_crtc_info, get_crtc_transform, get_output_info, get_output_primary,
     get_output_property, get_screen_resources_current, get_screen_size_range, query_version,
-    set_crtc_config, set_crtc_transform, set_output_primary, set_screen_size, Connection,
-    ConnectionExt, Crtc as CrtcId, CrtcChange, GetCrtcInfoReply, GetCrtcTransformReply,
-    GetOutputInfoReply, GetOutputPrimaryReply, GetScreenResourcesCurrentReply,
-    GetScreenSizeRangeReply, Mode as ModeId, ModeInfo, Notify, NotifyData, NotifyEvent, NotifyMask,
-    Output as OutputId, OutputChange, QueryVersionReply, Rotation as RRotation,
-    ScreenChangeNotifyEvent, ScreenSize, SetConfig,
+    set_crtc_config, set_crtc_gamma, set_crtc_transform, set_output_primary, set_panning,
+    set_screen_size, Connection, ConnectionExt, Crtc as CrtcId, CrtcChange, GetCrtcGammaReply,
+    GetCrtcInfoReply, GetCrtcTransformReply, GetOutputInfoReply, GetOutputPrimaryReply,
+    GetPanningReply, GetScreenResourcesCurrentReply, GetScreenSizeRangeReply, Mode as ModeId,
+    ModeInfo, Notify, NotifyData, NotifyEvent, NotifyMask, Output as OutputId, OutputChange,
+    Provider as ProviderId, QueryVersionReply, Rotation as RRotation, ScreenChangeNotifyEvent,
+    ScreenSize, SetConfig,
+};
+use x11rb::protocol::render::Transform as RTransform;
+use x11rb::protocol::xproto::{
+    change_property, delete_property, get_property, intern_atom, query_extension, Atom, AtomEnum,
+    PropMode, Window as WindowId,
 };
-use x11rb::protocol::render::Transform;
-use x11rb::protocol::xproto::{intern_atom, query_extension, AtomEnum, Window as WindowId};
 use x11rb::protocol::Event;
 use x11rb::rust_connection::RustConnection;
 use x11rb::CURRENT_TIME;
 
-use super::x_error_to_string;
-use crate::data::enums::Rotation;
+use super::error::{ApplyError, Cause, ConfigError, RandrError};
+use super::gamma::{self, Gamma, Ramps};
+use super::matching;
+use super::monitor::{self, Monitor};
+use super::property::{self, Backlight, OutputProperty};
+use super::provider::{self, Provider};
+use super::resources::{self, XFT_DPI, XFT_RGBA};
+use crate::custom_modes::CustomModes;
+use crate::data::enums::{MirrorFit, Rotation};
 use crate::data::mode::Mode;
 use crate::data::modes::Modes;
 use crate::data::output::{Output, PPI_DEFAULT};
 use crate::data::outputs::Outputs;
+use crate::data::panning::Panning;
+use crate::data::transform::{Transform, FILTER_DEFAULT};
+use crate::edid::{Edid, BLOCK_LEN as EDID_BLOCK_LEN};
+use crate::icc::{self, IccProfiles};
 use crate::math::{Rect, MM_PER_INCH};
-use crate::utils::nearly_eq;
+use crate::utils::{nearly_eq, nearly_eq_abs};
 
 type Version = QueryVersionReply;
 pub type ScreenSizeRange = GetScreenSizeRangeReply;
@@ -40,41 +57,59 @@ type ScreenResources = GetScreenResourcesCurrentReply;
 pub type OutputInfo = GetOutputInfoReply;
 type CrtcInfo = GetCrtcInfoReply;
 type Primary = GetOutputPrimaryReply;
-type Edid = Vec<u8>;
+type PanningInfo = GetPanningReply;
+type TransformInfo = GetCrtcTransformReply;
+type EdidData = Vec<u8>;
 
 pub const DISPLAY: Option<&str> = None;
 // pub const DISPLAY: Option<&str> = Some(":1");
 
 const MIN_VERSION: [u32; 2] = [1, 3];
 const CLIENT_VERSION: [u32; 2] = [1, 5];
+/// Version introducing providers and their change notifications.
+const PROVIDERS_VERSION: [u32; 2] = [1, 4];
+
+/// Root window property holding the ICC profile of the screen as defined by
+/// the ICC Profiles in X Specification.
+const ICC_PROFILE_ATOM: &str = "_ICC_PROFILE";
+/// Maximum length of an EDID, the base block followed by 255 extension
+/// blocks.
+const EDID_MAX_LEN: usize = 256 * EDID_BLOCK_LEN;
 
 pub struct Snapshot {
     root: WindowId,
     screen_size: ScreenSize,
     crtcs: HashMap<CrtcId, CrtcInfo>,
-    transforms: HashMap<CrtcId, Transform>,
+    transforms: HashMap<CrtcId, TransformInfo>,
+    gammas: HashMap<CrtcId, Ramps>,
+    pannings: HashMap<CrtcId, PanningInfo>,
+    icc_profile: Option<Vec<u8>>,
+    xft: [Option<String>; 2],
 }
 
 pub struct Randr {
     conn: RustConnection,
     root: WindowId,
+    version: [u32; 2],
     screen_size: Cell<ScreenSize>,
     screen_size_range: ScreenSizeRange,
     primary: Cell<Primary>,
     crtcs: RefCell<HashMap<CrtcId, CrtcInfo>>,
     outputs: RefCell<HashMap<OutputId, OutputInfo>>,
     modes: RefCell<HashMap<ModeId, ModeInfo>>,
-    transforms: RefCell<HashMap<CrtcId, Transform>>,
-    edids: HashMap<OutputId, Option<Edid>>,
-}
-
-impl Default for Randr {
-    fn default() -> Self { Self::new() }
+    mode_names: RefCell<HashMap<ModeId, String>>,
+    custom_modes: RefCell<CustomModes>,
+    /// ICC profiles assigned to any monitor or announced by rrandr.
+    known_icc_profiles: RefCell<HashSet<PathBuf>>,
+    transforms: RefCell<HashMap<CrtcId, TransformInfo>>,
+    gammas: RefCell<HashMap<CrtcId, Ramps>>,
+    pannings: RefCell<HashMap<CrtcId, PanningInfo>>,
+    edids: RefCell<HashMap<OutputId, Option<EdidData>>>,
 }
 
 impl Randr {
-    pub fn new() -> Self {
-        let (conn, screen_num) = x11rb::connect(DISPLAY).expect("connection to X Server");
+    pub fn new() -> Result<Self, RandrError> {
+        let (conn, screen_num) = x11rb::connect(DISPLAY)?;
         let screen = &conn.setup().roots[screen_num];
         let root = screen.root;
         let screen_size = ScreenSize {
@@ -88,59 +123,88 @@ impl Randr {
             screen_size.width, screen_size.height, screen_size.mwidth, screen_size.mheight
         );
 
-        let res =
-            get_screen_resources_current(&conn, root).expect("cookie to request screen resources");
-        let res = res.reply().expect("reply for screen resources");
-
-        let primary = get_output_primary(&conn, root).expect("cookie to request primary output");
-        let primary = primary.reply().expect("reply for primary output");
-
-        let screen_size_range =
-            get_screen_size_range(&conn, root).expect("cookie to request screen size range");
-        let screen_size_range = screen_size_range.reply().expect("reply for screen size range");
+        let Version { major_version, minor_version, .. } =
+            query_version(&conn, CLIENT_VERSION[0], CLIENT_VERSION[1])?.reply()?;
+        let screen_size_range = get_screen_size_range(&conn, root)?.reply()?;
 
-        let outputs = request_outputs(&conn, &res).expect("cookies to request outputs");
-        let crtcs = request_crtcs(&conn, &res).expect("cookies to request crtcs");
-        let transforms = request_transforms(&conn, &res).expect("cookies to request transforms");
+        let randr = Self {
+            conn,
+            root,
+            version: [major_version, minor_version],
+            screen_size: Cell::new(screen_size),
+            screen_size_range,
+            primary: Cell::default(),
+            crtcs: RefCell::default(),
+            outputs: RefCell::default(),
+            modes: RefCell::default(),
+            mode_names: RefCell::default(),
+            custom_modes: RefCell::new(CustomModes::load()),
+            known_icc_profiles: RefCell::default(),
+            transforms: RefCell::default(),
+            gammas: RefCell::default(),
+            pannings: RefCell::default(),
+            edids: RefCell::default(),
+        };
+        randr.refresh()?;
+        Ok(randr)
+    }
 
-        let outputs: HashMap<OutputId, OutputInfo> =
-            get_outputs(outputs).expect("reply for outputs");
-        let crtcs: HashMap<CrtcId, CrtcInfo> = get_crtcs(crtcs).expect("reply for crtcs");
+    /// Queries the outputs, CRTCs and modes again, e.g. after changing
+    /// providers made outputs appear or disappear.
+    pub fn refresh(&self) -> Result<(), RandrError> {
+        let conn = &self.conn;
+        let res = get_screen_resources_current(conn, self.root)?.reply()?;
+        let primary = get_output_primary(conn, self.root)?.reply()?;
+
+        let outputs = request_outputs(conn, &res)?;
+        let crtcs = request_crtcs(conn, &res)?;
+        let transforms = request_transforms(conn, &res)?;
+        let gammas = request_gammas(conn, &res)?;
+        let pannings = request_pannings(conn, &res)?;
+
+        let outputs: HashMap<OutputId, OutputInfo> = get_outputs(outputs)?;
+        let crtcs: HashMap<CrtcId, CrtcInfo> = get_crtcs(crtcs)?;
         let modes: HashMap<ModeId, ModeInfo> = res.modes.iter().map(|m| (m.id, *m)).collect();
-        let transforms = get_transforms(transforms).expect("reply for transforms");
+        let mode_names = get_mode_names(&res);
+        let transforms = get_transforms(transforms)?;
+        let gammas = get_gammas(gammas)?;
+        let pannings = get_pannings(pannings)?;
 
-        let edids: HashMap<OutputId, Option<Edid>> =
-            res.outputs.iter().map(|&o| (o, get_edid(&conn, o).ok())).collect();
+        let edids: HashMap<OutputId, Option<EdidData>> =
+            res.outputs.iter().map(|&o| (o, get_edid(conn, o).ok())).collect();
 
         #[cfg(debug_assertions)]
         log_crtcs(&crtcs, &modes, &transforms);
         #[cfg(debug_assertions)]
         log_outputs(&outputs, &modes);
 
-        Self {
-            conn,
-            root,
-            screen_size: Cell::new(screen_size),
-            screen_size_range,
-            primary: Cell::new(primary),
-            crtcs: RefCell::new(crtcs),
-            outputs: RefCell::new(outputs),
-            modes: RefCell::new(modes),
-            transforms: RefCell::new(transforms),
-            edids,
-        }
+        self.primary.set(primary);
+        self.crtcs.replace(crtcs);
+        self.outputs.replace(outputs);
+        self.modes.replace(modes);
+        self.mode_names.replace(mode_names);
+        self.transforms.replace(transforms);
+        self.gammas.replace(gammas);
+        self.pannings.replace(pannings);
+        self.edids.replace(edids);
+        Ok(())
     }
 
-    pub fn handle_event(&self, event: &Event) {
+    /// Updates the cached configuration from a change event. Fails if
+    /// changed resources can't be queried, e.g. while another client changes
+    /// modes, leaving the cache incomplete.
+    pub fn handle_event(&self, event: &Event) -> Result<(), RandrError> {
         match *event {
             Event::RandrScreenChangeNotify(e) => self.handle_screen_change(&e),
             Event::RandrNotify(NotifyEvent { sub_code, u: data, .. }) => match sub_code {
-                Notify::CRTC_CHANGE => self.handle_crtc_change(&data),
-                Notify::OUTPUT_CHANGE => self.handle_output_change(&data),
+                Notify::CRTC_CHANGE => return self.handle_crtc_change(&data),
+                Notify::OUTPUT_CHANGE => return self.handle_output_change(&data),
+                Notify::PROVIDER_CHANGE | Notify::RESOURCE_CHANGE => return self.refresh(),
                 _ => (),
             },
             _ => (),
         }
+        Ok(())
     }
 
     fn handle_screen_change(&self, event: &ScreenChangeNotifyEvent) {
@@ -169,7 +233,7 @@ impl Randr {
         });
     }
 
-    fn handle_crtc_change(&self, data: &NotifyData) {
+    fn handle_crtc_change(&self, data: &NotifyData) -> Result<(), RandrError> {
         let CrtcChange {
             timestamp, window, crtc, mode, rotation: rot, x, y, width, height, ..
         } = data.as_cc();
@@ -178,7 +242,7 @@ impl Randr {
 
         if self.root != window {
             warn!("Unknown window");
-            return;
+            return Ok(());
         }
 
         debug!("Mode: {mode}");
@@ -189,14 +253,9 @@ impl Randr {
         let mut crtcs = self.crtcs.borrow_mut();
         let Some(crtc_info) = crtcs.get_mut(&crtc) else {
             debug!("New CRTC found: {crtc}");
-            let crtc_info = self
-                .conn
-                .randr_get_crtc_info(crtc, timestamp)
-                .expect("should send crtc request")
-                .reply()
-                .expect("should get crtc reply");
+            let crtc_info = self.conn.randr_get_crtc_info(crtc, timestamp)?.reply()?;
             crtcs.insert(crtc, crtc_info);
-            return;
+            return Ok(());
         };
 
         crtc_info.mode = mode;
@@ -204,29 +263,27 @@ impl Randr {
         crtc_info.x = x;
         crtc_info.y = y;
 
-        let transform = self
-            .conn
-            .randr_get_crtc_transform(crtc)
-            .expect("should send transform request")
-            .reply()
-            .expect("should get transform reply")
-            .current_transform;
+        let transform = self.conn.randr_get_crtc_transform(crtc)?.reply()?;
+        let matrix = from_render_transform(&transform.current_transform);
         self.transforms.borrow_mut().insert(crtc, transform);
 
-        let sx = f64::from(Fixed(transform.matrix11));
-        let sy = f64::from(Fixed(transform.matrix22));
+        let panning = randr::get_panning(&self.conn, crtc)?.reply()?;
+        self.pannings.borrow_mut().insert(crtc, panning);
+
         let [w, h] = if rot.intersects(RRotation::ROTATE90 | RRotation::ROTATE270) {
             [height, width]
         } else {
             [width, height]
         }
         .map(f64::from);
+        let [w, h] = matrix.bounds(w, h);
 
-        crtc_info.width = (w * sx).round() as u16;
-        crtc_info.height = (h * sy).round() as u16;
+        crtc_info.width = w.round() as u16;
+        crtc_info.height = h.round() as u16;
+        Ok(())
     }
 
-    fn handle_output_change(&self, data: &NotifyData) {
+    fn handle_output_change(&self, data: &NotifyData) -> Result<(), RandrError> {
         let OutputChange {
             window, output, crtc, mode, connection: conn, subpixel_order: subp, ..
         } = data.as_oc();
@@ -235,7 +292,7 @@ impl Randr {
 
         if self.root != window {
             warn!("Unknown window");
-            return;
+            return Ok(());
         }
 
         debug!("CRTC: {crtc}");
@@ -246,7 +303,7 @@ impl Randr {
         let mut outputs = self.outputs.borrow_mut();
         let Some(output_info) = outputs.get_mut(&output) else {
             warn!("Output: {output} not found");
-            return;
+            return Ok(());
         };
 
         // Update CRTC association
@@ -268,38 +325,38 @@ impl Randr {
             output_info.crtc = crtc;
         }
 
+        // Update EDID (a different monitor might have been plugged in)
+        if conn != output_info.connection {
+            self.edids.borrow_mut().insert(output, get_edid(&self.conn, output).ok());
+        }
+
         output_info.connection = conn;
         output_info.subpixel_order = subp;
 
         // Update modes (there can be new and/or deleted modes)
-        let res = get_screen_resources_current(&self.conn, self.root)
-            .expect("should send screen resources request")
-            .reply()
-            .expect("should get screen resources reply");
+        let res = get_screen_resources_current(&self.conn, self.root)?.reply()?;
         *self.modes.borrow_mut() = res.modes.iter().map(|m| (m.id, *m)).collect::<HashMap<_, _>>();
+        *self.mode_names.borrow_mut() = get_mode_names(&res);
 
         // Update output modes
         if mode > 0 {
-            output_info.modes = get_output_info(&self.conn, output, res.config_timestamp)
-                .expect("should request output info")
-                .reply()
-                .expect("should get output info reply")
-                .modes;
+            output_info.modes =
+                get_output_info(&self.conn, output, res.config_timestamp)?.reply()?.modes;
         }
 
         // Update primary output
-        self.primary.set(
-            get_output_primary(&self.conn, self.root)
-                .expect("should send primary request")
-                .reply()
-                .expect("should get primary reply"),
-        );
+        self.primary.set(get_output_primary(&self.conn, self.root)?.reply()?);
+        Ok(())
     }
 
+    pub fn screen_size(&self) -> ScreenSize { self.screen_size.get() }
+
     pub fn screen_size_range(&self) -> ScreenSizeRange { self.screen_size_range }
 
     pub fn output_model(&self) -> Outputs {
         let outputs = Outputs::default();
+        let icc_profiles = IccProfiles::load();
+        self.known_icc_profiles.borrow_mut().extend(icc_profiles.paths().cloned());
         for (id, output_info) in self.outputs.borrow().iter() {
             if output_info.connection != Connection::CONNECTED {
                 continue;
@@ -309,31 +366,48 @@ impl Randr {
             let modes: Modes = Modes::new();
             let mut mode = None;
             for mode_id in &output_info.modes {
-                modes.append(&Mode::from(self.modes.borrow()[mode_id]));
+                modes.append(&self.get_mode(*mode_id));
             }
             let mut rotation = RRotation::ROTATE0;
             let mut pos = [0, 0];
             let mut dim = [0, 0];
             let mut scale = [1., 1.];
+            let mut gamma = Gamma::default();
+            let mut panning = None;
+            let mut transform = None;
+            let mut filter = String::new();
             if enabled {
                 let crtc_info = &self.crtcs.borrow()[&output_info.crtc];
                 mode = modes.find_by_id(crtc_info.mode);
                 rotation = crtc_info.rotation;
                 pos = [crtc_info.x, crtc_info.y];
                 dim = [crtc_info.width, crtc_info.height];
-                let transform = self.transforms.borrow()[&output_info.crtc];
-                scale =
-                    [f64::from(Fixed(transform.matrix11)), f64::from(Fixed(transform.matrix22))];
+                let transforms = self.transforms.borrow();
+                let info = &transforms[&output_info.crtc];
+                let matrix = from_render_transform(&info.current_transform);
+                scale = [matrix.0[0][0], matrix.0[1][1]];
+                transform = Some(matrix).filter(|m| !m.is_scale());
+                filter = String::from_utf8_lossy(&info.current_filter_name).into_owned();
+                if let Some(ramps) = self.gammas.borrow().get(&output_info.crtc) {
+                    gamma = Gamma::estimate(ramps);
+                }
+                if let Some(info) = self.pannings.borrow().get(&output_info.crtc) {
+                    panning = get_panning(info);
+                    if panning.is_some() {
+                        // The output moves within its panning area, place it at its origin
+                        pos = [info.left, info.top].map(|v| i16::try_from(v).unwrap_or(i16::MAX));
+                    }
+                }
             }
-            let product_name = self
-                .edids
-                .get(id)
-                .and_then(|e| e.as_ref().map(|edid| get_monitor_name(edid)))
-                .unwrap_or_default();
-            outputs.append(&Output::new(
+            let name = String::from_utf8_lossy(&output_info.name).into_owned();
+            let edid = self.edid(*id);
+            let product_name = edid.as_ref().and_then(|edid| edid.name.clone());
+            let monitor_id = edid.map_or_else(|| name.clone(), |edid| edid.monitor_id());
+            let output = Output::new(
                 *id,
-                String::from_utf8_lossy(&output_info.name).into_owned(),
+                name,
                 product_name,
+                monitor_id,
                 enabled,
                 *id == self.primary.get().output,
                 pos,
@@ -344,22 +418,273 @@ impl Randr {
                 scale,
                 dim,
                 [output_info.mm_width, output_info.mm_height],
-            ));
+            );
+            output.set_gamma(gamma.rgb);
+            output.set_brightness(gamma.brightness);
+            output.set_panning(panning);
+            output.set_transform(transform);
+            output.set_filter(if filter.is_empty() { FILTER_DEFAULT } else { &filter });
+            output.set_icc_profile(
+                icc_profiles.get(&output.monitor_id()).map(|p| p.to_string_lossy().into_owned()),
+            );
+            outputs.append(&output);
         }
+        self.find_mirrors(&outputs);
         outputs
     }
 
+    fn get_mode(&self, id: ModeId) -> Mode {
+        let mode = Mode::from(self.modes.borrow()[&id]);
+        if let Some(name) = self.mode_names.borrow().get(&id) {
+            mode.set_name(name.as_str());
+        }
+        mode.set_custom(self.custom_modes.borrow().contains(&mode.name()));
+        mode
+    }
+
+    /// Creates a mode on the server and adds it to the modes of `output`.
+    pub fn create_mode(
+        &self,
+        output: OutputId,
+        mode_info: ModeInfo,
+        name: &str,
+    ) -> Result<Mode, Box<dyn Error>> {
+        debug!("Creating mode {name} for output {output}");
+        let id = create_mode(&self.conn, self.root, mode_info, name.as_bytes())?.reply()?.mode;
+        add_output_mode(&self.conn, output, id)?.check()?;
+
+        self.modes.borrow_mut().insert(id, ModeInfo { id, ..mode_info });
+        self.mode_names.borrow_mut().insert(id, name.to_owned());
+        self.remember_custom_mode(name, true);
+        if let Some(output_info) = self.outputs.borrow_mut().get_mut(&output) {
+            if !output_info.modes.contains(&id) {
+                output_info.modes.push(id);
+            }
+        }
+        Ok(self.get_mode(id))
+    }
+
+    /// Removes a mode from the modes of `output` and destroys it once no
+    /// output uses it anymore.
+    pub fn delete_mode(&self, output: OutputId, mode: ModeId) -> Result<(), Box<dyn Error>> {
+        debug!("Deleting mode {mode} of output {output}");
+        delete_output_mode(&self.conn, output, mode)?.check()?;
+        if let Some(output_info) = self.outputs.borrow_mut().get_mut(&output) {
+            output_info.modes.retain(|&m| m != mode);
+        }
+
+        if self.outputs.borrow().values().all(|o| !o.modes.contains(&mode)) {
+            debug!("Destroying mode {mode}");
+            destroy_mode(&self.conn, mode)?.check()?;
+            self.modes.borrow_mut().remove(&mode);
+            if let Some(name) = self.mode_names.borrow_mut().remove(&mode) {
+                self.remember_custom_mode(&name, false);
+            }
+        }
+        Ok(())
+    }
+
+    /// Remembers or forgets that the mode `name` was created by the user.
+    fn remember_custom_mode(&self, name: &str, custom: bool) {
+        let mut custom_modes = self.custom_modes.borrow_mut();
+        if custom {
+            custom_modes.insert(name);
+        } else {
+            custom_modes.remove(name);
+        }
+        if let Err(e) = custom_modes.save() {
+            warn!("Failed to remember custom modes: {e}");
+        }
+    }
+
+    pub fn output_properties(
+        &self,
+        output: OutputId,
+    ) -> Result<Vec<OutputProperty>, Box<dyn Error>> {
+        property::get_output_properties(&self.conn, output)
+    }
+
+    pub fn set_output_property(
+        &self,
+        output: OutputId,
+        property: Atom,
+        value: i64,
+    ) -> Result<(), Box<dyn Error>> {
+        debug!("Setting property {property} of output {output} to {value}");
+        property::set_output_property(&self.conn, output, property, value)
+    }
+
+    /// Raw EDID of the monitor connected to `output` including all extension
+    /// blocks.
+    pub fn edid_data(&self, output: OutputId) -> Option<Vec<u8>> {
+        self.edids.borrow().get(&output)?.clone().filter(|data| !data.is_empty())
+    }
+
+    /// Parsed EDID of the monitor connected to `output`.
+    pub fn edid(&self, output: OutputId) -> Option<Edid> {
+        Edid::parse(&self.edid_data(output)?)
+            .inspect_err(|e| warn!("Failed to parse EDID of output {output}: {e}"))
+            .ok()
+    }
+
+    pub fn backlight(&self, output: OutputId) -> Option<Backlight> {
+        property::get_backlight(&self.conn, output).unwrap_or_else(|e| {
+            warn!("Failed to get backlight of output {output}: {e}");
+            None
+        })
+    }
+
+    /// Sets the brightness of the backlight of `output` in percent. Unlike
+    /// other changes this takes effect immediately.
+    pub fn set_backlight(
+        &self,
+        output: OutputId,
+        backlight: &Backlight,
+        percent: f64,
+    ) -> Result<(), Box<dyn Error>> {
+        debug!("Setting backlight of output {output} to {percent:.0}%");
+        property::set_backlight(&self.conn, output, backlight, percent)
+    }
+
+    pub fn monitors(&self) -> Vec<Monitor> {
+        monitor::get_monitors(&self.conn, self.root).unwrap_or_else(|e| {
+            warn!("Failed to get monitors: {e}");
+            Vec::new()
+        })
+    }
+
+    /// Creates or replaces a monitor. Like backlight changes this takes effect
+    /// immediately.
+    pub fn set_monitor(&self, monitor: &Monitor) -> Result<(), Box<dyn Error>> {
+        debug!("Setting monitor {} to {:?}", monitor.name, monitor.rect);
+        monitor::set_monitor(&self.conn, self.root, monitor)
+    }
+
+    pub fn delete_monitor(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        debug!("Deleting monitor {name}");
+        monitor::delete_monitor(&self.conn, self.root, name)
+    }
+
+    /// Gets the providers of the screen. Requires version 1.4 of the extension.
+    pub fn providers(&self) -> Vec<Provider> {
+        if self.version < PROVIDERS_VERSION {
+            return Vec::new();
+        }
+        provider::get_providers(&self.conn, self.root).unwrap_or_else(|e| {
+            warn!("Failed to get providers: {e}");
+            Vec::new()
+        })
+    }
+
+    pub fn set_provider_output_source(
+        &self,
+        provider: ProviderId,
+        source: Option<ProviderId>,
+    ) -> Result<(), Box<dyn Error>> {
+        debug!("Setting output source of provider {provider} to {source:?}");
+        provider::set_output_source(&self.conn, self.root, provider, source)
+    }
+
+    pub fn set_provider_offload_sink(
+        &self,
+        provider: ProviderId,
+        sink: Option<ProviderId>,
+    ) -> Result<(), Box<dyn Error>> {
+        debug!("Setting offload sink of provider {provider} to {sink:?}");
+        provider::set_offload_sink(&self.conn, self.root, provider, sink)
+    }
+
     pub fn snapshot(&self) -> Snapshot {
         Snapshot {
             root: self.root,
             screen_size: self.screen_size.get(),
             crtcs: self.crtcs.borrow().clone(),
             transforms: self.transforms.borrow().clone(),
+            gammas: self.gammas.borrow().clone(),
+            pannings: self.pannings.borrow().clone(),
+            icc_profile: self.icc_profile().unwrap_or_else(|e| {
+                warn!("Failed to get {ICC_PROFILE_ATOM}: {e}");
+                None
+            }),
+            xft: self.resources().map_or_else(
+                |e| {
+                    warn!("Failed to get resources: {e}");
+                    [None, None]
+                },
+                |r| [XFT_DPI, XFT_RGBA].map(|name| resources::value(&r, name).map(str::to_owned)),
+            ),
+        }
+    }
+
+    /// Checks whether `outputs` can be applied before any request is sent:
+    /// the screen doesn't exceed its maximum size and every enabled output
+    /// gets a CRTC supporting its mode, rotation and reflection.
+    pub fn check_config(&self, outputs: &Outputs) -> Vec<ConfigError> {
+        let enabled = outputs
+            .iter::<Output>()
+            .map(Result::unwrap)
+            .filter(Output::enabled)
+            .collect::<Vec<_>>();
+        let mut errors = Vec::new();
+
+        let ScreenSizeRange { max_width, max_height, .. } = self.screen_size_range;
+        let bounds = Rect::bounds(enabled.iter().map(Output::panning_rect).collect());
+        let exceeding = enabled
+            .iter()
+            .filter(|o| {
+                let rect = o.panning_rect();
+                rect.right() - bounds.left() > i32::from(max_width)
+                    || rect.bottom() - bounds.top() > i32::from(max_height)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if !exceeding.is_empty() {
+            errors
+                .push(ConfigError::ScreenSize { outputs: exceeding, max: [max_width, max_height] });
         }
+
+        let infos = self.outputs.borrow();
+        let crtcs = self.crtcs.borrow();
+        for output in &enabled {
+            let Some(info) = infos.get(&output.id()) else {
+                continue;
+            };
+            if output.mode().is_none_or(|m| !info.modes.contains(&m.id())) {
+                errors.push(ConfigError::Mode(output.clone()));
+            } else if !info.crtcs.iter().any(|c| {
+                crtcs.get(c).is_some_and(|c| c.rotations.contains(output.randr_rotation()))
+            }) {
+                errors.push(ConfigError::Rotation(output.clone()));
+            }
+        }
+
+        if let Err(unassigned) = self.assign_crtcs(outputs) {
+            // Outputs with an unsupported mode or rotation never get a CRTC
+            let invalid = errors.iter().flat_map(ConfigError::outputs).collect::<Vec<_>>();
+            let unassigned =
+                unassigned.into_iter().filter(|o| !invalid.contains(&o.id())).collect::<Vec<_>>();
+            if !unassigned.is_empty() {
+                errors.push(ConfigError::NoCrtc(unassigned));
+            }
+        }
+        errors
     }
 
-    pub fn apply(&self, outputs: &Outputs) -> bool {
+    #[allow(clippy::too_many_lines)]
+    pub fn apply(&self, outputs: &Outputs) -> Result<(), ApplyError> {
         debug!("Applying changes");
+        let errors = self.check_config(outputs);
+        if !errors.is_empty() {
+            return Err(ApplyError::Config(errors));
+        }
+        let assignments = self
+            .assign_crtcs(outputs)
+            .map_err(|outputs| ApplyError::Config(vec![ConfigError::NoCrtc(outputs)]))?;
+        let assigned = assignments
+            .iter()
+            .flat_map(|(crtc_id, clones)| clones.iter().map(|o| (o.id(), *crtc_id)))
+            .collect::<HashMap<_, _>>();
+
         let primary = outputs.iter::<Output>().map(Result::unwrap).find(Output::primary);
         let screen_size = self.get_screen_size(outputs, primary.as_ref());
 
@@ -377,23 +702,37 @@ impl Randr {
             }
             let crtc = &self.crtcs.borrow()[&crtc_id];
             // Even though the crtc dimension contains the transformation, a match error
-            // occurs when setting the screen size, so disable scaled CRTCs.
-            let transform = self.transforms.borrow()[&crtc_id];
-            let has_scale = transform.matrix11 / 65536 != 1 || transform.matrix22 / 65536 != 1;
-            if !output.enabled()
-                || has_scale
+            // occurs when setting the screen size, so disable transformed CRTCs.
+            let has_transform =
+                !from_render_transform(&self.transforms.borrow()[&crtc_id].current_transform)
+                    .is_identity();
+            let panning = self.pannings.borrow().get(&crtc_id).copied().unwrap_or_default();
+            let right = (i32::from(crtc.x) + i32::from(crtc.width))
+                .max(i32::from(panning.left) + i32::from(panning.width));
+            let bottom = (i32::from(crtc.y) + i32::from(crtc.height))
+                .max(i32::from(panning.top) + i32::from(panning.height));
+            if assigned.get(&output.id()) != Some(&crtc_id)
+                || has_transform
                 || (screen_size_px_changed
-                    && (i32::from(crtc.x) + i32::from(crtc.width) > i32::from(screen_size.width)
-                        || i32::from(crtc.y) + i32::from(crtc.height)
-                            > i32::from(screen_size.height)))
+                    && (right > i32::from(screen_size.width)
+                        || bottom > i32::from(screen_size.height)))
             {
-                // Disable outputs that are still enabled but shouldn't be and outputs that stay
-                // enabled but currently don't fit the new screen size. The latter needs to be
-                // done to avoid an invalid intermediate configuration when actually setting the
-                // new screen size.
-                if handle_reply_error(self.disable_crtc(crtc_id), "disable CRTC") {
-                    return false;
-                }
+                // Disable outputs that are still enabled but shouldn't be or move to another
+                // CRTC and outputs that stay enabled but currently don't fit the new screen
+                // size. The latter needs to be done to avoid an invalid
+                // intermediate configuration when actually setting the new
+                // screen size.
+                check_reply(self.disable_crtc(crtc_id)).map_err(|cause| {
+                    // Name all outputs of a clone group driven by the CRTC
+                    let outputs = self.outputs.borrow();
+                    let names = crtc
+                        .outputs
+                        .iter()
+                        .filter_map(|o| outputs.get(o))
+                        .map(|info| String::from_utf8_lossy(&info.name).into_owned())
+                        .collect();
+                    ApplyError::DisableCrtc { crtc: crtc_id, outputs: names, cause }
+                })?;
             }
         }
 
@@ -403,76 +742,267 @@ impl Randr {
                 screen_size.width, screen_size.height, screen_size.mwidth, screen_size.mheight
             );
 
-            if handle_no_reply_error(
-                set_screen_size(
-                    &self.conn,
-                    self.root,
-                    screen_size.width,
-                    screen_size.height,
-                    screen_size.mwidth.into(),
-                    screen_size.mheight.into(),
-                ),
-                "set screen size",
-            ) {
-                return false;
-            }
+            let mm = [screen_size.mwidth.into(), screen_size.mheight.into()];
+            check_no_reply(set_screen_size(
+                &self.conn,
+                self.root,
+                screen_size.width,
+                screen_size.height,
+                mm[0],
+                mm[1],
+            ))
+            .map_err(|cause| ApplyError::SetScreenSize {
+                size: [screen_size.width, screen_size.height],
+                mm,
+                cause,
+            })?;
         }
 
         // Update outputs
-        for output in outputs.iter::<Output>().map(Result::unwrap).filter(Output::enabled) {
-            let output_info = &self.outputs.borrow()[&output.id()];
-            let mut crtc_id = output_info.crtc;
-            if crtc_id == 0
-                || self.crtcs.borrow().get(&crtc_id).is_some_and(|ci: &CrtcInfo| {
-                    ci.outputs.len() > 1 && ci.outputs[0] != output.id()
-                })
-            {
-                // If this output was disabled before get it a new empty CRTC. If this output is
-                // enabled, shares a CRTC with other outputs and it's not the first one listed,
-                // move it to a new empty CRTC.
-                if let Some(empty_id) = self.get_valid_empty_crtc(&output) {
-                    crtc_id = empty_id;
-                } else {
-                    return false;
+        for (crtc_id, clones) in assignments {
+            let output = &clones[0];
+            let names = || clones.iter().map(Output::name).collect::<Vec<_>>();
+            let transform = get_transform(output);
+            let filter = output.filter();
+            let filter = if filter.is_empty() { FILTER_DEFAULT } else { &filter };
+            check_no_reply(set_crtc_transform(
+                &self.conn,
+                crtc_id,
+                transform,
+                filter.as_bytes(),
+                &self.get_filter_params(filter),
+            ))
+            .map_err(|cause| ApplyError::SetTransform {
+                crtc: crtc_id,
+                outputs: names(),
+                cause,
+            })?;
+
+            let mode = output.mode();
+            check_reply(self.update_crtc(
+                crtc_id,
+                output.x(),
+                output.y(),
+                mode.as_ref().map_or(0, Mode::id),
+                output.randr_rotation(),
+                &clones.iter().map(Output::id).collect::<Vec<_>>(),
+            ))
+            .map_err(|cause| ApplyError::SetCrtcConfig {
+                crtc: crtc_id,
+                outputs: names(),
+                mode: mode.as_ref().map_or(0, Mode::id),
+                mode_name: mode.as_ref().map_or_else(String::new, |m| {
+                    format!("{}x{} {:.2} Hz", m.width(), m.height(), m.refresh())
+                }),
+                cause,
+            })?;
+
+            check_reply(self.update_panning(crtc_id, output)).map_err(|cause| {
+                ApplyError::SetPanning { crtc: crtc_id, outputs: names(), cause }
+            })?;
+
+            self.update_gamma(crtc_id, output).map_err(|cause| ApplyError::SetGamma {
+                crtc: crtc_id,
+                outputs: names(),
+                cause,
+            })?;
+        }
+
+        // Set primary output
+        let primary_id = primary.as_ref().map(Output::id).unwrap_or_default();
+        check_no_reply(set_output_primary(&self.conn, self.root, primary_id)).map_err(|cause| {
+            ApplyError::SetPrimary { output: primary.as_ref().map(Output::name), cause }
+        })?;
+
+        self.update_icc_profile(outputs, primary.as_ref());
+        Ok(())
+    }
+
+    /// Gets the parameters of `filter` as currently used by any CRTC. Only
+    /// filters like convolutions that were set up by another client take
+    /// parameters.
+    fn get_filter_params(&self, filter: &str) -> Vec<i32> {
+        self.transforms
+            .borrow()
+            .values()
+            .find(|t| t.current_filter_name == filter.as_bytes())
+            .map(|t| t.current_params.clone())
+            .unwrap_or_default()
+    }
+
+    /// Announces the ICC profile of `primary` to color managed applications.
+    /// If `primary` has none, a profile announced by rrandr is removed while
+    /// one announced by another tool is kept.
+    fn update_icc_profile(&self, outputs: &Outputs, primary: Option<&Output>) {
+        let data = primary.and_then(Output::icc_profile).and_then(|path| {
+            match icc::read_profile(Path::new(&path)) {
+                Ok(data) => {
+                    self.known_icc_profiles.borrow_mut().insert(PathBuf::from(&path));
+                    debug!("Setting {ICC_PROFILE_ATOM} to {path}");
+                    Some(data)
+                }
+                Err(e) => {
+                    warn!("Failed to read ICC profile {path}: {e}");
+                    None
+                }
+            }
+        });
+        if data.is_none() {
+            let current = match self.icc_profile() {
+                Ok(current) => current,
+                Err(e) => {
+                    warn!("Failed to get {ICC_PROFILE_ATOM}: {e}");
+                    return;
                 }
+            };
+            let assigned =
+                outputs.iter::<Output>().map(Result::unwrap).any(|o| o.icc_profile().is_some());
+            let known = self
+                .known_icc_profiles
+                .borrow()
+                .iter()
+                .filter_map(|path| icc::read_profile(path).ok())
+                .collect::<Vec<_>>();
+            if !icc::is_removable(current.as_deref(), assigned, &known) {
+                return;
             }
+            debug!("Removing {ICC_PROFILE_ATOM}");
+        }
+        if let Err(e) = self.set_icc_profile(data.as_deref()) {
+            warn!("Failed to set {ICC_PROFILE_ATOM}: {e}");
+        }
+    }
+
+    /// Gets the ICC profile of the screen announced to color managed
+    /// applications.
+    fn icc_profile(&self) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+        let atom = intern_atom(&self.conn, true, ICC_PROFILE_ATOM.as_bytes())?.reply()?.atom;
+        if atom == Atom::from(AtomEnum::NONE) {
+            return Ok(None);
+        }
+        let reply =
+            get_property(&self.conn, false, self.root, atom, AtomEnum::CARDINAL, 0, u32::MAX)?
+                .reply()?;
+        Ok((reply.type_ != Atom::from(AtomEnum::NONE)).then_some(reply.value))
+    }
 
-            let mut transform = Transform::default();
-            transform.matrix11 = Fixed::from(output.scale_x()).0;
-            transform.matrix22 = Fixed::from(output.scale_y()).0;
-            transform.matrix33 = Fixed::from(1.).0;
+    /// Sets or removes the ICC profile of the screen.
+    fn set_icc_profile(&self, data: Option<&[u8]>) -> Result<(), Box<dyn Error>> {
+        let atom = intern_atom(&self.conn, false, ICC_PROFILE_ATOM.as_bytes())?.reply()?.atom;
+        match data {
+            Some(data) => change_property(
+                &self.conn,
+                PropMode::REPLACE,
+                self.root,
+                atom,
+                AtomEnum::CARDINAL,
+                8,
+                u32::try_from(data.len())?,
+                data,
+            )?
+            .check()?,
+            None => delete_property(&self.conn, self.root, atom)?.check()?,
+        }
+        Ok(())
+    }
 
-            if handle_no_reply_error(
-                set_crtc_transform(&self.conn, crtc_id, transform, "bilinear".as_bytes(), &[]),
-                "set CRTC transform",
-            ) {
-                return false;
-            }
+    /// Tells applications using Xft the DPI and subpixel order of the primary
+    /// output by updating the resources of the screen. A given `dpi`
+    /// overrides the DPI of the primary output.
+    pub fn update_xft(&self, outputs: &Outputs, dpi: Option<u16>) {
+        let primary = outputs.iter::<Output>().map(Result::unwrap).find(Output::primary);
+        let dpi = dpi.map_or_else(
+            || {
+                let [x, y] = primary.as_ref().map_or(PPI_DEFAULT, Output::ppi);
+                x.midpoint(y).round().to_string()
+            },
+            |dpi| dpi.to_string(),
+        );
+        let rgba = primary.as_ref().and_then(|p| {
+            let subpixel = self.outputs.borrow().get(&p.id())?.subpixel_order;
+            resources::xft_rgba(subpixel, p.rotation(), p.reflection())
+        });
+        debug!("Setting {XFT_DPI} to {dpi} and {XFT_RGBA} to {rgba:?}");
+        // An unknown subpixel order keeps the one set by the user
+        let mut values = vec![(XFT_DPI, Some(dpi.as_str()))];
+        values.extend(rgba.map(|rgba| (XFT_RGBA, Some(rgba))));
+        if let Err(e) = self.set_xft(&values) {
+            warn!("Failed to update resources: {e}");
+        }
+    }
 
-            if handle_reply_error(
-                self.update_crtc(
-                    crtc_id,
-                    output.x(),
-                    output.y(),
-                    output.mode().map_or(0, |m| m.id()),
-                    output.randr_rotation(),
-                    &[output.id()],
-                ),
-                "update CRTC",
-            ) {
-                return false;
-            }
+    /// Gets the resource database of the screen as stored in the
+    /// `RESOURCE_MANAGER` property of the root window.
+    fn resources(&self) -> Result<String, Box<dyn Error>> {
+        let reply = get_property(
+            &self.conn,
+            false,
+            self.root,
+            AtomEnum::RESOURCE_MANAGER,
+            AtomEnum::STRING,
+            0,
+            u32::MAX,
+        )?
+        .reply()?;
+        Ok(String::from_utf8_lossy(&reply.value).into_owned())
+    }
+
+    /// Sets or removes the given Xft resources, leaving the resource database
+    /// untouched if they already have these values.
+    fn set_xft(&self, values: &[(&str, Option<&str>)]) -> Result<(), Box<dyn Error>> {
+        let current = self.resources()?;
+        let updated = values
+            .iter()
+            .fold(current.clone(), |r, (name, value)| resources::with_value(&r, name, *value));
+        if updated == current {
+            return Ok(());
         }
+        change_property(
+            &self.conn,
+            PropMode::REPLACE,
+            self.root,
+            AtomEnum::RESOURCE_MANAGER,
+            AtomEnum::STRING,
+            8,
+            u32::try_from(updated.len())?,
+            updated.as_bytes(),
+        )?
+        .check()?;
+        Ok(())
+    }
 
-        // Set primary output
-        let primary_id = primary.map(|p| p.id()).unwrap_or_default();
-        if handle_no_reply_error(
-            set_output_primary(&self.conn, self.root, primary_id),
-            "set primary output",
-        ) {
-            return false;
+    /// Restores mirror relations from the CRTC transforms of the current
+    /// configuration.
+    fn find_mirrors(&self, outputs: &Outputs) {
+        let enabled = outputs
+            .iter::<Output>()
+            .map(Result::unwrap)
+            .filter(Output::enabled)
+            .collect::<Vec<_>>();
+        for output in &enabled {
+            if output.transform().is_some()
+                || (nearly_eq(output.scale_x(), 1.) && nearly_eq(output.scale_y(), 1.))
+            {
+                continue;
+            }
+            let crtc_id = self.outputs.borrow()[&output.id()].crtc;
+            let transform = self.transforms.borrow()[&crtc_id].current_transform;
+            let letterbox = transform.matrix13 != 0 || transform.matrix23 != 0;
+            let Some(source) = enabled.iter().find(|o| {
+                *o != output
+                    && o.x() == output.x()
+                    && o.y() == output.y()
+                    && (letterbox || o.rect() == output.rect())
+            }) else {
+                continue;
+            };
+            output.mirror_of(source);
+            output.set_mirror_fit(if letterbox {
+                MirrorFit::Letterbox
+            } else {
+                MirrorFit::Stretch
+            });
         }
-        true
     }
 
     #[allow(clippy::cast_sign_loss)]
@@ -483,7 +1013,7 @@ impl Randr {
             .map(Result::unwrap)
             .filter(Output::enabled)
             .collect::<Vec<_>>();
-        let bounds = Rect::bounds(enabled.iter().map(|o| o.rect()).collect());
+        let bounds = Rect::bounds(enabled.iter().map(|o| o.panning_rect()).collect());
         let width = self
             .screen_size_range
             .min_width
@@ -523,24 +1053,198 @@ impl Randr {
         ScreenSize { width, height, mwidth: mm_dim[0], mheight: mm_dim[1] }
     }
 
-    fn get_valid_empty_crtc(&self, output: &Output) -> Option<CrtcId> {
-        let outputs = self.outputs.borrow();
-        let Some(output_info) = outputs.get(&output.id()) else {
-            error!("Unknown output {}", output.id());
-            return None;
+    /// Groups enabled outputs that can be driven by a single CRTC: they share
+    /// position, mode, rotation and scale and list each other as clones.
+    fn get_clone_groups(&self, outputs: &Outputs) -> Vec<Vec<Output>> {
+        let infos = self.outputs.borrow();
+        let are_clones = |a: &Output, b: &Output| {
+            a.is_clone_of(b)
+                && a.mode().map(|m| m.id()) == b.mode().map(|m| m.id())
+                && a.randr_rotation() == b.randr_rotation()
+                && nearly_eq(a.scale_x(), b.scale_x())
+                && nearly_eq(a.scale_y(), b.scale_y())
+                && get_gamma(a).nearly_eq(&get_gamma(b))
+                && a.panning() == b.panning()
+                && infos.get(&a.id()).is_some_and(|i| i.clones.contains(&b.id()))
+                && infos.get(&b.id()).is_some_and(|i| i.clones.contains(&a.id()))
         };
-        for crtc_id in &output_info.crtcs {
-            if let Some(crtc_info) = self.crtcs.borrow().get(crtc_id) {
-                if crtc_info.outputs.is_empty()
-                    && crtc_info.possible.contains(&output.id())
-                    && crtc_info.rotations.contains(output.randr_rotation())
-                {
-                    return Some(*crtc_id);
+        let mut groups: Vec<Vec<Output>> = Vec::new();
+        for output in outputs.iter::<Output>().map(Result::unwrap).filter(Output::enabled) {
+            if let Some(group) =
+                groups.iter_mut().find(|g| g.iter().all(|o| are_clones(o, &output)))
+            {
+                group.push(output);
+            } else {
+                groups.push(vec![output]);
+            }
+        }
+        groups
+    }
+
+    /// Assigns a CRTC to each group of clones in `outputs` without changing
+    /// anything. Fails with the outputs left without a CRTC.
+    fn assign_crtcs(&self, outputs: &Outputs) -> Result<Vec<(CrtcId, Vec<Output>)>, Vec<Output>> {
+        let (assignments, unassigned) = self.match_crtcs(self.get_clone_groups(outputs));
+        if unassigned.is_empty() {
+            Ok(assignments)
+        } else {
+            Err(unassigned)
+        }
+    }
+
+    /// Gets the disabled outputs of `outputs` that can't be enabled without
+    /// leaving an enabled output without a CRTC.
+    pub fn blocked_outputs(&self, outputs: &Outputs) -> Vec<OutputId> {
+        let groups = self.get_clone_groups(outputs);
+        let count = |assignments: &[(CrtcId, Vec<Output>)]| {
+            assignments.iter().map(|(_, clones)| clones.len()).sum::<usize>()
+        };
+        let assigned = count(&self.match_crtcs(groups.clone()).0);
+        outputs
+            .iter::<Output>()
+            .map(Result::unwrap)
+            .filter(|o| !o.enabled())
+            .filter(|o| {
+                let mut groups = groups.clone();
+                groups.push(vec![o.clone()]);
+                count(&self.match_crtcs(groups).0) <= assigned
+            })
+            .map(|o| o.id())
+            .collect()
+    }
+
+    /// Matches groups of clones to CRTCs that can drive them, so that as many
+    /// outputs as possible get a CRTC and as many groups as possible keep
+    /// their current CRTC to avoid mode sets. Clones without a common CRTC are
+    /// driven separately. Returns the assigned groups and the outputs left
+    /// without a CRTC.
+    fn match_crtcs(
+        &self,
+        mut groups: Vec<Vec<Output>>,
+    ) -> (Vec<(CrtcId, Vec<Output>)>, Vec<Output>) {
+        let mut crtc_ids = self.crtcs.borrow().keys().copied().collect::<Vec<_>>();
+        crtc_ids.sort_unstable();
+        loop {
+            let costs = groups
+                .iter()
+                .map(|clones| {
+                    let current = self.outputs.borrow().get(&clones[0].id()).map_or(0, |o| o.crtc);
+                    crtc_ids
+                        .iter()
+                        .map(|&c| self.is_valid_crtc(c, clones).then_some(u32::from(c != current)))
+                        .collect()
+                })
+                .collect::<Vec<_>>();
+            let matches = matching::min_cost_matching(&costs);
+            if let Some(i) =
+                (0..groups.len()).find(|&i| matches[i].is_none() && groups[i].len() > 1)
+            {
+                // Fall back to one CRTC per output at the same position
+                debug!("No common CRTC for clones, driving them separately");
+                let clones = groups.remove(i);
+                groups.extend(clones.into_iter().map(|o| vec![o]));
+                continue;
+            }
+            let mut assignments = Vec::new();
+            let mut unassigned = Vec::new();
+            for (clones, m) in groups.into_iter().zip(matches) {
+                match m {
+                    Some(i) => assignments.push((crtc_ids[i], clones)),
+                    None => unassigned.extend(clones),
                 }
             }
+            return (assignments, unassigned);
         }
-        error!("Failed to get empty CRTC for output {}", output.id());
-        None
+    }
+
+    fn is_valid_crtc(&self, crtc_id: CrtcId, clones: &[Output]) -> bool {
+        let crtcs = self.crtcs.borrow();
+        let outputs = self.outputs.borrow();
+        let Some(crtc_info) = crtcs.get(&crtc_id) else {
+            return false;
+        };
+        clones.iter().all(|o| {
+            crtc_info.possible.contains(&o.id())
+                && crtc_info.rotations.contains(o.randr_rotation())
+                && outputs.get(&o.id()).is_some_and(|i| i.crtcs.contains(&crtc_id))
+        })
+    }
+
+    /// Writes the gamma ramps of `output` to `crtc` unless its current ramps
+    /// already match. The calibration curves of an assigned ICC profile take
+    /// precedence and are always written.
+    fn update_gamma(&self, crtc: CrtcId, output: &Output) -> Result<(), Cause> {
+        let gamma = get_gamma(output);
+        let icc_profile = output.icc_profile();
+        if icc_profile.is_none()
+            && self.gammas.borrow().get(&crtc).is_some_and(|g| Gamma::estimate(g).nearly_eq(&gamma))
+        {
+            return Ok(());
+        }
+        let size = get_crtc_gamma_size(&self.conn, crtc)?.reply()?.size;
+        if size < 2 {
+            warn!("CRTC {crtc} doesn't support gamma ramps");
+            return Ok(());
+        }
+        let calibration = icc_profile.and_then(|path| {
+            icc::read_calibration(Path::new(&path), size).unwrap_or_else(|e| {
+                warn!("Failed to read calibration of ICC profile {path}: {e}");
+                None
+            })
+        });
+        let [red, green, blue] = if let Some(ramps) = calibration {
+            debug!("Setting calibration of CRTC {crtc} from ICC profile");
+            ramps
+        } else {
+            debug!(
+                "Setting gamma of CRTC {crtc} to {:.2}:{:.2}:{:.2} with brightness {:.2}",
+                gamma.rgb[0], gamma.rgb[1], gamma.rgb[2], gamma.brightness
+            );
+            gamma.ramps(size)
+        };
+        check_no_reply(set_crtc_gamma(&self.conn, crtc, &red, &green, &blue))?;
+        self.gammas.borrow_mut().insert(crtc, [red, green, blue]);
+        Ok(())
+    }
+
+    /// Sets the panning of `crtc` to the one of `output` unless it already
+    /// matches.
+    fn update_panning(&self, crtc: CrtcId, output: &Output) -> Result<SetConfig, ReplyError> {
+        let panning = get_panning_info(output);
+        if self.pannings.borrow().get(&crtc).is_some_and(|p| same_panning(p, &panning)) {
+            return Ok(SetConfig::SUCCESS);
+        }
+        debug!(
+            "Setting panning of CRTC {crtc} to {}x{}+{}+{}",
+            panning.width, panning.height, panning.left, panning.top
+        );
+        let status = self.set_panning(crtc, &panning)?;
+        if status == SetConfig::SUCCESS {
+            self.pannings.borrow_mut().insert(crtc, panning);
+        }
+        Ok(status)
+    }
+
+    fn set_panning(&self, crtc: CrtcId, panning: &PanningInfo) -> Result<SetConfig, ReplyError> {
+        Ok(set_panning(
+            &self.conn,
+            crtc,
+            CURRENT_TIME,
+            panning.left,
+            panning.top,
+            panning.width,
+            panning.height,
+            panning.track_left,
+            panning.track_top,
+            panning.track_width,
+            panning.track_height,
+            panning.border_left,
+            panning.border_top,
+            panning.border_right,
+            panning.border_bottom,
+        )?
+        .reply()?
+        .status)
     }
 
     fn disable_crtc(&self, crtc: CrtcId) -> Result<SetConfig, ReplyError> {
@@ -556,11 +1260,6 @@ impl Randr {
         rotation: RRotation,
         outputs: &[OutputId],
     ) -> Result<SetConfig, ReplyError> {
-        if outputs.len() > 1 {
-            error!("Attaching multiple outputs to one CRTC is not supported yet");
-            return Ok(SetConfig::FAILED);
-        }
-
         if mode == 0 && !outputs.is_empty() || mode > 0 && outputs.is_empty() {
             error!("Output must be set if mode is set and vice versa");
             return Ok(SetConfig::FAILED);
@@ -588,22 +1287,27 @@ impl Randr {
             return Ok(SetConfig::FAILED);
         }
 
-        if !outputs.is_empty() && mode > 0 {
-            if let Some(output_info) = self.outputs.borrow().get(&outputs[0]) {
-                if !crtc_info.possible.contains(&outputs[0]) || !output_info.crtcs.contains(&crtc) {
-                    error!("Cannot attach output {} to CRTC {crtc}", outputs[0]);
-                    return Ok(SetConfig::FAILED);
-                }
-                if !crtc_info.rotations.contains(rotation) {
-                    error!("Rotation {rotation:#?} not valid for CRTC {crtc}");
-                    return Ok(SetConfig::FAILED);
-                }
-                if !output_info.modes.contains(&mode) {
-                    error!("Mode {mode} not valid for output {}", outputs[0]);
-                    return Ok(SetConfig::FAILED);
-                }
-            } else {
-                error!("Unknown output: {}", outputs[0]);
+        if mode > 0 && !crtc_info.rotations.contains(rotation) {
+            error!("Rotation {rotation:#?} not valid for CRTC {crtc}");
+            return Ok(SetConfig::FAILED);
+        }
+
+        for (i, output) in outputs.iter().enumerate() {
+            let output_infos = self.outputs.borrow();
+            let Some(output_info) = output_infos.get(output) else {
+                error!("Unknown output: {output}");
+                return Ok(SetConfig::FAILED);
+            };
+            if !crtc_info.possible.contains(output) || !output_info.crtcs.contains(&crtc) {
+                error!("Cannot attach output {output} to CRTC {crtc}");
+                return Ok(SetConfig::FAILED);
+            }
+            if !output_info.modes.contains(&mode) {
+                error!("Mode {mode} not valid for output {output}");
+                return Ok(SetConfig::FAILED);
+            }
+            if let Some(other) = outputs[..i].iter().find(|o| !output_info.clones.contains(o)) {
+                error!("Output {output} cannot be a clone of output {other}");
                 return Ok(SetConfig::FAILED);
             }
         }
@@ -613,7 +1317,11 @@ impl Randr {
         } else {
             debug!(
                 "Attaching output {} to CTRC {} at position +{}+{} with mode {}",
-                outputs[0], crtc, x, y, mode
+                outputs.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "),
+                crtc,
+                x,
+                y,
+                mode
             );
         }
 
@@ -679,13 +1387,14 @@ impl Randr {
             }
 
             if mode > 0 {
+                let transform = &snapshot.transforms[&crtc_id];
                 handle_no_reply_error(
                     set_crtc_transform(
                         &self.conn,
                         crtc_id,
-                        snapshot.transforms[&crtc_id],
-                        "bilinear".as_bytes(),
-                        &[],
+                        transform.current_transform,
+                        &transform.current_filter_name,
+                        &transform.current_params,
                     ),
                     "set CRTC transform",
                 );
@@ -700,10 +1409,39 @@ impl Randr {
                     ),
                     "revert CRTC",
                 );
+                if let Some(panning) = snapshot.pannings.get(&crtc_id) {
+                    if handle_reply_error(
+                        self.set_panning(crtc_id, panning),
+                        &format!("revert panning of CRTC {crtc_id}"),
+                    ) {
+                        continue;
+                    }
+                    self.pannings.borrow_mut().insert(crtc_id, *panning);
+                }
             } else {
                 error!("No mode for CRTC {crtc_id}");
             }
         }
+
+        for (crtc_id, [red, green, blue]) in snapshot.gammas {
+            if red.is_empty() {
+                continue;
+            }
+            handle_no_reply_error(
+                set_crtc_gamma(&self.conn, crtc_id, &red, &green, &blue),
+                &format!("revert gamma of CRTC {crtc_id}"),
+            );
+            self.gammas.borrow_mut().insert(crtc_id, [red, green, blue]);
+        }
+
+        if let Err(e) = self.set_icc_profile(snapshot.icc_profile.as_deref()) {
+            warn!("Failed to revert {ICC_PROFILE_ATOM}: {e}");
+        }
+
+        let [dpi, rgba] = snapshot.xft.each_ref().map(Option::as_deref);
+        if let Err(e) = self.set_xft(&[(XFT_DPI, dpi), (XFT_RGBA, rgba)]) {
+            warn!("Failed to revert resources: {e}");
+        }
     }
 }
 
@@ -725,15 +1463,35 @@ pub fn check() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-pub fn run_event_loop(sender: Sender<Event>) -> Result<JoinHandle<()>, Box<dyn Error>> {
+/// Returns the output of an output property change event.
+pub fn get_property_notify_output(event: &Event) -> Option<OutputId> {
+    match event {
+        Event::RandrNotify(NotifyEvent { sub_code: Notify::OUTPUT_PROPERTY, u: data, .. }) => {
+            Some(data.as_op().output)
+        }
+        _ => None,
+    }
+}
+
+/// Sends screen, CRTC and output change events to `sender`. Output property
+/// changes are only sent if `properties` is true.
+pub fn run_event_loop(
+    sender: Sender<Event>,
+    properties: bool,
+) -> Result<JoinHandle<()>, Box<dyn Error>> {
     let (conn, screen_num) = x11rb::connect(DISPLAY)?;
     let root = conn.setup().roots[screen_num].root;
 
-    conn.randr_select_input(
-        root,
-        NotifyMask::SCREEN_CHANGE | NotifyMask::CRTC_CHANGE | NotifyMask::OUTPUT_CHANGE,
-    )?
-    .check()?;
+    let mut mask = NotifyMask::SCREEN_CHANGE | NotifyMask::CRTC_CHANGE | NotifyMask::OUTPUT_CHANGE;
+    if properties {
+        mask |= NotifyMask::OUTPUT_PROPERTY;
+    }
+    let Version { major_version, minor_version, .. } =
+        query_version(&conn, CLIENT_VERSION[0], CLIENT_VERSION[1])?.reply()?;
+    if [major_version, minor_version] >= PROVIDERS_VERSION {
+        mask |= NotifyMask::PROVIDER_CHANGE | NotifyMask::RESOURCE_CHANGE;
+    }
+    conn.randr_select_input(root, mask)?.check()?;
 
     let handle = thread::spawn(move || {
         while let Ok(event) = conn.wait_for_event() {
@@ -744,36 +1502,57 @@ pub fn run_event_loop(sender: Sender<Event>) -> Result<JoinHandle<()>, Box<dyn E
     Ok(handle)
 }
 
-fn get_edid(conn: &RustConnection, output: OutputId) -> Result<Edid, Box<dyn Error>> {
+fn get_mode_names(res: &ScreenResources) -> HashMap<ModeId, String> {
+    let mut names = HashMap::new();
+    let mut offset = 0;
+    for mode in &res.modes {
+        let end = offset + usize::from(mode.name_len);
+        if let Some(name) = res.names.get(offset..end) {
+            names.insert(mode.id, String::from_utf8_lossy(name).into_owned());
+        }
+        offset = end;
+    }
+    names
+}
+
+fn get_edid(conn: &RustConnection, output: OutputId) -> Result<EdidData, Box<dyn Error>> {
     let name = "EDID";
     let property = intern_atom(conn, true, name.as_bytes())?.reply()?.atom;
-    if property == AtomEnum::NONE.into() {
+    if property == u32::from(AtomEnum::NONE) {
         return Err(format!("No property named: {name}").into());
     }
-    Ok(get_output_property(conn, output, property, AtomEnum::INTEGER, 0, 256, false, false)?
-        .reply()?
-        .data)
-}
-
-fn get_monitor_name(edid: &[u8]) -> Option<String> {
-    if edid.len() >= 128 {
-        let version = edid[0x12];
-        let revision = edid[0x13];
-        if version == 1 && (revision == 3 || revision == 4) {
-            let mut i = 0x48;
-            while i <= 0x6C {
-                // This 18 byte descriptor is a used as a display descriptor with a tag 0xFC
-                // (display product name).
-                if edid[i..(i + 3)] == [0, 0, 0] && edid[i + 3] == 0xFC && edid[i + 4] == 0 {
-                    return Some(
-                        String::from_utf8_lossy(&edid[(i + 5)..(i + 18)]).trim_end().to_owned(),
-                    );
-                }
-                i += 18;
-            }
+    // Offset and length are in 32 bit units, read until the extension blocks are
+    // complete
+    let mut data = Vec::new();
+    loop {
+        let offset = u32::try_from(data.len() / 4)?;
+        let length = u32::try_from(EDID_BLOCK_LEN / 4)?;
+        let reply = get_output_property(
+            conn,
+            output,
+            property,
+            AtomEnum::INTEGER,
+            offset,
+            length,
+            false,
+            false,
+        )?
+        .reply()?;
+        // A property of another type has no data but bytes after the offset
+        if reply.format != 8 && (reply.bytes_after > 0 || !reply.data.is_empty()) {
+            return Err(format!("Unexpected format of property {name}: {}", reply.format).into());
+        }
+        if reply.data.is_empty() && reply.bytes_after > 0 {
+            return Err(format!("No data read from property {name}").into());
+        }
+        data.extend(reply.data);
+        if reply.bytes_after == 0 {
+            return Ok(data);
+        }
+        if data.len() >= EDID_MAX_LEN {
+            return Err(format!("Property {name} exceeds {EDID_MAX_LEN} bytes").into());
         }
     }
-    None
 }
 
 // TODO checkout GetXIDListRequest
@@ -810,6 +1589,28 @@ fn request_transforms<'a, Conn: RequestConnection>(
     Ok(cookies)
 }
 
+fn request_gammas<'a, Conn: RequestConnection>(
+    conn: &'a Conn,
+    res: &ScreenResources,
+) -> Result<HashMap<CrtcId, Cookie<'a, Conn, GetCrtcGammaReply>>, ConnectionError> {
+    let mut cookies = HashMap::new();
+    for crtc in &res.crtcs {
+        cookies.insert(*crtc, get_crtc_gamma(conn, *crtc)?);
+    }
+    Ok(cookies)
+}
+
+fn request_pannings<'a, Conn: RequestConnection>(
+    conn: &'a Conn,
+    res: &ScreenResources,
+) -> Result<HashMap<CrtcId, Cookie<'a, Conn, PanningInfo>>, ConnectionError> {
+    let mut cookies = HashMap::new();
+    for crtc in &res.crtcs {
+        cookies.insert(*crtc, randr::get_panning(conn, *crtc)?);
+    }
+    Ok(cookies)
+}
+
 fn get_outputs(
     cookies: HashMap<OutputId, Cookie<impl RequestConnection, OutputInfo>>,
 ) -> Result<HashMap<OutputId, OutputInfo>, ReplyError> {
@@ -832,20 +1633,41 @@ fn get_crtcs(
 
 fn get_transforms(
     cookies: HashMap<CrtcId, Cookie<impl RequestConnection, GetCrtcTransformReply>>,
-) -> Result<HashMap<CrtcId, Transform>, ReplyError> {
+) -> Result<HashMap<CrtcId, TransformInfo>, ReplyError> {
     let mut crtcs = HashMap::new();
     for (crtc, c) in cookies {
-        crtcs.insert(crtc, c.reply()?.current_transform);
+        crtcs.insert(crtc, c.reply()?);
     }
     Ok(crtcs)
 }
 
+fn get_gammas(
+    cookies: HashMap<CrtcId, Cookie<impl RequestConnection, GetCrtcGammaReply>>,
+) -> Result<HashMap<CrtcId, Ramps>, ReplyError> {
+    let mut gammas = HashMap::new();
+    for (crtc, c) in cookies {
+        let GetCrtcGammaReply { red, green, blue, .. } = c.reply()?;
+        gammas.insert(crtc, [red, green, blue]);
+    }
+    Ok(gammas)
+}
+
+fn get_pannings(
+    cookies: HashMap<CrtcId, Cookie<impl RequestConnection, PanningInfo>>,
+) -> Result<HashMap<CrtcId, PanningInfo>, ReplyError> {
+    let mut pannings = HashMap::new();
+    for (crtc, c) in cookies {
+        pannings.insert(crtc, c.reply()?);
+    }
+    Ok(pannings)
+}
+
 #[cfg(debug_assertions)]
 #[allow(clippy::use_debug)]
 fn log_crtcs(
     crtcs: &HashMap<CrtcId, CrtcInfo>,
     modes: &HashMap<ModeId, ModeInfo>,
-    transforms: &HashMap<CrtcId, Transform>,
+    transforms: &HashMap<CrtcId, TransformInfo>,
 ) {
     for (crtc_id, crtc) in crtcs {
         debug!("{:-^40}", format!(" CRTC {crtc_id} "));
@@ -858,14 +1680,15 @@ fn log_crtcs(
         debug!("Outputs:   {:?}", crtc.outputs);
         debug!("Rot:       {:#?}", crtc.rotation);
         debug!("Possible:  {:?}", crtc.possible);
-        log_transform(&transforms[crtc_id]);
+        log_transform(&transforms[crtc_id].current_transform);
+        debug!("Filter:    {}", String::from_utf8_lossy(&transforms[crtc_id].current_filter_name));
     }
 }
 
 #[cfg(debug_assertions)]
 #[allow(clippy::use_debug)]
-fn log_transform(transform: &Transform) {
-    let Transform {
+fn log_transform(transform: &RTransform) {
+    let RTransform {
         matrix11: a11,
         matrix12: a12,
         matrix13: a13,
@@ -905,32 +1728,30 @@ fn log_outputs(outputs: &HashMap<OutputId, OutputInfo>, modes: &HashMap<ModeId,
     }
 }
 
-fn handle_reply_error(result: Result<SetConfig, ReplyError>, msg: &str) -> bool {
-    let mut error = true;
-    match result {
-        Ok(SetConfig::SUCCESS) => error = false,
-        Ok(SetConfig::FAILED) => error!("Failed to {msg}."),
-        Ok(status) => error!("Failed to {msg}. Cause: {status:#?}"),
-        Err(ReplyError::X11Error(e)) => error!("{}", x_error_to_string(&e)),
-        Err(e) => error!("Failed to {msg}. Cause: {e:?}"),
+/// Checks the status replied to a request changing the configuration.
+fn check_reply(result: Result<SetConfig, ReplyError>) -> Result<(), Cause> {
+    match result? {
+        SetConfig::SUCCESS => Ok(()),
+        status => Err(Cause::Status(status)),
     }
-    error
+}
+
+/// Waits for a request without reply to be processed and checks for an error.
+fn check_no_reply(
+    result: Result<VoidCookie<RustConnection>, ConnectionError>,
+) -> Result<(), Cause> {
+    Ok(result?.check()?)
+}
+
+fn handle_reply_error(result: Result<SetConfig, ReplyError>, msg: &str) -> bool {
+    check_reply(result).inspect_err(|e| error!("Failed to {msg}. Cause: {e}")).is_err()
 }
 
 fn handle_no_reply_error(
     result: Result<VoidCookie<RustConnection>, ConnectionError>,
     msg: &str,
 ) -> bool {
-    let mut error = true;
-    match result {
-        Ok(cookie) => match cookie.check() {
-            Ok(()) => error = false,
-            Err(ReplyError::X11Error(e)) => error!("{}", x_error_to_string(&e)),
-            Err(e) => error!("Failed to {msg}. Cause: {e}"),
-        },
-        Err(e) => error!("Failed to request {msg}. Cause: {e}"),
-    }
-    error
+    check_no_reply(result).inspect_err(|e| error!("Failed to {msg}. Cause: {e}")).is_err()
 }
 
 pub fn gen_xrandr_command(outputs: &Outputs) -> String {
@@ -942,14 +1763,18 @@ pub fn gen_xrandr_command(outputs: &Outputs) -> String {
         if let Some(mode) = output.mode() {
             cmd += &format!(" --mode {}x{}", mode.width(), mode.height());
             cmd += &format!(" --rate {:.2}", mode.refresh());
-            cmd += &format!(" --pos {}x{}", output.x(), output.y());
+            let source = outputs.find_by_id(output.mirror());
+            cmd += &if let Some(source) = &source {
+                format!(" --same-as {}", source.name())
+            } else {
+                format!(" --pos {}x{}", output.x(), output.y())
+            };
             cmd += &format!(" --rotate {}", output.rotation().xrandr());
             cmd += &format!(" --reflect {}", output.reflection().xrandr());
-            if nearly_eq(output.scale_x(), output.scale_y()) {
-                cmd += &format!(" --scale {:.2}", output.scale_x());
-            } else {
-                cmd += &format!(" --scale {:.2}x{:.2}", output.scale_x(), output.scale_y());
-            }
+            cmd += &gen_xrandr_scale(&output, source.as_ref());
+            cmd += &gen_xrandr_filter(&output);
+            cmd += &gen_xrandr_gamma(&output);
+            cmd += &gen_xrandr_panning(&output);
             if output.primary() {
                 cmd += " --primary";
                 cmd += &format!(" --dpi {}", &output.name());
@@ -962,6 +1787,171 @@ pub fn gen_xrandr_command(outputs: &Outputs) -> String {
     cmd
 }
 
+fn gen_xrandr_scale(output: &Output, source: Option<&Output>) -> String {
+    if let Some(source) = source.filter(|_| output.mirror_fit() == MirrorFit::Stretch) {
+        format!(" --scale-from {}x{}", source.width(), source.height())
+    } else if output.mirror() > 0 || output.transform().is_some() {
+        let m = from_render_transform(&get_transform(output))
+            .0
+            .map(|row| row.map(|v| format!("{v:.4}")));
+        format!(" --transform {}", m.as_flattened().join(","))
+    } else if nearly_eq(output.scale_x(), output.scale_y()) {
+        format!(" --scale {:.2}", output.scale_x())
+    } else {
+        format!(" --scale {:.2}x{:.2}", output.scale_x(), output.scale_y())
+    }
+}
+
+fn gen_xrandr_filter(output: &Output) -> String {
+    let filter = output.filter();
+    if filter.is_empty() || filter == FILTER_DEFAULT {
+        String::new()
+    } else {
+        format!(" --filter {filter}")
+    }
+}
+
+fn gen_xrandr_gamma(output: &Output) -> String {
+    let Gamma { rgb: [r, g, b], brightness } = get_gamma(output);
+    let gamma = if [r, g, b].iter().all(|&v| nearly_eq_abs(v, 1., gamma::TOLERANCE)) {
+        String::new()
+    } else {
+        format!(" --gamma {r:.2}:{g:.2}:{b:.2}")
+    };
+    if nearly_eq_abs(brightness, 1., gamma::TOLERANCE) {
+        gamma
+    } else {
+        format!("{gamma} --brightness {brightness:.2}")
+    }
+}
+
+fn gen_xrandr_panning(output: &Output) -> String {
+    let Some(panning) = output.panning() else {
+        return String::new();
+    };
+    let info = get_panning_info(output);
+    let mut arg = format!(" --panning {}x{}+{}+{}", info.width, info.height, info.left, info.top);
+    if panning.has_tracking_area() || panning.border != [0; 4] {
+        let [l, t, r, b] = panning.border;
+        arg = format!(
+            "{arg}/{}x{}+{}+{}/{l}/{t}/{r}/{b}",
+            info.track_width, info.track_height, info.track_left, info.track_top
+        );
+    }
+    arg
+}
+
+fn get_gamma(output: &Output) -> Gamma {
+    Gamma { rgb: output.gamma(), brightness: output.brightness() }
+}
+
+/// Gets the panning of `output` in screen coordinates. Outputs that don't pan
+/// give an empty panning area.
+fn get_panning_info(output: &Output) -> PanningInfo {
+    let Some(p) = output.panning() else {
+        return PanningInfo::default();
+    };
+    let area = output.panning_rect();
+    let absolute =
+        |origin: i16, v: i16| u16::try_from(i32::from(origin) + i32::from(v)).unwrap_or(0);
+    let track = p.has_tracking_area();
+    PanningInfo {
+        left: u16::try_from(area.x()).unwrap_or(0),
+        top: u16::try_from(area.y()).unwrap_or(0),
+        width: area.width(),
+        height: area.height(),
+        track_left: if track { absolute(area.x(), p.track_x) } else { 0 },
+        track_top: if track { absolute(area.y(), p.track_y) } else { 0 },
+        track_width: if track { p.track_width } else { 0 },
+        track_height: if track { p.track_height } else { 0 },
+        border_left: p.border[0],
+        border_top: p.border[1],
+        border_right: p.border[2],
+        border_bottom: p.border[3],
+        ..PanningInfo::default()
+    }
+}
+
+/// Gets the panning of a CRTC relative to its panning area or `None` if it
+/// doesn't pan.
+fn get_panning(info: &PanningInfo) -> Option<Panning> {
+    if info.width == 0 || info.height == 0 {
+        return None;
+    }
+    let relative = |v: u16, origin: u16| i16::try_from(i32::from(v) - i32::from(origin)).ok();
+    let track = info.track_width > 0 && info.track_height > 0;
+    Some(Panning {
+        width: info.width,
+        height: info.height,
+        track_x: relative(info.track_left, info.left).filter(|_| track).unwrap_or(0),
+        track_y: relative(info.track_top, info.top).filter(|_| track).unwrap_or(0),
+        track_width: if track { info.track_width } else { 0 },
+        track_height: if track { info.track_height } else { 0 },
+        border: [info.border_left, info.border_top, info.border_right, info.border_bottom],
+    })
+}
+
+fn same_panning(a: &PanningInfo, b: &PanningInfo) -> bool {
+    let values = |p: &PanningInfo| {
+        (
+            [p.left, p.top, p.width, p.height],
+            [p.track_left, p.track_top, p.track_width, p.track_height],
+            [p.border_left, p.border_top, p.border_right, p.border_bottom],
+        )
+    };
+    values(a) == values(b)
+}
+
+/// Gets the CRTC transform of `output`, its full transform if it has one or
+/// else its scale. Letterboxed mirrors are scaled uniformly and centered on
+/// their source.
+fn get_transform(output: &Output) -> RTransform {
+    if let Some(transform) = output.transform() {
+        return to_render_transform(&transform);
+    }
+    let [sx, sy] = [output.scale_x(), output.scale_y()];
+    let mut transform = RTransform::default();
+    if output.mirror() > 0 && output.mirror_fit() == MirrorFit::Letterbox {
+        let s = sx.max(sy);
+        transform.matrix11 = Fixed::from(s).0;
+        transform.matrix22 = Fixed::from(s).0;
+        transform.matrix13 = Fixed::from(f64::from(output.width()) * (1. - s / sx) / 2.).0;
+        transform.matrix23 = Fixed::from(f64::from(output.height()) * (1. - s / sy) / 2.).0;
+    } else {
+        transform.matrix11 = Fixed::from(sx).0;
+        transform.matrix22 = Fixed::from(sy).0;
+    }
+    transform.matrix33 = Fixed::from(1.).0;
+    transform
+}
+
+fn from_render_transform(t: &RTransform) -> Transform {
+    Transform(
+        [
+            [t.matrix11, t.matrix12, t.matrix13],
+            [t.matrix21, t.matrix22, t.matrix23],
+            [t.matrix31, t.matrix32, t.matrix33],
+        ]
+        .map(|row| row.map(|v| f64::from(Fixed(v)))),
+    )
+}
+
+fn to_render_transform(t: &Transform) -> RTransform {
+    let [[matrix11, matrix12, matrix13], [matrix21, matrix22, matrix23], [matrix31, matrix32, matrix33]] =
+        t.0.map(|row| row.map(|v| Fixed::from(v).0));
+    RTransform {
+        matrix11,
+        matrix12,
+        matrix13,
+        matrix21,
+        matrix22,
+        matrix23,
+        matrix31,
+        matrix32,
+        matrix33,
+    }
+}
+
 struct Fixed(i32);
 
 impl From<Fixed> for f64 {
@@ -0,0 +1,156 @@
+use x11rb::protocol::render::SubPixel;
+
+use crate::data::enums::{Reflection, Rotation};
+
+/// Resource holding the DPI used by Xft to scale fonts.
+pub const XFT_DPI: &str = "Xft.dpi";
+/// Resource holding the subpixel order used by Xft for antialiasing.
+pub const XFT_RGBA: &str = "Xft.rgba";
+
+/// Gets the value of the resource `name` from the resource database
+/// `resources` as stored in the `RESOURCE_MANAGER` property.
+pub fn value<'a>(resources: &'a str, name: &str) -> Option<&'a str> {
+    resources.lines().rev().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == name).then_some(value.trim())
+    })
+}
+
+/// Sets the resource `name` to `value` in the resource database
+/// `resources`, removing it if `value` is `None`. An existing resource keeps
+/// its position.
+pub fn with_value(resources: &str, name: &str, value: Option<&str>) -> String {
+    let line = value.map(|value| format!("{name}:\t{value}"));
+    let mut lines = Vec::new();
+    let mut replaced = false;
+    for l in resources.lines() {
+        if l.split_once(':').is_some_and(|(key, _)| key.trim() == name) {
+            if !replaced {
+                lines.extend(line.as_deref());
+                replaced = true;
+            }
+        } else {
+            lines.push(l);
+        }
+    }
+    if !replaced {
+        lines.extend(line.as_deref());
+    }
+    let mut resources = lines.join("\n");
+    if !lines.is_empty() {
+        resources.push('\n');
+    }
+    resources
+}
+
+/// Xft subpixel order of a monitor with the subpixel order `subpixel` as
+/// seen on the screen after `rotation` and `reflection` are applied.
+pub fn xft_rgba(
+    subpixel: SubPixel,
+    rotation: Rotation,
+    reflection: Reflection,
+) -> Option<&'static str> {
+    // Horizontal and vertical orders in the sequence they take when turning
+    // the content counterclockwise.
+    const ORDERS: [SubPixel; 4] = [
+        SubPixel::HORIZONTAL_RGB,
+        SubPixel::VERTICAL_RGB,
+        SubPixel::HORIZONTAL_BGR,
+        SubPixel::VERTICAL_BGR,
+    ];
+    if subpixel == SubPixel::NONE {
+        return Some("none");
+    }
+    let i = ORDERS.iter().position(|&o| o == subpixel)?;
+    let turns = match rotation {
+        Rotation::Normal => 0,
+        Rotation::Left => 1,
+        Rotation::Inverted => 2,
+        Rotation::Right => 3,
+    };
+    let mut subpixel = ORDERS[(i + turns) % ORDERS.len()];
+    let horizontal = subpixel == SubPixel::HORIZONTAL_RGB || subpixel == SubPixel::HORIZONTAL_BGR;
+    let flipped = match reflection {
+        Reflection::Normal => false,
+        Reflection::Horizontal => horizontal,
+        Reflection::Vertical => !horizontal,
+        Reflection::Both => true,
+    };
+    if flipped {
+        subpixel = ORDERS[(i + turns + 2) % ORDERS.len()];
+    }
+    Some(match subpixel {
+        SubPixel::HORIZONTAL_RGB => "rgb",
+        SubPixel::HORIZONTAL_BGR => "bgr",
+        SubPixel::VERTICAL_RGB => "vrgb",
+        _ => "vbgr",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESOURCES: &str = "Xft.antialias:\t1\nXft.dpi:\t96\n*customization:\t-color\n";
+
+    #[test]
+    fn get_value() {
+        assert_eq!(value(RESOURCES, XFT_DPI), Some("96"));
+        assert_eq!(value(RESOURCES, XFT_RGBA), None);
+        // The last definition wins
+        assert_eq!(value("Xft.dpi: 96\nXft.dpi: 144\n", XFT_DPI), Some("144"));
+    }
+
+    #[test]
+    fn set_value() {
+        assert_eq!(
+            with_value(RESOURCES, XFT_DPI, Some("144")),
+            "Xft.antialias:\t1\nXft.dpi:\t144\n*customization:\t-color\n"
+        );
+        assert_eq!(
+            with_value(RESOURCES, XFT_RGBA, Some("rgb")),
+            format!("{RESOURCES}Xft.rgba:\trgb\n")
+        );
+        assert_eq!(
+            with_value(RESOURCES, XFT_DPI, None),
+            "Xft.antialias:\t1\n*customization:\t-color\n"
+        );
+        assert_eq!(with_value("Xft.dpi: 96\nXft.dpi: 144\n", XFT_DPI, None), "");
+        assert_eq!(with_value("", XFT_RGBA, None), "");
+    }
+
+    #[test]
+    fn rotate_subpixel_order() {
+        let rgba = |subpixel, rotation| xft_rgba(subpixel, rotation, Reflection::Normal);
+        for (rotation, orders) in [
+            (Rotation::Normal, ["rgb", "bgr", "vrgb", "vbgr"]),
+            (Rotation::Left, ["vrgb", "vbgr", "bgr", "rgb"]),
+            (Rotation::Inverted, ["bgr", "rgb", "vbgr", "vrgb"]),
+            (Rotation::Right, ["vbgr", "vrgb", "rgb", "bgr"]),
+        ] {
+            let rotated = [
+                SubPixel::HORIZONTAL_RGB,
+                SubPixel::HORIZONTAL_BGR,
+                SubPixel::VERTICAL_RGB,
+                SubPixel::VERTICAL_BGR,
+            ]
+            .map(|subpixel| rgba(subpixel, rotation));
+            assert_eq!(rotated, orders.map(Some), "{rotation:?}");
+        }
+    }
+
+    #[test]
+    fn reflect_subpixel_order() {
+        let rgb = SubPixel::HORIZONTAL_RGB;
+        assert_eq!(xft_rgba(rgb, Rotation::Normal, Reflection::Horizontal), Some("bgr"));
+        assert_eq!(xft_rgba(rgb, Rotation::Normal, Reflection::Vertical), Some("rgb"));
+        assert_eq!(xft_rgba(rgb, Rotation::Left, Reflection::Vertical), Some("vbgr"));
+        assert_eq!(xft_rgba(rgb, Rotation::Left, Reflection::Both), Some("vbgr"));
+    }
+
+    #[test]
+    fn unknown_subpixel_order() {
+        assert_eq!(xft_rgba(SubPixel::NONE, Rotation::Left, Reflection::Both), Some("none"));
+        assert_eq!(xft_rgba(SubPixel::UNKNOWN, Rotation::Normal, Reflection::Normal), None);
+    }
+}
@@ -0,0 +1,407 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use gtk::prelude::ListModelExtManual;
+use log::debug;
+
+use crate::data::enums::{MirrorFit, Reflection, Rotation};
+use crate::data::output::Output;
+use crate::data::outputs::Outputs;
+use crate::data::panning::Panning;
+use crate::data::transform::Transform;
+
+/// Output options in the dialect of xrandr as generated by
+/// [`gen_xrandr_command`](crate::x11::randr::gen_xrandr_command).
+#[derive(Default)]
+pub struct Args {
+    outputs: Vec<OutputArgs>,
+    noprimary: bool,
+}
+
+#[derive(Default)]
+struct OutputArgs {
+    name: String,
+    auto: bool,
+    off: bool,
+    mode: Option<[u16; 2]>,
+    rate: Option<f64>,
+    pos: Option<[i16; 2]>,
+    rotation: Option<Rotation>,
+    reflection: Option<Reflection>,
+    scale: Option<[f64; 2]>,
+    scale_from: Option<[u16; 2]>,
+    transform: Option<Transform>,
+    filter: Option<String>,
+    gamma: Option<[f64; 3]>,
+    brightness: Option<f64>,
+    panning: Option<PanningArgs>,
+    primary: bool,
+    relation: Option<(Relation, String)>,
+}
+
+/// `--panning WxH[+X+Y[/TWxTH+TX+TY[/L/T/R/B]]]` with an absolute tracking
+/// area.
+struct PanningArgs {
+    size: [u16; 2],
+    pos: Option<[i16; 2]>,
+    tracking: Option<([u16; 2], [i16; 2])>,
+    border: [i16; 4],
+}
+
+#[derive(Clone, Copy)]
+enum Relation {
+    LeftOf,
+    RightOf,
+    Above,
+    Below,
+    SameAs,
+}
+
+impl Args {
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("Missing value for {arg}"));
+            if arg == "--output" {
+                parsed.outputs.push(OutputArgs { name: value()?, ..Default::default() });
+                continue;
+            }
+            match arg.as_str() {
+                "--noprimary" => {
+                    parsed.noprimary = true;
+                    continue;
+                }
+                "--dpi" => {
+                    debug!("Ignoring --dpi {}", value()?);
+                    continue;
+                }
+                _ => (),
+            }
+            let Some(output) = parsed.outputs.last_mut() else {
+                return Err(format!("{arg} requires a preceding --output"));
+            };
+            match arg.as_str() {
+                "--auto" => output.auto = true,
+                "--off" => output.off = true,
+                "--primary" => output.primary = true,
+                "--mode" => output.mode = Some(parse_pair(&value()?)?),
+                "--rate" | "--refresh" => output.rate = Some(parse_factor(&arg, &value()?, true)?),
+                "--pos" => output.pos = Some(parse_pair(&value()?)?),
+                "--rotate" | "--orientation" => output.rotation = Some(value()?.parse()?),
+                "--reflect" => output.reflection = Some(value()?.parse()?),
+                "--scale" => {
+                    let v = value()?;
+                    output.scale = Some(match v.split_once('x') {
+                        Some((sx, sy)) => {
+                            [parse_factor(&arg, sx, true)?, parse_factor(&arg, sy, true)?]
+                        }
+                        None => [parse_factor(&arg, &v, true)?; 2],
+                    });
+                }
+                "--scale-from" => output.scale_from = Some(parse_pair(&value()?)?),
+                "--transform" => output.transform = Some(parse_transform(&value()?)?),
+                "--filter" => output.filter = Some(value()?),
+                "--gamma" => output.gamma = Some(parse_gamma(&value()?)?),
+                "--brightness" => output.brightness = Some(parse_factor(&arg, &value()?, false)?),
+                "--panning" => output.panning = Some(parse_panning(&value()?)?),
+                "--left-of" => output.relation = Some((Relation::LeftOf, value()?)),
+                "--right-of" => output.relation = Some((Relation::RightOf, value()?)),
+                "--above" => output.relation = Some((Relation::Above, value()?)),
+                "--below" => output.relation = Some((Relation::Below, value()?)),
+                "--same-as" => output.relation = Some((Relation::SameAs, value()?)),
+                _ => return Err(format!("Unknown option: {arg}")),
+            }
+        }
+        if parsed.outputs.is_empty() && !parsed.noprimary {
+            return Err("No output given".to_owned());
+        }
+        Ok(parsed)
+    }
+
+    /// Changes `outputs` according to these options. Outputs that aren't
+    /// mentioned stay as they are.
+    #[allow(clippy::too_many_lines)]
+    pub fn apply_to(&self, outputs: &Outputs) -> Result<(), Box<dyn Error>> {
+        let find = |name: &str| {
+            outputs
+                .iter::<Output>()
+                .map(Result::unwrap)
+                .find(|o| o.name() == name)
+                .ok_or(format!("Output {name} not found or not connected"))
+        };
+
+        if self.noprimary || self.outputs.iter().any(|o| o.primary && !o.off) {
+            outputs.iter::<Output>().map(Result::unwrap).for_each(|o| o.set_primary(false));
+        }
+
+        for args in &self.outputs {
+            let output = find(&args.name)?;
+            if args.off {
+                output.disable();
+                continue;
+            }
+            let mode = match (args.mode, output.mode()) {
+                (Some([w, h]), _) => Some([w, h]),
+                (None, Some(m)) if !args.auto => Some([m.width(), m.height()]),
+                _ => None,
+            };
+            let mode = match mode {
+                Some([w, h]) => output
+                    .modes()
+                    .find_by_res(w, h, args.rate)
+                    .ok_or(format!("No mode {w}x{h} for output {}", args.name))?,
+                None => {
+                    output.modes().first().ok_or(format!("No mode for output {}", args.name))?
+                }
+            };
+            if !output.enabled() {
+                output.set_enabled(true);
+                output.set_x(0);
+                output.set_y(0);
+            }
+            output.set_mode(Some(&mode));
+            if let Some(rotation) = args.rotation {
+                output.set_rotation(rotation);
+            }
+            if let Some(reflection) = args.reflection {
+                output.set_reflection(reflection);
+            }
+            if let Some([sx, sy]) = args.scale {
+                output.set_scale_xy(sx, sy);
+            }
+            if let Some([w, h]) = args.scale_from {
+                let [mw, mh] = match output.rotation() {
+                    Rotation::Normal | Rotation::Inverted => [mode.width(), mode.height()],
+                    Rotation::Left | Rotation::Right => [mode.height(), mode.width()],
+                };
+                output.set_scale_xy(f64::from(w) / f64::from(mw), f64::from(h) / f64::from(mh));
+            }
+            if let Some(transform) = args.transform {
+                let [[_, _, tx], [_, _, ty], _] = transform.0;
+                let mirror = matches!(args.relation, Some((Relation::SameAs, _)));
+                if transform.is_scale() && (mirror || (tx == 0. && ty == 0.)) {
+                    output.set_scale_xy(transform.0[0][0], transform.0[1][1]);
+                    output.set_transform(None::<Transform>);
+                } else {
+                    output.set_transform(Some(transform));
+                }
+            }
+            if let Some(filter) = &args.filter {
+                output.set_filter(filter.as_str());
+            }
+            if let Some(gamma) = args.gamma {
+                output.set_gamma(gamma);
+            }
+            if let Some(brightness) = args.brightness {
+                output.set_brightness(brightness);
+            }
+            if let Some([x, y]) = args.pos.or(args.panning.as_ref().and_then(|p| p.pos)) {
+                output.set_x(x);
+                output.set_y(y);
+            }
+            if let Some(panning) = &args.panning {
+                output.set_panning(panning.relative_to(output.x(), output.y()));
+            }
+            if args.primary {
+                output.set_primary(true);
+            }
+        }
+
+        // Relative positions depend on the final dimensions of all outputs and
+        // are resolved after the position of the output they refer to
+        let mut pending = self
+            .outputs
+            .iter()
+            .filter_map(|args| {
+                args.relation.as_ref().map(|(relation, other)| (args, relation, other))
+            })
+            .collect::<Vec<_>>();
+        while !pending.is_empty() {
+            let Some(i) = pending
+                .iter()
+                .position(|(_, _, other)| !pending.iter().any(|(args, ..)| &args.name == *other))
+            else {
+                let names = pending.iter().map(|(args, ..)| args.name.as_str()).collect::<Vec<_>>();
+                return Err(format!("Cyclic relative positions of {}", names.join(", ")).into());
+            };
+            let (args, relation, other) = pending.remove(i);
+            let output = find(&args.name)?;
+            let other = find(other)?;
+            if !output.enabled() || !other.enabled() {
+                continue;
+            }
+            let (x, y) = match relation {
+                Relation::LeftOf => {
+                    (i32::from(other.x()) - i32::from(output.width()), other.y().into())
+                }
+                Relation::RightOf => {
+                    (i32::from(other.x()) + i32::from(other.width()), other.y().into())
+                }
+                Relation::Above => {
+                    (other.x().into(), i32::from(other.y()) - i32::from(output.height()))
+                }
+                Relation::Below => {
+                    (other.x().into(), i32::from(other.y()) + i32::from(other.height()))
+                }
+                Relation::SameAs => (other.x().into(), other.y().into()),
+            };
+            output.set_x(i16::try_from(x)?);
+            output.set_y(i16::try_from(y)?);
+            if matches!(relation, Relation::SameAs) {
+                if args.scale_from.is_some() {
+                    output.set_mirror(other.id());
+                    output.set_mirror_fit(MirrorFit::Stretch);
+                } else if args.transform.is_some_and(|t| t.is_scale()) {
+                    // Letterboxed mirrors are scaled uniformly and translated
+                    output.mirror_of(&other);
+                    output.set_mirror_fit(MirrorFit::Letterbox);
+                }
+            }
+        }
+
+        normalize(outputs)
+    }
+}
+
+/// Moves enabled outputs with negative coordinates back into the screen.
+fn normalize(outputs: &Outputs) -> Result<(), Box<dyn Error>> {
+    let enabled =
+        outputs.iter::<Output>().map(Result::unwrap).filter(Output::enabled).collect::<Vec<_>>();
+    let (dx, dy) = enabled.iter().fold((0, 0), |(x, y), o| (o.x().min(x), o.y().min(y)));
+    if dx == 0 && dy == 0 {
+        return Ok(());
+    }
+    for output in enabled {
+        output.set_x(i16::try_from(i32::from(output.x()) - i32::from(dx))?);
+        output.set_y(i16::try_from(i32::from(output.y()) - i32::from(dy))?);
+    }
+    Ok(())
+}
+
+impl PanningArgs {
+    /// Panning with the tracking area relative to the panning area at `x`,
+    /// `y`. A size of zero disables panning.
+    fn relative_to(&self, x: i16, y: i16) -> Option<Panning> {
+        let [width, height] = self.size;
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let ([track_width, track_height], [track_x, track_y]) =
+            self.tracking.map_or(([0; 2], [0; 2]), |(size, [tx, ty])| {
+                (size, [tx.saturating_sub(x), ty.saturating_sub(y)])
+            });
+        Some(Panning {
+            width,
+            height,
+            track_x,
+            track_y,
+            track_width,
+            track_height,
+            border: self.border,
+        })
+    }
+}
+
+fn parse<T: FromStr>(s: &str) -> Result<T, String> {
+    s.parse().map_err(|_| format!("Invalid value: {s}"))
+}
+
+/// Parses a finite number given for `arg` that is positive or, unless
+/// `positive`, zero.
+fn parse_factor(arg: &str, s: &str, positive: bool) -> Result<f64, String> {
+    let v: f64 = parse(s)?;
+    if v.is_finite() && if positive { v > 0. } else { v >= 0. } {
+        Ok(v)
+    } else {
+        Err(format!("Invalid value for {arg}: {s}"))
+    }
+}
+
+fn parse_pair<T: FromStr>(s: &str) -> Result<[T; 2], String> {
+    let (a, b) = s.split_once('x').ok_or(format!("Invalid value: {s}"))?;
+    Ok([parse(a)?, parse(b)?])
+}
+
+/// Parses `WxH[+X+Y]`.
+fn parse_geometry<T: FromStr>(s: &str) -> Result<([T; 2], Option<[i16; 2]>), String> {
+    let Some((size, pos)) = s.split_once('+') else {
+        return Ok((parse_pair(s)?, None));
+    };
+    let (x, y) = pos.split_once('+').ok_or(format!("Invalid value: {s}"))?;
+    Ok((parse_pair(size)?, Some([parse(x)?, parse(y)?])))
+}
+
+fn parse_panning(s: &str) -> Result<PanningArgs, String> {
+    let mut parts = s.split('/');
+    let (size, pos) = parse_geometry(parts.next().unwrap_or_default())?;
+    let tracking = match parts.next() {
+        Some(tracking) => {
+            let (size, pos) = parse_geometry(tracking)?;
+            Some((size, pos.unwrap_or_default()))
+        }
+        None => None,
+    };
+    let border = parts.map(parse).collect::<Result<Vec<i16>, _>>()?;
+    let border = if border.is_empty() {
+        [0; 4]
+    } else {
+        border.try_into().map_err(|_| format!("Invalid panning: {s}"))?
+    };
+    Ok(PanningArgs { size, pos, tracking, border })
+}
+
+/// Parses the row-major matrix `a,b,c,d,e,f,g,h,i` or `none`.
+fn parse_transform(s: &str) -> Result<Transform, String> {
+    if s == "none" {
+        return Ok(Transform::default());
+    }
+    let values = s.split(',').map(parse).collect::<Result<Vec<f64>, _>>()?;
+    let values: [f64; 9] = values.try_into().map_err(|_| format!("Invalid transform: {s}"))?;
+    let mut transform = Transform::default();
+    transform.0.as_flattened_mut().copy_from_slice(&values);
+    Ok(transform)
+}
+
+fn parse_gamma(s: &str) -> Result<[f64; 3], String> {
+    let gamma = s.split(':').map(parse).collect::<Result<Vec<f64>, _>>()?;
+    match gamma[..] {
+        [r, g, b] if [r, g, b].iter().all(|&v| v.is_finite() && v > 0.) => Ok([r, g, b]),
+        _ => Err(format!("Invalid gamma: {s}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &str) -> Result<Args, String> {
+        Args::parse(args.split_whitespace().map(str::to_owned))
+    }
+
+    #[test]
+    fn parse_factors() {
+        let args = parse_args("--output HDMI-1 --scale 1.5x2 --rate 59.94 --brightness 0").unwrap();
+        let output = &args.outputs[0];
+        assert_eq!(output.scale, Some([1.5, 2.]));
+        assert_eq!(output.rate, Some(59.94));
+        assert_eq!(output.brightness, Some(0.));
+    }
+
+    #[test]
+    fn reject_invalid_factors() {
+        for args in [
+            "--scale 0",
+            "--scale -1",
+            "--scale 1x0",
+            "--scale NaN",
+            "--scale inf",
+            "--rate 0",
+            "--rate -60",
+            "--brightness -0.5",
+            "--brightness inf",
+            "--gamma 1:inf:1",
+        ] {
+            assert!(parse_args(&format!("--output HDMI-1 {args}")).is_err(), "{args}");
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use crate::data::enums::Fallback;
+use crate::MarkdownTable;
+
+#[derive(Clone, Deserialize, Serialize, MarkdownTable)]
+#[serde(default)]
+/// Background daemon configuration (`rrandr --daemon`)
+pub struct Daemon {
+    /// Time in milliseconds to wait for further screen changes before reacting
+    /// to a change of connected monitors
+    pub debounce: u16,
+    /// Layout to apply when no layout has been remembered for the connected
+    /// monitors. One of `none`, `extend_right`, `extend_left`, `extend_below`
+    /// or `extend_above` (relative to the primary output).
+    pub fallback: Fallback,
+}
+
+impl Default for Daemon {
+    fn default() -> Self { Self { debounce: 500, fallback: Fallback::ExtendRight } }
+}
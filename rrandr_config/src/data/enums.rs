@@ -59,3 +59,29 @@ impl fmt::Display for BorderStyle {
         write!(f, "{}", format!("{:?}", self).to_lowercase())
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fallback {
+    None,
+    ExtendRight,
+    ExtendLeft,
+    ExtendBelow,
+    ExtendAbove,
+}
+
+impl fmt::Display for Fallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::None => "none",
+                Self::ExtendRight => "extend_right",
+                Self::ExtendLeft => "extend_left",
+                Self::ExtendBelow => "extend_below",
+                Self::ExtendAbove => "extend_above",
+            }
+        )
+    }
+}
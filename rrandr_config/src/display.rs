@@ -94,6 +94,8 @@ pub struct LightColors {
     pub screen: Color,
     /// Output selection color
     pub selection: Color,
+    /// Color of outputs that can't be applied
+    pub invalid: Color,
 }
 
 impl Default for LightColors {
@@ -104,6 +106,7 @@ impl Default for LightColors {
             border: Color::from_str("#d8d4d0").unwrap_or_default(),
             screen: Color::from_str("#cdc7c2").unwrap_or_default(),
             selection: Color::from_str("#3584e4").unwrap_or_default(),
+            invalid: Color::from_str("#e01b24").unwrap_or_default(),
         }
     }
 }
@@ -122,6 +125,8 @@ pub struct DarkColors {
     pub screen: Color,
     /// Output selection color
     pub selection: Color,
+    /// Color of outputs that can't be applied
+    pub invalid: Color,
 }
 
 impl Default for DarkColors {
@@ -132,6 +137,7 @@ impl Default for DarkColors {
             border: Color::from_str("#282828").unwrap_or_default(),
             screen: Color::from_str("#1b1b1b").unwrap_or_default(),
             selection: Color::from_str("#1b68c6").unwrap_or_default(),
+            invalid: Color::from_str("#c01c28").unwrap_or_default(),
         }
     }
 }
@@ -1,10 +1,13 @@
+pub mod daemon;
 pub mod data;
 pub mod display;
 pub mod popup;
+pub mod xft;
 
 use std::fs;
 use std::path::PathBuf;
 
+use daemon::Daemon;
 use display::Display;
 use glib::{home_dir, user_config_dir};
 use gtk::{glib, Settings};
@@ -12,6 +15,7 @@ use log::{info, warn};
 use popup::Popup;
 use rrandr_config_derive::MarkdownTable;
 use serde::{Deserialize, Serialize};
+use xft::Xft;
 
 use crate::data::color::Color;
 
@@ -35,6 +39,10 @@ pub struct Config {
     pub display: Display,
     #[table]
     pub popup: Popup,
+    #[table]
+    pub daemon: Daemon,
+    #[table]
+    pub xft: Xft,
     #[serde(skip)]
     settings: Option<Settings>,
 }
@@ -48,6 +56,8 @@ impl Default for Config {
             revert_hook: Default::default(),
             display: Display::default(),
             popup: Popup::default(),
+            daemon: Daemon::default(),
+            xft: Xft::default(),
             settings: None,
         }
     }
@@ -78,6 +88,8 @@ impl Config {
 
     impl_color!(display, display_selection_color, selection);
 
+    impl_color!(display, display_invalid_color, invalid);
+
     impl_color!(popup, popup_text_color, text);
 
     impl_color!(popup, popup_background_color, background);
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use crate::MarkdownTable;
+
+#[derive(Clone, Default, Deserialize, Serialize, MarkdownTable)]
+#[serde(default)]
+/// Xft resources of applications (`Xft.dpi`, `Xft.rgba`)
+pub struct Xft {
+    /// Set `Xft.dpi` and `Xft.rgba` in the resources of the screen to the DPI
+    /// and subpixel order of the primary output after changes have been
+    /// applied and restore them when changes are reverted
+    pub update: bool,
+    /// DPI to set instead of the DPI of the primary output, `0` to use the
+    /// primary output
+    pub dpi: u16,
+}
+
+impl Xft {
+    /// DPI to set instead of the DPI of the primary output.
+    pub fn dpi(&self) -> Option<u16> { (self.dpi > 0).then_some(self.dpi) }
+}